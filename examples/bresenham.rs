@@ -76,8 +76,8 @@ fn draw_interactive_grid(app: &App, draw: &Draw) {
     let window = app.main_window();
     let win = window.rect();
     // 100-step and 10-step grids.
-    draw_grid(&draw, &win, 100.0, 1.0);
-    draw_grid(&draw, &win, 25.0, 0.5);
+    draw_grid(draw, &win, 100.0, 1.0);
+    draw_grid(draw, &win, 25.0, 0.5);
     // Ellipse at mouse.
     draw.ellipse().wh([5.0; 2].into()).xy(app.mouse.position());
 
@@ -149,7 +149,7 @@ fn draw_interactive_grid(app: &App, draw: &Draw) {
 fn view(app: &App, m: &Model, frame: Frame) {
     let draw = app.draw();
     draw.background().rgb(0.11, 0.12, 0.13);
-    draw_interactive_grid(&app, &draw);
+    draw_interactive_grid(app, &draw);
 
     // Mouse position text.
     let mouse = app.mouse.position();
@@ -0,0 +1,184 @@
+/// The assignment problem: every worker (row) gets a different job (column)
+/// at the least total cost, solved by the Hungarian algorithm in O(n^2 m).
+/// Each worker in turn is added along the shortest augmenting path of reduced costs,
+/// and the potentials of the rows and columns keep every reduced cost non-negative.
+/// [https://en.wikipedia.org/wiki/Hungarian_algorithm]
+use crate::simplex::Number;
+use nalgebra as na;
+
+/// An optimal assignment
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assignment<T: Number> {
+    /// The total cost of the assignment
+    pub cost: T,
+    /// The job of each worker, `None` if there are more workers than jobs and this one has none
+    pub jobs: Vec<Option<usize>>,
+    /// The potential of each worker: u_i + v_j <= c_ij, with equality on every pair assigned.
+    /// Together with `v` they are the dual values of the assignment problem.
+    pub u: Vec<T>,
+    /// The potential of each job
+    pub v: Vec<T>,
+}
+
+/// Assigns the workers (rows) to the jobs (columns) at the least cost. Maximizing works
+/// with the costs negated. If the matrix isn't square, only as many pairs as the shorter
+/// side allows are made.
+pub fn hungarian<T: Number>(costs: &na::DMatrix<T>) -> Assignment<T> {
+    if costs.nrows() > costs.ncols() {
+        let transposed = hungarian(&costs.transpose());
+        let mut jobs = vec![None; costs.nrows()];
+        for (j, worker) in transposed.jobs.into_iter().enumerate() {
+            if let Some(i) = worker {
+                jobs[i] = Some(j);
+            }
+        }
+        return Assignment {
+            cost: transposed.cost,
+            jobs,
+            u: transposed.v,
+            v: transposed.u,
+        };
+    }
+    let (n, m) = costs.shape();
+    // Row 0 and column 0 are sentinels, column 0 holds the worker being added
+    let mut u = vec![T::zero(); n + 1];
+    let mut v = vec![T::zero(); m + 1];
+    let mut worker = vec![0; m + 1];
+    let mut way = vec![0; m + 1];
+    for i in 1..=n {
+        worker[0] = i;
+        let mut j0 = 0;
+        // The least reduced cost of reaching each column so far
+        let mut reach: Vec<Option<T>> = vec![None; m + 1];
+        let mut used = vec![false; m + 1];
+        loop {
+            used[j0] = true;
+            let i0 = worker[j0];
+            let mut delta: Option<T> = None;
+            let mut j1 = 0;
+            for j in (1..=m).filter(|&j| !used[j]) {
+                let reduced = costs[(i0 - 1, j - 1)].clone() - &u[i0] - &v[j];
+                if reach[j].as_ref().is_none_or(|least| reduced < *least) {
+                    reach[j] = Some(reduced);
+                    way[j] = j0;
+                }
+                if delta
+                    .as_ref()
+                    .is_none_or(|delta| reach[j].as_ref().unwrap() < delta)
+                {
+                    delta = reach[j].clone();
+                    j1 = j;
+                }
+            }
+            let delta = delta.expect("There are at least as many jobs as workers");
+            for j in 0..=m {
+                if used[j] {
+                    u[worker[j]] += &delta;
+                    v[j] -= &delta;
+                } else if let Some(least) = reach[j].as_mut() {
+                    *least -= &delta;
+                }
+            }
+            j0 = j1;
+            if worker[j0] == 0 {
+                break;
+            }
+        }
+        // Flip the assignments along the augmenting path
+        while j0 != 0 {
+            let j1 = way[j0];
+            worker[j0] = worker[j1];
+            j0 = j1;
+        }
+    }
+    let mut jobs = vec![None; n];
+    for (j, &i) in worker.iter().enumerate().skip(1) {
+        if i != 0 {
+            jobs[i - 1] = Some(j - 1);
+        }
+    }
+    let cost = (jobs.iter().enumerate())
+        .filter_map(|(i, job)| job.map(|j| costs[(i, j)].clone()))
+        .fold(T::zero(), |acc, c| acc + c);
+    Assignment {
+        cost,
+        jobs,
+        u: u.split_off(1),
+        v: v.split_off(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplex::Rational;
+    use crate::testing::bipartite;
+    use crate::transportation::{transportation, Start};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn check(res: &Assignment<f64>, costs: &na::DMatrix<f64>) {
+        let mut jobs: Vec<_> = res.jobs.iter().flatten().collect();
+        assert_eq!(jobs.len(), costs.nrows().min(costs.ncols()));
+        jobs.sort();
+        jobs.dedup();
+        assert_eq!(jobs.len(), costs.nrows().min(costs.ncols()));
+        for (i, job) in res.jobs.iter().enumerate() {
+            for j in 0..costs.ncols() {
+                let reduced = costs[(i, j)] - res.u[i] - res.v[j];
+                assert!(reduced >= -1E-9, "{:?}", res);
+                if *job == Some(j) {
+                    assert!(reduced.abs() < 1E-9, "{:?}", res);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn assignment_test_square() {
+        let costs = na::DMatrix::from_row_slice(
+            4,
+            4,
+            &[
+                82.0, 83.0, 69.0, 92.0, 77.0, 37.0, 49.0, 92.0, 11.0, 69.0, 5.0, 86.0, 8.0, 9.0,
+                98.0, 23.0,
+            ],
+        );
+        let res = hungarian(&costs);
+        assert_eq!(res.cost, 140.0);
+        assert_eq!(res.jobs, vec![Some(2), Some(1), Some(0), Some(3)]);
+        check(&res, &costs);
+        let ones = [1.0; 4];
+        assert!((bipartite(&ones, &ones, &costs) - 140.0).abs() < 1E-9);
+        // The potentials of a square problem are its dual solution
+        let dual: f64 = res.u.iter().chain(&res.v).sum();
+        assert!((dual - res.cost).abs() < 1E-9);
+        // An assignment is a transportation problem with unit supply and demand
+        let shipped = transportation(&ones, &ones, &costs, Start::Vogel);
+        assert!((shipped.cost - res.cost).abs() < 1E-9);
+    }
+
+    #[test]
+    fn assignment_test_rectangular_and_random() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for &(n, m) in &[(5, 5), (3, 6), (6, 3), (8, 8)] {
+            let costs = na::DMatrix::from_fn(n, m, |_, _| rng.gen_range(0, 100) as f64);
+            let res = hungarian(&costs);
+            check(&res, &costs);
+            let optimum = bipartite(&vec![1.0; n], &vec![1.0; m], &costs);
+            assert!((res.cost - optimum).abs() < 1E-6, "{} {}", costs, res.cost);
+            if n > m {
+                assert_eq!(res.jobs.iter().filter(|job| job.is_none()).count(), n - m);
+            }
+        }
+    }
+
+    #[test]
+    fn assignment_test_maximize_rational() {
+        // Negated profits
+        let r = |x: i64| Rational::from_integer(-x);
+        let profits = na::DMatrix::from_row_slice(3, 3, &[7, 5, 8, 6, 4, 2, 3, 9, 1].map(r));
+        let res = hungarian(&profits);
+        assert_eq!(res.jobs, vec![Some(2), Some(0), Some(1)]);
+        assert_eq!(res.cost, r(23));
+    }
+}
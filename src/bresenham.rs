@@ -16,9 +16,9 @@ impl From<[i32; 2]> for Point {
     }
 }
 
-impl Into<[i32; 2]> for Point {
-    fn into(self) -> [i32; 2] {
-        [self.x, self.y]
+impl From<Point> for [i32; 2] {
+    fn from(p: Point) -> Self {
+        [p.x, p.y]
     }
 }
 /// rasterize outputs an array of pixel coordinates between points p1 and p2.
@@ -3,7 +3,6 @@
 /// I'll be honest, this whole fourier thing still pretty much confuses me;
 /// gotta take a closer look someday on it.
 /// Sources: [https://betterexplained.com/articles/an-interactive-guide-to-the-fourier-transform/]
-
 pub const TAU: f32 = 6.283_185_5;
 pub struct Complex(f32, f32);
 
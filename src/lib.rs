@@ -2,6 +2,13 @@
 pub mod bresenham;
 pub mod img;
 // Else
+pub mod assignment;
 pub mod fourier;
 pub mod lstsq;
+pub mod milp;
+pub mod network;
 pub mod simplex;
+pub mod transportation;
+
+#[cfg(test)]
+mod testing;
@@ -100,7 +100,7 @@ fn plot_linear_regression(
     // Drawing our line segment
     chart
         .draw_series(LineSeries::new(
-            points.iter().map(|&(x, _)| (x as f64, x as f64 * m + b)),
+            points.iter().map(|&(x, _)| (x, x * m + b)),
             ShapeStyle {
                 color: GREEN.to_rgba(),
                 filled: false,
@@ -245,7 +245,11 @@ mod tests {
         let eps = 1E-6;
         assert!(
             (m - m2).abs() < eps && (b - b2).abs() < eps,
-            "least_squares_gen() and least_squares_ordinary() result with different outputs: {} {}",
+            "least_squares_gen() and least_squares_ordinary() result with different outputs: ({}, {}) ({}, {})",
+            m,
+            b,
+            m2,
+            b2
         );
 
         // Drawing data set points and the approximation function
@@ -0,0 +1,480 @@
+/// Mixed-integer linear programming with branch and bound: the simplex method solves
+/// the problem without the integrality restrictions, and every fractional value
+/// of an integer variable splits the problem in two, x <= floor(v) and x >= ceil(v).
+/// [https://en.wikipedia.org/wiki/Branch_and_bound]
+/// [https://en.wikipedia.org/wiki/Integer_programming]
+use crate::simplex::{num, LinearProgram, Number, Options, Status};
+
+/// Which fractional variable to branch on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Branching {
+    /// The one with the fractional part closest to 1/2
+    MostFractional,
+    /// The one with the smallest index
+    FirstIndex,
+}
+
+/// Which of the open subproblems to solve next
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NodeSelection {
+    /// The most recently created one, finds feasible solutions fast and keeps the queue short
+    DepthFirst,
+    /// The one with the best bound of the objective function, proves optimality in fewer nodes
+    BestBound,
+}
+
+/// The knobs of branch and bound
+pub struct MilpOptions<'a, T: Number> {
+    /// The options of the simplex method solving every relaxation
+    pub lp: Options<'a, T>,
+    pub branching: Branching,
+    pub node_selection: NodeSelection,
+    /// The search stops once the relative gap between the best solution
+    /// and the best bound falls to this value
+    pub gap: T,
+    /// Values this close to a whole number count as whole
+    pub integrality: T,
+    /// The number of relaxations the solver gives up after
+    pub max_nodes: usize,
+}
+
+impl<T: Number> Default for MilpOptions<'static, T> {
+    fn default() -> Self {
+        MilpOptions {
+            lp: Options::default(),
+            branching: Branching::MostFractional,
+            node_selection: NodeSelection::BestBound,
+            gap: T::zero(),
+            integrality: num(1E-5),
+            max_nodes: 10_000,
+        }
+    }
+}
+
+/// The result of branch and bound
+#[derive(Debug, Clone)]
+pub struct MilpSolution<T: Number> {
+    /// `Optimal` when the gap has been closed down to the requested one,
+    /// `IterationLimit` when the solver ran out of nodes
+    pub status: Status,
+    /// The value of the objective function at `x`
+    pub objective: T,
+    /// The best solution found, empty if there is none
+    pub x: Vec<T>,
+    /// No solution can be better than this value of the objective function.
+    /// `None` if nothing bounds it: the root relaxation hasn't been solved,
+    /// or the program is unbounded or infeasible.
+    pub bound: Option<T>,
+    /// The relative gap between `objective` and `bound`
+    pub gap: T,
+    /// The number of relaxations solved
+    pub nodes: usize,
+}
+
+/// A subproblem: the original program with tighter bounds of the variables
+struct Node<T: Number> {
+    lower: Vec<Option<T>>,
+    upper: Vec<Option<T>>,
+    /// The objective value of the parent's relaxation (maximized), no child does better.
+    /// `None` for the root, whose relaxation bounds nothing before it's solved.
+    bound: Option<T>,
+}
+
+/// Solves the program with every `integer` variable restricted to whole values
+pub fn branch_and_bound<T: Number>(
+    lp: &LinearProgram<T>,
+    options: &MilpOptions<T>,
+) -> MilpSolution<T> {
+    // The search maximizes, so the objective values are multiplied by the sign
+    let sign = lp.sign();
+    let mut relaxation = lp.clone();
+    let mut queue = vec![Node {
+        lower: lp.variables.iter().map(|v| v.lower.clone()).collect(),
+        upper: lp.variables.iter().map(|v| v.upper.clone()).collect(),
+        bound: None,
+    }];
+    let mut incumbent: Option<(T, Vec<T>)> = None;
+    let mut nodes = 0;
+
+    let status = loop {
+        let node = match select(&mut queue, options.node_selection) {
+            Some(node) => node,
+            None => break Status::Optimal,
+        };
+        if nodes > 0 {
+            if let Some((best, _)) = &incumbent {
+                let bound = node.bound.clone().and_then(|b| best_bound(&queue, b));
+                if bound.is_some_and(|bound| relative_gap(best, &bound) <= options.gap) {
+                    queue.push(node);
+                    break Status::Optimal;
+                }
+                if node.bound.as_ref().is_some_and(|bound| bound <= best) {
+                    continue;
+                }
+            }
+        }
+        if nodes == options.max_nodes {
+            queue.push(node);
+            break Status::IterationLimit;
+        }
+        nodes += 1;
+
+        for (j, var) in relaxation.variables.iter_mut().enumerate() {
+            var.lower = node.lower[j].clone();
+            var.upper = node.upper[j].clone();
+        }
+        let solution = relaxation.solve(&options.lp);
+        match solution.status {
+            Status::Optimal => {}
+            Status::Infeasible => continue,
+            // Without a finite relaxation at the root there is nothing to bound the search with
+            Status::Unbounded if nodes == 1 => break Status::Unbounded,
+            Status::Unbounded => continue,
+            Status::IterationLimit => {
+                queue.push(node);
+                break Status::IterationLimit;
+            }
+        }
+        let value = sign.clone() * &solution.objective;
+        if let Some((best, _)) = &incumbent {
+            if value <= *best {
+                continue;
+            }
+        }
+
+        match pick_branch(lp, &solution.x, options) {
+            None => {
+                // Whole values get rid of the noise of the floating point arithmetic
+                let mut x = solution.x;
+                for (v, var) in x.iter_mut().zip(&lp.variables) {
+                    if var.integer {
+                        *v = round(v);
+                    }
+                }
+                let value = sign.clone() * lp.objective_value(&x);
+                incumbent = Some((value, x));
+            }
+            Some(j) => {
+                let v = &solution.x[j];
+                let down_value = v.floor();
+                let mut down = Node {
+                    lower: node.lower.clone(),
+                    upper: node.upper.clone(),
+                    bound: Some(value.clone()),
+                };
+                down.upper[j] = Some(down_value.clone());
+                let mut up = node;
+                up.lower[j] = Some(down_value.clone() + T::one());
+                up.bound = Some(value);
+                // Depth-first search dives into the side the value is closer to
+                if v.clone() - down_value < num(0.5) {
+                    queue.push(up);
+                    queue.push(down);
+                } else {
+                    queue.push(down);
+                    queue.push(up);
+                }
+            }
+        }
+    };
+
+    let (best, x) = incumbent.unwrap_or_else(|| (T::zero(), Vec::new()));
+    let bound = match (status, x.is_empty()) {
+        (Status::Unbounded, _) | (Status::Optimal, true) => None,
+        (_, true) => (queue.first())
+            .and_then(|node| node.bound.clone())
+            .and_then(|bound| best_bound(&queue, bound)),
+        _ => best_bound(&queue, best.clone()),
+    };
+    let (status, gap) = match (status, x.is_empty()) {
+        // Every subproblem turned out infeasible
+        (Status::Optimal, true) => (Status::Infeasible, T::zero()),
+        (status, true) => (status, T::zero()),
+        (status, false) => {
+            // A solution comes from a relaxation, so the root and its children are solved
+            let bound = bound.as_ref().expect("The open nodes have bounds");
+            (status, relative_gap(&best, bound))
+        }
+    };
+    MilpSolution {
+        status,
+        objective: sign.clone() * best,
+        x,
+        bound: bound.map(|bound| sign * bound),
+        gap,
+        nodes,
+    }
+}
+
+/// Takes the next node off the queue
+fn select<T: Number>(queue: &mut Vec<Node<T>>, selection: NodeSelection) -> Option<Node<T>> {
+    match selection {
+        NodeSelection::DepthFirst => queue.pop(),
+        NodeSelection::BestBound => {
+            let mut best = 0;
+            for i in 1..queue.len() {
+                let better = match (&queue[i].bound, &queue[best].bound) {
+                    (None, Some(_)) => true,
+                    (Some(bound), Some(least)) => bound > least,
+                    _ => false,
+                };
+                if better {
+                    best = i;
+                }
+            }
+            if queue.is_empty() {
+                None
+            } else {
+                Some(queue.remove(best))
+            }
+        }
+    }
+}
+
+/// The largest of the given bound and the bounds of the open nodes,
+/// `None` if one of them isn't known yet
+fn best_bound<T: Number>(queue: &[Node<T>], bound: T) -> Option<T> {
+    queue.iter().try_fold(bound, |a, node| {
+        node.bound.clone().map(|b| if b > a { b } else { a })
+    })
+}
+
+/// The integer variable to branch on, `None` if all of them are whole
+fn pick_branch<T: Number>(
+    lp: &LinearProgram<T>,
+    x: &[T],
+    options: &MilpOptions<T>,
+) -> Option<usize> {
+    let half: T = num(0.5);
+    let fractional = (0..x.len())
+        .filter(|&j| lp.variables[j].integer)
+        .map(|j| (j, (x[j].clone() - round(&x[j])).abs()))
+        .filter(|(_, frac)| *frac > options.integrality);
+    match options.branching {
+        Branching::FirstIndex => fractional.map(|(j, _)| j).next(),
+        Branching::MostFractional => fractional
+            .fold(None, |best: Option<(usize, T)>, (j, frac)| {
+                let distance = (half.clone() - frac).abs();
+                match best {
+                    Some((_, ref d)) if *d <= distance => best,
+                    _ => Some((j, distance)),
+                }
+            })
+            .map(|(j, _)| j),
+    }
+}
+
+/// The closest whole number
+fn round<T: Number>(x: &T) -> T {
+    (x.clone() + num::<T>(0.5)).floor()
+}
+
+/// (bound - best) / |best|, where |best| is taken at least 1
+fn relative_gap<T: Number>(best: &T, bound: &T) -> T {
+    let scale = if best.abs() > T::one() {
+        best.abs()
+    } else {
+        T::one()
+    };
+    let gap = (bound.clone() - best) / scale;
+    if gap > T::zero() {
+        gap
+    } else {
+        T::zero()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplex::{Rational, Sense};
+
+    fn all_options() -> Vec<MilpOptions<'static, f64>> {
+        let mut options = Vec::new();
+        for &branching in &[Branching::MostFractional, Branching::FirstIndex] {
+            for &node_selection in &[NodeSelection::DepthFirst, NodeSelection::BestBound] {
+                options.push(MilpOptions {
+                    branching,
+                    node_selection,
+                    ..MilpOptions::default()
+                });
+            }
+        }
+        options
+    }
+
+    #[test]
+    fn milp_test_small() {
+        /*
+        Objective function = 5x + 4y
+        Constraints:
+        6x + 4y <= 24
+        x + 2y <= 6
+        x, y >= 0 and whole
+        */
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_integer_variable("x", 0.0, None);
+        let y = lp.add_integer_variable("y", 0.0, None);
+        lp.add_constraint("c1", &[(x, 6.0), (y, 4.0)], Sense::Le, 24.0);
+        lp.add_constraint("c2", &[(x, 1.0), (y, 2.0)], Sense::Le, 6.0);
+        lp.maximize(&[(x, 5.0), (y, 4.0)]);
+        // The relaxation reaches 21 at (3, 1.5)
+        assert!((lp.solve(&Options::default()).objective - 21.0).abs() < 1E-9);
+
+        for options in all_options() {
+            let res = branch_and_bound(&lp, &options);
+            assert_eq!(res.status, Status::Optimal);
+            assert_eq!(res.objective, 20.0);
+            assert_eq!(res.x, vec![4.0, 0.0]);
+            assert_eq!(res.gap, 0.0);
+        }
+    }
+
+    #[test]
+    fn milp_test_problem1() {
+        /*
+        Objective function = 20_000x + 45_000y + 85_000z
+        Constraints:
+        10x + 15y + 10z <= 720
+        13x + 5y + 5z <= 680
+        20x + 5y + 10z <= 550
+        z <= 7
+        x, y, z >= 0 and whole
+        */
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_integer_variable("x", 0.0, None);
+        let y = lp.add_integer_variable("y", 0.0, None);
+        let z = lp.add_integer_variable("z", 0.0, Some(7.0));
+        lp.add_constraint("c1", &[(x, 10.0), (y, 15.0), (z, 10.0)], Sense::Le, 720.0);
+        lp.add_constraint("c2", &[(x, 13.0), (y, 5.0), (z, 5.0)], Sense::Le, 680.0);
+        lp.add_constraint("c3", &[(x, 20.0), (y, 5.0), (z, 10.0)], Sense::Le, 550.0);
+        lp.maximize(&[(x, 20_000.0), (y, 45_000.0), (z, 85_000.0)]);
+
+        for options in all_options() {
+            let res = branch_and_bound(&lp, &options);
+            assert_eq!(res.status, Status::Optimal);
+            assert!(
+                (res.objective - 2_530_000.0).abs() < 1E-6,
+                "The expected optimal value: 2_530_000, the value we got: {}",
+                res.objective
+            );
+            assert_eq!(res.x, vec![0.0, 43.0, 7.0]);
+        }
+    }
+
+    #[test]
+    fn milp_test_knapsack() {
+        let values = [10.0, 13.0, 7.0, 8.0, 4.0];
+        let weights = [5.0, 7.0, 4.0, 3.0, 1.0];
+        let mut lp = LinearProgram::<Rational>::new();
+        let items: Vec<_> = (0..values.len())
+            .map(|i| lp.add_binary_variable(&format!("item{}", i)))
+            .collect();
+        let weight: Vec<_> = items
+            .iter()
+            .zip(&weights)
+            .map(|(&v, &w)| (v, num(w)))
+            .collect();
+        lp.add_constraint("capacity", &weight, Sense::Le, num(10.0));
+        let value: Vec<_> = items
+            .iter()
+            .zip(&values)
+            .map(|(&v, &c)| (v, num(c)))
+            .collect();
+        lp.maximize(&value);
+
+        let res = branch_and_bound(&lp, &MilpOptions::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert_eq!(res.objective, num(22.0));
+        let taken: Vec<_> = res.x.iter().map(|v| v.to_f64()).collect();
+        assert_eq!(taken, vec![1.0, 0.0, 0.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn milp_test_minimize_and_infeasible() {
+        /*
+        Objective function = x + y -> min
+        Constraints:
+        2x + 2y >= 3
+        x, y >= 0 and whole
+        */
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_integer_variable("x", 0.0, None);
+        let y = lp.add_integer_variable("y", 0.0, None);
+        lp.add_constraint("c1", &[(x, 2.0), (y, 2.0)], Sense::Ge, 3.0);
+        lp.minimize(&[(x, 1.0), (y, 1.0)]);
+        let res = branch_and_bound(&lp, &MilpOptions::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert_eq!(res.objective, 2.0);
+        assert_eq!(res.bound, Some(2.0));
+
+        // There is no whole x with 2x = 1
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_integer_variable("x", 0.0, Some(10.0));
+        lp.add_constraint("c1", &[(x, 2.0)], Sense::Eq, 1.0);
+        lp.maximize(&[(x, 1.0)]);
+        let res = branch_and_bound(&lp, &MilpOptions::default());
+        assert_eq!(res.status, Status::Infeasible);
+        assert!(res.x.is_empty());
+    }
+
+    #[test]
+    fn milp_test_gap_and_node_limit() {
+        let values = [12.0, 11.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0];
+        let weights = [7.0, 6.0, 5.0, 5.0, 4.0, 4.0, 3.0, 3.0];
+        let mut lp = LinearProgram::<f64>::new();
+        let items: Vec<_> = (0..values.len())
+            .map(|i| lp.add_binary_variable(&format!("item{}", i)))
+            .collect();
+        let weight: Vec<_> = items.iter().zip(&weights).map(|(&v, &w)| (v, w)).collect();
+        lp.add_constraint("capacity", &weight, Sense::Le, 17.0);
+        let value: Vec<_> = items.iter().zip(&values).map(|(&v, &c)| (v, c)).collect();
+        lp.maximize(&value);
+
+        let exact = branch_and_bound(&lp, &MilpOptions::default());
+        assert_eq!(exact.status, Status::Optimal);
+        assert_eq!(exact.gap, 0.0);
+
+        let options = MilpOptions {
+            gap: 0.2,
+            node_selection: NodeSelection::DepthFirst,
+            ..MilpOptions::default()
+        };
+        let rough = branch_and_bound(&lp, &options);
+        assert_eq!(rough.status, Status::Optimal);
+        assert!(rough.nodes <= exact.nodes);
+        assert!(rough.gap <= 0.2 && rough.bound.unwrap() >= exact.objective);
+        assert!(rough.objective >= exact.objective / 1.2);
+
+        let options = MilpOptions {
+            max_nodes: 1,
+            ..MilpOptions::default()
+        };
+        let res = branch_and_bound(&lp, &options);
+        assert_eq!(res.status, Status::IterationLimit);
+        assert_eq!(res.nodes, 1);
+        assert!(res.bound.unwrap() >= exact.objective);
+
+        // Nothing has been solved, so nothing is bounded
+        let options = MilpOptions {
+            max_nodes: 0,
+            ..MilpOptions::default()
+        };
+        let res = branch_and_bound(&lp, &options);
+        assert_eq!(res.status, Status::IterationLimit);
+        assert_eq!((res.nodes, res.bound), (0, None));
+        assert!(res.x.is_empty());
+
+        // The root relaxation runs out of pivots
+        let options = MilpOptions {
+            lp: Options {
+                max_iterations: 0,
+                ..Options::default()
+            },
+            ..MilpOptions::default()
+        };
+        let res = branch_and_bound(&lp, &options);
+        assert_eq!(res.status, Status::IterationLimit);
+        assert_eq!(res.bound, None);
+    }
+}
@@ -0,0 +1,684 @@
+/// Minimum-cost flow solved by the network simplex method: the basis of a network
+/// problem is a spanning tree of arcs, so a pivot only pushes flow around the cycle
+/// the entering arc closes in the tree, with no table at all.
+/// The tree starts from an artificial root joined to every node by an arc
+/// as expensive as any path (big M), and the pivots keep it strongly feasible
+/// (the leaving arc is the last one blocking the cycle), which rules out cycling.
+/// Max-flow and shortest paths are stated as minimum-cost flows on top of it.
+/// [https://en.wikipedia.org/wiki/Network_simplex_algorithm]
+use crate::simplex::{Number, Status};
+use std::collections::VecDeque;
+
+/// A handle of a node added to a `Network`, its index in `Flow::potentials`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Node(pub usize);
+
+/// A handle of an arc added to a `Network`, its index in `Flow::flows`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Arc(pub usize);
+
+#[derive(Debug, Clone)]
+pub struct ArcData<T: Number> {
+    pub from: Node,
+    pub to: Node,
+    /// The cost of a unit of flow along the arc
+    pub cost: T,
+    /// `None` if the arc takes any flow
+    pub capacity: Option<T>,
+}
+
+/// The nodes with their supplies and the arcs between them
+#[derive(Debug, Clone)]
+pub struct Network<T: Number> {
+    /// What each node puts into the network, negative for the demand it takes out
+    pub supplies: Vec<T>,
+    pub arcs: Vec<ArcData<T>>,
+}
+
+/// The result of the network simplex method
+#[derive(Debug, Clone)]
+pub struct Flow<T: Number> {
+    /// `Infeasible` when the supplies can't reach the demands (or don't balance),
+    /// `Unbounded` when a cycle of negative cost takes any flow
+    pub status: Status,
+    /// The total cost of the flow
+    pub cost: T,
+    /// The flow along each arc
+    pub flows: Vec<T>,
+    /// The potential of each node: an arc with flow below its capacity costs at least
+    /// the drop of potential along it, and one with flow above zero at most that
+    pub potentials: Vec<T>,
+    /// The number of pivots it took
+    pub pivots: usize,
+}
+
+impl<T: Number> Flow<T> {
+    /// The flow along the arc
+    pub fn flow(&self, arc: Arc) -> T {
+        self.flows[arc.0].clone()
+    }
+}
+
+impl<T: Number> Default for Network<T> {
+    fn default() -> Self {
+        Network {
+            supplies: Vec::new(),
+            arcs: Vec::new(),
+        }
+    }
+}
+
+impl<T: Number> Network<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a node putting `supply` into the network, or taking it out if negative
+    pub fn add_node(&mut self, supply: T) -> Node {
+        self.supplies.push(supply);
+        Node(self.supplies.len() - 1)
+    }
+
+    pub fn set_supply(&mut self, node: Node, supply: T) {
+        self.supplies[node.0] = supply;
+    }
+
+    /// Adds an arc taking up to `capacity` units (any amount if `None`) at `cost` each
+    pub fn add_arc(&mut self, from: Node, to: Node, cost: T, capacity: Option<T>) -> Arc {
+        self.arcs.push(ArcData {
+            from,
+            to,
+            cost,
+            capacity,
+        });
+        Arc(self.arcs.len() - 1)
+    }
+
+    /// Sends the supplies to the demands at the least cost
+    pub fn solve(&self) -> Flow<T> {
+        let total = self.supplies.iter().fold(T::zero(), |acc, b| acc + b);
+        let mut tree = Tree::new(self);
+        let status = if total.abs() > T::eps() {
+            Status::Infeasible
+        } else {
+            tree.optimize()
+        };
+        let m = self.arcs.len();
+        let flows = tree.flows[..m].to_vec();
+        let cost = (self.arcs.iter().zip(&flows))
+            .fold(T::zero(), |acc, (arc, x)| acc + arc.cost.clone() * x);
+        let artificial = tree.flows[m..].iter().any(|x| *x > T::eps());
+        Flow {
+            status: match status {
+                Status::Optimal if artificial => Status::Infeasible,
+                status => status,
+            },
+            cost,
+            flows,
+            potentials: tree.potentials[..self.supplies.len()].to_vec(),
+            pivots: tree.pivots,
+        }
+    }
+}
+
+/// Where a non-tree arc rests
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Tree,
+    Lower,
+    Upper,
+}
+
+/// The spanning tree of a basic flow. The nodes of the network are followed by the root,
+/// the arcs by one artificial arc per node joining it to the root.
+struct Tree<T: Number> {
+    from: Vec<usize>,
+    to: Vec<usize>,
+    cost: Vec<T>,
+    capacity: Vec<Option<T>>,
+    flows: Vec<T>,
+    state: Vec<State>,
+    /// The tree arc towards the root of every node
+    parent: Vec<Option<usize>>,
+    depth: Vec<usize>,
+    potentials: Vec<T>,
+    pivots: usize,
+}
+
+impl<T: Number> Tree<T> {
+    fn new(network: &Network<T>) -> Self {
+        let n = network.supplies.len();
+        let root = n;
+        let big = (network.arcs.iter()).fold(T::one(), |acc, arc| acc + arc.cost.abs());
+        let mut tree = Tree {
+            from: network.arcs.iter().map(|arc| arc.from.0).collect(),
+            to: network.arcs.iter().map(|arc| arc.to.0).collect(),
+            cost: network.arcs.iter().map(|arc| arc.cost.clone()).collect(),
+            capacity: network
+                .arcs
+                .iter()
+                .map(|arc| arc.capacity.clone())
+                .collect(),
+            flows: vec![T::zero(); network.arcs.len()],
+            state: vec![State::Lower; network.arcs.len()],
+            parent: vec![None; n + 1],
+            depth: vec![0; n + 1],
+            potentials: vec![T::zero(); n + 1],
+            pivots: 0,
+        };
+        // Supplies go up to the root and demands come down from it,
+        // arcs with no flow point to the root
+        for (i, b) in network.supplies.iter().enumerate() {
+            let (from, to) = if b.is_negative() {
+                (root, i)
+            } else {
+                (i, root)
+            };
+            tree.from.push(from);
+            tree.to.push(to);
+            tree.cost.push(big.clone());
+            tree.capacity.push(None);
+            tree.flows.push(b.abs());
+            tree.state.push(State::Tree);
+        }
+        tree.rebuild();
+        tree
+    }
+
+    /// Finds the parent arcs, depths and potentials from the tree arcs, walking from the root
+    fn rebuild(&mut self) {
+        let root = self.parent.len() - 1;
+        let mut adjacent = vec![Vec::new(); root + 1];
+        for (k, _) in self
+            .state
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| **s == State::Tree)
+        {
+            adjacent[self.from[k]].push(k);
+            adjacent[self.to[k]].push(k);
+        }
+        self.parent[root] = None;
+        self.depth[root] = 0;
+        self.potentials[root] = T::zero();
+        let mut queue = VecDeque::new();
+        queue.push_back(root);
+        while let Some(node) = queue.pop_front() {
+            for &k in &adjacent[node] {
+                if self.parent[node] == Some(k) {
+                    continue;
+                }
+                // The reduced cost c_k - p_from + p_to of a tree arc is zero
+                let child = if self.from[k] == node {
+                    self.potentials[self.to[k]] = self.potentials[node].clone() - &self.cost[k];
+                    self.to[k]
+                } else {
+                    self.potentials[self.from[k]] = self.potentials[node].clone() + &self.cost[k];
+                    self.from[k]
+                };
+                self.parent[child] = Some(k);
+                self.depth[child] = self.depth[node] + 1;
+                queue.push_back(child);
+            }
+        }
+    }
+
+    fn reduced_cost(&self, k: usize) -> T {
+        self.cost[k].clone() - &self.potentials[self.from[k]] + &self.potentials[self.to[k]]
+    }
+
+    /// How much more flow the arc takes when crossed forwards, or gives back when crossed
+    /// backwards. `None` stands for no limit.
+    fn residual(&self, k: usize, forwards: bool) -> Option<T> {
+        match (forwards, &self.capacity[k]) {
+            (true, Some(capacity)) => Some(capacity.clone() - &self.flows[k]),
+            (true, None) => None,
+            (false, _) => Some(self.flows[k].clone()),
+        }
+    }
+
+    fn optimize(&mut self) -> Status {
+        loop {
+            // The arc violating its optimality condition the most
+            let mut entering: Option<(T, usize)> = None;
+            for k in 0..self.state.len() {
+                let violation = match self.state[k] {
+                    State::Lower => -self.reduced_cost(k),
+                    State::Upper => self.reduced_cost(k),
+                    State::Tree => continue,
+                };
+                let most = entering.as_ref().map_or(T::eps(), |(most, _)| most.clone());
+                if violation > most {
+                    entering = Some((violation, k));
+                }
+            }
+            let entering = match entering {
+                Some((_, k)) => k,
+                None => return Status::Optimal,
+            };
+            if !self.pivot(entering) {
+                return Status::Unbounded;
+            }
+            self.pivots += 1;
+        }
+    }
+
+    /// Pushes flow around the cycle of the entering arc. Returns false if nothing limits it.
+    fn pivot(&mut self, entering: usize) -> bool {
+        // The flow goes from `first` to `second` along the entering arc
+        let (first, second) = match self.state[entering] {
+            State::Lower => (self.from[entering], self.to[entering]),
+            _ => (self.to[entering], self.from[entering]),
+        };
+        // The paths up to the apex, where they meet, as (arc, crossed forwards)
+        let (mut a, mut b) = (first, second);
+        let (mut down, mut up) = (Vec::new(), Vec::new());
+        while a != b {
+            if self.depth[a] >= self.depth[b] {
+                let k = self.parent[a].unwrap();
+                // The flow goes down from the apex to `first`
+                down.push((k, self.to[k] == a));
+                a = self.other(k, a);
+            } else {
+                let k = self.parent[b].unwrap();
+                // and up from `second` to the apex
+                up.push((k, self.from[k] == b));
+                b = self.other(k, b);
+            }
+        }
+        // The cycle in the direction of the flow, starting from the apex
+        let mut cycle: Vec<(usize, bool)> = down.into_iter().rev().collect();
+        cycle.push((entering, self.state[entering] == State::Lower));
+        cycle.extend(up);
+        let residuals: Vec<Option<T>> = (cycle.iter())
+            .map(|&(k, forwards)| self.residual(k, forwards))
+            .collect();
+        let amount = match residuals
+            .iter()
+            .flatten()
+            .min_by(|a, b| a.partial_cmp(b).unwrap())
+        {
+            Some(amount) => amount.clone(),
+            None => return false,
+        };
+        // The last arc to block the flow keeps the tree strongly feasible
+        let blocking = (residuals
+            .iter()
+            .rposition(|r| r.as_ref().is_some_and(|r| r.clone() - &amount <= T::eps())))
+        .unwrap();
+        for &(k, forwards) in &cycle {
+            if forwards {
+                self.flows[k] += &amount;
+            } else {
+                self.flows[k] -= &amount;
+            }
+        }
+        let (leaving, forwards) = cycle[blocking];
+        // Flows at a bound are set to it exactly, to keep float errors from piling up
+        self.state[leaving] = if forwards {
+            self.flows[leaving] = self.capacity[leaving].clone().unwrap();
+            State::Upper
+        } else {
+            self.flows[leaving] = T::zero();
+            State::Lower
+        };
+        if leaving != entering {
+            self.state[entering] = State::Tree;
+            self.rebuild();
+        }
+        true
+    }
+
+    /// The node at the other end of the arc
+    fn other(&self, k: usize, node: usize) -> usize {
+        if self.from[k] == node {
+            self.to[k]
+        } else {
+            self.from[k]
+        }
+    }
+}
+
+/// The largest flow from the source to the sink, with the cut limiting it
+#[derive(Debug, Clone)]
+pub struct MaxFlow<T: Number> {
+    /// `Unbounded` when a path of arcs without capacities joins the source to the sink
+    pub status: Status,
+    /// The amount of flow reaching the sink
+    pub value: T,
+    /// The flow along each arc
+    pub flows: Vec<T>,
+    /// The nodes on the side of the source of a minimum cut: the arcs leaving them
+    /// are full and their capacities add up to `value`
+    pub cut: Vec<Node>,
+}
+
+/// Sends as much flow as the capacities allow from the source to the sink, ignoring
+/// the costs and supplies of the network. An arc back from the sink to the source
+/// rewards every unit of flow around, which the minimum-cost flow makes the most of.
+pub fn max_flow<T: Number>(network: &Network<T>, source: Node, sink: Node) -> MaxFlow<T> {
+    let mut circulation = Network::new();
+    for _ in &network.supplies {
+        circulation.add_node(T::zero());
+    }
+    for arc in &network.arcs {
+        circulation.add_arc(arc.from, arc.to, T::zero(), arc.capacity.clone());
+    }
+    let back = circulation.add_arc(sink, source, -T::one(), None);
+    let flow = circulation.solve();
+    let mut flows = flow.flows;
+    let value = flows.remove(back.0);
+    // The nodes the source still reaches through arcs with room for more flow
+    let mut reached = vec![false; network.supplies.len()];
+    reached[source.0] = true;
+    let mut queue = VecDeque::new();
+    queue.push_back(source.0);
+    while let Some(node) = queue.pop_front() {
+        for (arc, x) in network.arcs.iter().zip(&flows) {
+            let room = |c: &T| c.clone() - x.clone() > T::eps();
+            let next = if arc.from.0 == node && arc.capacity.as_ref().is_none_or(room) {
+                arc.to.0
+            } else if arc.to.0 == node && *x > T::eps() {
+                arc.from.0
+            } else {
+                continue;
+            };
+            if !reached[next] {
+                reached[next] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+    MaxFlow {
+        status: flow.status,
+        value,
+        flows,
+        cut: (0..reached.len())
+            .filter(|&i| reached[i])
+            .map(Node)
+            .collect(),
+    }
+}
+
+/// The shortest paths from a node to every other one
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<T: Number> {
+    /// `Unbounded` when a cycle of negative length can be reached
+    pub status: Status,
+    /// The length of the shortest path to each node, `None` if there is no path to it
+    pub distances: Vec<Option<T>>,
+    /// The last arc of the shortest path to each node
+    pub predecessors: Vec<Option<Arc>>,
+}
+
+impl<T: Number> ShortestPaths<T> {
+    /// The arcs of the shortest path to the node, from the source on
+    pub fn path(&self, network: &Network<T>, node: Node) -> Option<Vec<Arc>> {
+        self.distances[node.0].as_ref()?;
+        let mut path = Vec::new();
+        let mut node = node;
+        while let Some(arc) = self.predecessors[node.0] {
+            path.push(arc);
+            node = network.arcs[arc.0].from;
+            assert!(
+                path.len() < self.predecessors.len(),
+                "The paths form a tree"
+            );
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Finds the shortest paths from the source, the costs of the arcs being their lengths
+/// (of any sign) and their capacities ignored. Every node the source reaches takes
+/// a unit of flow out of the network, which follows its shortest path.
+pub fn shortest_paths<T: Number>(network: &Network<T>, source: Node) -> ShortestPaths<T> {
+    let n = network.supplies.len();
+    let mut reached = vec![false; n];
+    reached[source.0] = true;
+    let mut queue = VecDeque::new();
+    queue.push_back(source.0);
+    while let Some(node) = queue.pop_front() {
+        for arc in network.arcs.iter().filter(|arc| arc.from.0 == node) {
+            if !reached[arc.to.0] {
+                reached[arc.to.0] = true;
+                queue.push_back(arc.to.0);
+            }
+        }
+    }
+    let mut paths = Network::new();
+    let n_reached = reached.iter().filter(|&&r| r).count();
+    for (i, &r) in reached.iter().enumerate() {
+        paths.add_node(match r {
+            _ if i == source.0 => T::from_f64((n_reached - 1) as f64),
+            true => -T::one(),
+            false => T::zero(),
+        });
+    }
+    // The arcs out of the nodes the source doesn't reach can't be on any of its paths
+    let kept: Vec<usize> = (0..network.arcs.len())
+        .filter(|&k| reached[network.arcs[k].from.0])
+        .collect();
+    for &k in &kept {
+        let arc = &network.arcs[k];
+        paths.add_arc(arc.from, arc.to, arc.cost.clone(), None);
+    }
+    let flow = paths.solve();
+    let mut predecessors = vec![None; n];
+    for (&k, f) in kept.iter().zip(&flow.flows) {
+        let arc = &network.arcs[k];
+        if *f > T::eps() && arc.to != source {
+            predecessors[arc.to.0] = Some(Arc(k));
+        }
+    }
+    let distances = (0..n)
+        .map(|i| match reached[i] && flow.status == Status::Optimal {
+            true => Some(flow.potentials[source.0].clone() - &flow.potentials[i]),
+            false => None,
+        })
+        .collect();
+    ShortestPaths {
+        status: flow.status,
+        distances,
+        predecessors,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplex::Rational;
+    use crate::testing::linear_program;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    /// The flow keeps within the capacities, balances the supplies and the potentials prove it optimal
+    fn check(network: &Network<f64>, flow: &Flow<f64>) {
+        let mut balance = network.supplies.clone();
+        for (arc, x) in network.arcs.iter().zip(&flow.flows) {
+            assert!(*x >= 0.0 && arc.capacity.is_none_or(|c| *x <= c + 1E-9));
+            balance[arc.from.0] -= x;
+            balance[arc.to.0] += x;
+            let reduced = arc.cost - flow.potentials[arc.from.0] + flow.potentials[arc.to.0];
+            if *x > 1E-9 {
+                assert!(reduced <= 1E-9, "{:?}", flow);
+            }
+            if arc.capacity.is_none_or(|c| *x < c - 1E-9) {
+                assert!(reduced >= -1E-9, "{:?}", flow);
+            }
+        }
+        assert!(balance.iter().all(|b| b.abs() < 1E-9));
+    }
+
+    #[test]
+    fn network_test_min_cost_flow() {
+        /*
+        Two plants (supplies 20 and 10) ship through a hub to two markets (demands 15 each),
+        the direct road from the first plant to the first market takes at most 5
+        */
+        let mut network = Network::new();
+        let plant1 = network.add_node(20.0);
+        let plant2 = network.add_node(10.0);
+        let hub = network.add_node(0.0);
+        let market1 = network.add_node(-15.0);
+        let market2 = network.add_node(-15.0);
+        let direct = network.add_arc(plant1, market1, 1.0, Some(5.0));
+        network.add_arc(plant1, hub, 2.0, None);
+        network.add_arc(plant2, hub, 3.0, Some(8.0));
+        network.add_arc(plant2, market2, 6.0, None);
+        network.add_arc(hub, market1, 2.0, None);
+        network.add_arc(hub, market2, 1.0, Some(12.0));
+        let flow = network.solve();
+        assert_eq!(flow.status, Status::Optimal);
+        assert_eq!(flow.flow(direct), 5.0);
+        check(&network, &flow);
+        let (status, cost) = linear_program(&network);
+        assert_eq!(status, Status::Optimal);
+        assert!((flow.cost - cost).abs() < 1E-9, "{} {}", flow.cost, cost);
+
+        // The demand can't be met through the capacities
+        network.arcs[direct.0].capacity = Some(1.0);
+        network.arcs[1].capacity = Some(1.0);
+        assert_eq!(network.solve().status, Status::Infeasible);
+        assert_eq!(linear_program(&network).0, Status::Infeasible);
+        // Nor can unbalanced supplies
+        network.set_supply(market2, -16.0);
+        assert_eq!(network.solve().status, Status::Infeasible);
+    }
+
+    #[test]
+    fn network_test_random_against_simplex() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for _ in 0..20 {
+            let n = rng.gen_range(6, 11);
+            let mut network = Network::new();
+            let nodes: Vec<_> = (0..n).map(|_| network.add_node(0.0)).collect();
+            for _ in 0..3 * n {
+                let (from, to) = (rng.gen_range(0, n), rng.gen_range(0, n));
+                if from == to {
+                    continue;
+                }
+                let cost = rng.gen_range(0, 20) as f64;
+                let capacity = Some(rng.gen_range(0, 15) as f64).filter(|&c| c > 2.0);
+                network.add_arc(nodes[from], nodes[to], cost, capacity);
+            }
+            for _ in 0..4 {
+                let amount = rng.gen_range(0, 10) as f64;
+                let (from, to) = (rng.gen_range(0, n), rng.gen_range(0, n));
+                network.supplies[from] += amount;
+                network.supplies[to] -= amount;
+            }
+            let flow = network.solve();
+            let (status, cost) = linear_program(&network);
+            assert_eq!(flow.status, status);
+            if status == Status::Optimal {
+                check(&network, &flow);
+                assert!((flow.cost - cost).abs() < 1E-6, "{} {}", flow.cost, cost);
+            }
+        }
+    }
+
+    #[test]
+    fn network_test_max_flow() {
+        // Cormen et al., Introduction to Algorithms, figure 26.1
+        let mut network = Network::new();
+        let nodes: Vec<_> = (0..6)
+            .map(|_| network.add_node(Rational::from_integer(0)))
+            .collect();
+        let arcs = [
+            (0, 1, 16),
+            (0, 2, 13),
+            (1, 3, 12),
+            (2, 1, 4),
+            (2, 4, 14),
+            (3, 2, 9),
+            (3, 5, 20),
+            (4, 3, 7),
+            (4, 5, 4),
+        ];
+        for &(from, to, capacity) in &arcs {
+            let capacity = Rational::from_integer(capacity);
+            network.add_arc(
+                nodes[from],
+                nodes[to],
+                Rational::from_integer(1),
+                Some(capacity),
+            );
+        }
+        let res = max_flow(&network, nodes[0], nodes[5]);
+        assert_eq!(res.status, Status::Optimal);
+        assert_eq!(res.value, Rational::from_integer(23));
+        assert_eq!(res.cut, vec![Node(0), Node(1), Node(2), Node(4)]);
+        let across = (network.arcs.iter())
+            .filter(|arc| res.cut.contains(&arc.from) && !res.cut.contains(&arc.to))
+            .fold(Rational::from_integer(0), |acc, arc| {
+                acc + arc.capacity.unwrap()
+            });
+        assert_eq!(across, res.value);
+
+        network.arcs[6].capacity = None;
+        network.arcs[0].capacity = None;
+        network.arcs[2].capacity = None;
+        assert_eq!(
+            max_flow(&network, nodes[0], nodes[5]).status,
+            Status::Unbounded
+        );
+
+        // The same network in floating point, with capacities in tenths
+        let mut network = Network::new();
+        let nodes: Vec<_> = (0..6).map(|_| network.add_node(0.0)).collect();
+        for &(from, to, capacity) in &arcs {
+            network.add_arc(nodes[from], nodes[to], 1.0, Some(capacity as f64 / 10.0));
+        }
+        let res = max_flow(&network, nodes[0], nodes[5]);
+        assert!((res.value - 2.3).abs() < 1E-9);
+        assert_eq!(res.cut, vec![Node(0), Node(1), Node(2), Node(4)]);
+    }
+
+    #[test]
+    fn network_test_shortest_paths() {
+        let mut network = Network::new();
+        let nodes: Vec<_> = (0..6).map(|_| network.add_node(0.0)).collect();
+        let arcs = [
+            (0, 1, 4.0),
+            (0, 2, 1.0),
+            (2, 1, 2.0),
+            (1, 3, 1.0),
+            (2, 3, 5.0),
+            (3, 4, 3.0),
+            (4, 1, -2.0),
+            (5, 0, 1.0),
+        ];
+        for &(from, to, length) in &arcs {
+            network.add_arc(nodes[from], nodes[to], length, Some(1.0));
+        }
+        let res = shortest_paths(&network, nodes[0]);
+        assert_eq!(res.status, Status::Optimal);
+        let expected = vec![Some(0.0), Some(3.0), Some(1.0), Some(4.0), Some(7.0), None];
+        assert_eq!(res.distances, expected);
+        assert_eq!(
+            res.path(&network, nodes[4]),
+            Some(vec![Arc(1), Arc(2), Arc(3), Arc(5)])
+        );
+        assert_eq!(res.path(&network, nodes[0]), Some(vec![]));
+        assert_eq!(res.path(&network, nodes[5]), None);
+
+        // A cycle of negative length
+        network.arcs[6].cost = -5.0;
+        assert_eq!(shortest_paths(&network, nodes[0]).status, Status::Unbounded);
+    }
+
+    #[test]
+    fn network_test_shortest_paths_unreachable_cycle() {
+        // The cycle of negative length between the last two nodes is out of the source's reach
+        let mut network = Network::new();
+        let nodes: Vec<_> = (0..4).map(|_| network.add_node(0.0)).collect();
+        network.add_arc(nodes[0], nodes[1], 1.0, None);
+        network.add_arc(nodes[2], nodes[3], -3.0, None);
+        network.add_arc(nodes[3], nodes[2], 1.0, None);
+        let res = shortest_paths(&network, nodes[0]);
+        assert_eq!(res.status, Status::Optimal);
+        assert_eq!(res.distances, vec![Some(0.0), Some(1.0), None, None]);
+        assert_eq!(res.path(&network, nodes[1]), Some(vec![Arc(0)]));
+    }
+}
@@ -0,0 +1,348 @@
+/// Column generation for programs with too many variables to list: the restricted master
+/// problem holds a few of the columns, and a pricing oracle looks for a column
+/// with a reduced cost c_j - y^T a_j that improves the objective under the duals y
+/// of the master. The new columns join the master, which is solved again,
+/// until the oracle finds none: the solution of the master solves the whole program then.
+/// The reference oracle prices the patterns of the cutting-stock problem with a knapsack.
+/// [https://en.wikipedia.org/wiki/Column_generation]
+/// [https://en.wikipedia.org/wiki/Cutting_stock_problem]
+use super::{
+    Constraint, Direction, LinearProgram, Number, Options, Sense, Solution, Status, Variable,
+};
+
+/// A candidate column of the master: its coefficient in the objective function
+/// and its non-zero coefficients in the constraints
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column<T: Number> {
+    pub name: String,
+    pub cost: T,
+    pub terms: Vec<(Constraint, T)>,
+}
+
+impl<T: Number> Column<T> {
+    /// c_j - y^T a_j under the duals of the constraints
+    pub fn reduced_cost(&self, duals: &[T]) -> T {
+        (self.terms.iter()).fold(self.cost.clone(), |acc, (c, a)| {
+            acc - duals[c.0].clone() * a
+        })
+    }
+}
+
+/// Finds columns missing from the master
+pub trait PricingOracle<T: Number> {
+    /// The columns worth adding under the duals of the constraints of the master
+    /// (as in `Solution::duals`), e.g. the one with the best reduced cost.
+    /// Columns that don't improve the objective are ignored, none at all ends the loop.
+    fn price(&mut self, duals: &[T]) -> Vec<Column<T>>;
+}
+
+/// The knobs of column generation
+pub struct ColumnOptions<'a, T: Number> {
+    /// The options of the simplex method solving the master
+    pub lp: Options<'a, T>,
+    /// The number of rounds of new columns the loop stops after
+    pub max_rounds: usize,
+    /// Columns have to improve the objective by more than this per unit
+    pub tolerance: T,
+}
+
+impl<T: Number> Default for ColumnOptions<'static, T> {
+    fn default() -> Self {
+        ColumnOptions {
+            lp: Options::default(),
+            max_rounds: 1000,
+            tolerance: T::eps(),
+        }
+    }
+}
+
+/// The result of column generation
+#[derive(Debug, Clone)]
+pub struct ColumnGeneration<T: Number> {
+    /// The master with the columns added
+    pub program: LinearProgram<T>,
+    /// The solution of the last master
+    pub solution: Solution<T>,
+    /// The variables of the columns added, in order
+    pub columns: Vec<Variable>,
+    /// The number of rounds of columns added
+    pub rounds: usize,
+    /// Whether the oracle found no improving column, so `solution` solves the whole program
+    pub converged: bool,
+}
+
+/// Solves the master again and again, adding the improving columns of the oracle,
+/// until there are none or the rounds run out. The master has to be feasible
+/// with its own columns.
+pub fn column_generation<T: Number>(
+    master: &LinearProgram<T>,
+    oracle: &mut dyn PricingOracle<T>,
+    options: &ColumnOptions<T>,
+) -> ColumnGeneration<T> {
+    let mut program = master.clone();
+    let sign = program.sign();
+    let mut columns = Vec::new();
+    let mut rounds = 0;
+    let (solution, converged) = loop {
+        let solution = program.solve(&options.lp);
+        if solution.status != Status::Optimal {
+            break (solution, false);
+        }
+        let mut improving = oracle.price(&solution.duals);
+        improving.retain(|col| col.reduced_cost(&solution.duals) * &sign > options.tolerance);
+        if improving.is_empty() {
+            break (solution, true);
+        }
+        if rounds == options.max_rounds {
+            break (solution, false);
+        }
+        for col in improving {
+            columns.push(program.add_column(&col.name, col.cost, &col.terms));
+        }
+        rounds += 1;
+    };
+    ColumnGeneration {
+        program,
+        solution,
+        columns,
+        rounds,
+        converged,
+    }
+}
+
+/// Cutting rolls of paper of one width into pieces of smaller widths, using the fewest
+/// rolls. A column is a pattern: how many pieces of each width one roll is cut into.
+/// The pricing is the unbounded knapsack of the pieces, each worth its dual.
+#[derive(Debug, Clone)]
+pub struct CuttingStock<T: Number> {
+    pub roll: usize,
+    pub widths: Vec<usize>,
+    /// The number of pieces of each width needed
+    pub demands: Vec<T>,
+    /// The number of patterns named so far
+    patterns: usize,
+}
+
+impl<T: Number> CuttingStock<T> {
+    pub fn new(roll: usize, widths: &[usize], demands: &[T]) -> Self {
+        assert_eq!(widths.len(), demands.len(), "One demand per width");
+        assert!(
+            widths.iter().all(|&w| w > 0 && w <= roll),
+            "The pieces have to fit into the roll"
+        );
+        CuttingStock {
+            roll,
+            widths: widths.to_vec(),
+            demands: demands.to_vec(),
+            patterns: 0,
+        }
+    }
+
+    /// The master minimizing the rolls used, with a constraint `demand<i>` per width
+    /// and a pattern per width cutting as many pieces of it as fit
+    pub fn master(&mut self) -> LinearProgram<T> {
+        let mut lp = LinearProgram::new();
+        lp.direction = Direction::Minimize;
+        for (i, demand) in self.demands.iter().enumerate() {
+            let name = format!("demand{}", i + 1);
+            lp.add_constraint(&name, &[], Sense::Ge, demand.clone());
+        }
+        for i in 0..self.widths.len() {
+            let mut pieces = vec![0; self.widths.len()];
+            pieces[i] = self.roll / self.widths[i];
+            let col = self.column(&pieces);
+            lp.add_column(&col.name, col.cost, &col.terms);
+        }
+        lp
+    }
+
+    /// The number of pieces of each width a column of the master cuts
+    pub fn pattern(lp: &LinearProgram<T>, var: Variable) -> Vec<T> {
+        (lp.constraints.iter())
+            .map(|c| {
+                (c.terms.iter())
+                    .find(|(v, _)| *v == var)
+                    .map_or(T::zero(), |(_, a)| a.clone())
+            })
+            .collect()
+    }
+
+    fn column(&mut self, pieces: &[usize]) -> Column<T> {
+        self.patterns += 1;
+        let terms = (pieces.iter().enumerate())
+            .filter(|(_, &count)| count > 0)
+            .map(|(i, &count)| (Constraint(i), T::from_f64(count as f64)))
+            .collect();
+        Column {
+            name: format!("pattern{}", self.patterns),
+            cost: T::one(),
+            terms,
+        }
+    }
+}
+
+impl<T: Number> PricingOracle<T> for CuttingStock<T> {
+    /// The pattern worth the most under the duals of the demands, which come first in the
+    /// master as `master` builds it, by dynamic programming over the widths
+    fn price(&mut self, duals: &[T]) -> Vec<Column<T>> {
+        // The best worth of the pieces fitting into each width, and the last piece cut
+        let mut best = vec![T::zero(); self.roll + 1];
+        let mut last: Vec<Option<usize>> = vec![None; self.roll + 1];
+        for width in 1..=self.roll {
+            best[width] = best[width - 1].clone();
+            last[width] = None;
+            for (i, &w) in self.widths.iter().enumerate().filter(|(_, &w)| w <= width) {
+                let worth = best[width - w].clone() + &duals[i];
+                if worth > best[width] {
+                    best[width] = worth;
+                    last[width] = Some(i);
+                }
+            }
+        }
+        let mut pieces = vec![0; self.widths.len()];
+        let mut width = self.roll;
+        while width > 0 {
+            match last[width] {
+                Some(i) => {
+                    pieces[i] += 1;
+                    width -= self.widths[i];
+                }
+                None => width -= 1,
+            }
+        }
+        if pieces.iter().all(|&count| count == 0) {
+            return Vec::new();
+        }
+        vec![self.column(&pieces)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplex::Rational;
+
+    /// Every pattern with at least one piece, listed up front
+    fn all_patterns(roll: usize, widths: &[usize]) -> Vec<Vec<usize>> {
+        let mut patterns = vec![vec![]];
+        for &w in widths {
+            patterns = (patterns.into_iter())
+                .flat_map(|pattern: Vec<usize>| {
+                    let used: usize = pattern.iter().zip(widths).map(|(n, w)| n * w).sum();
+                    (0..=(roll - used) / w).map(move |n| [pattern.clone(), vec![n]].concat())
+                })
+                .collect();
+        }
+        patterns.retain(|pattern| pattern.iter().any(|&n| n > 0));
+        patterns
+    }
+
+    #[test]
+    fn column_generation_test_cutting_stock() {
+        // Chvatal, Linear Programming, chapter 13
+        let (roll, widths) = (100, [45, 36, 31, 14]);
+        let demands: [f64; 4] = [97.0, 610.0, 395.0, 211.0];
+        let mut stock = CuttingStock::new(roll, &widths, &demands);
+        let master = stock.master();
+        assert_eq!(master.variables.len(), 4);
+        let res = column_generation(&master, &mut stock, &ColumnOptions::default());
+        assert!(res.converged);
+        assert_eq!(res.solution.status, Status::Optimal);
+        assert!(res.rounds > 0 && res.columns.len() == res.rounds);
+        assert!((res.solution.objective - 452.25).abs() < 1E-9);
+        for &var in &res.columns {
+            let pattern = CuttingStock::pattern(&res.program, var);
+            let used: f64 = pattern
+                .iter()
+                .zip(&widths)
+                .map(|(n, &w)| n * w as f64)
+                .sum();
+            assert!(used <= roll as f64);
+        }
+
+        // The same program with all of the patterns
+        let mut full = LinearProgram::new();
+        full.direction = Direction::Minimize;
+        for (i, &d) in demands.iter().enumerate() {
+            full.add_constraint(&format!("demand{}", i + 1), &[], Sense::Ge, d);
+        }
+        let patterns = all_patterns(roll, &widths);
+        for (k, pattern) in patterns.iter().enumerate() {
+            let terms: Vec<_> = (pattern.iter().enumerate())
+                .map(|(i, &n)| (Constraint(i), n as f64))
+                .collect();
+            full.add_column(&format!("p{}", k), 1.0, &terms);
+        }
+        let solution = full.solve(&Options::default());
+        assert!((solution.objective - res.solution.objective).abs() < 1E-9);
+        assert!(res.program.variables.len() < patterns.len());
+    }
+
+    #[test]
+    fn column_generation_test_rational_and_rounds() {
+        let r = |n: i64| Rational::from_integer(n);
+        let demands = [r(44), r(3), r(48)];
+        let mut stock = CuttingStock::new(218, &[81, 70, 68], &demands);
+        let master = stock.master();
+        let res = column_generation(&master, &mut stock, &ColumnOptions::default());
+        assert!(res.converged);
+        // Every pattern is worth at most a roll under the final duals
+        let duals = &res.solution.duals;
+        for pattern in all_patterns(218, &[81, 70, 68]) {
+            let worth =
+                (pattern.iter().zip(duals)).fold(r(0), |acc, (&n, y)| acc + y * r(n as i64));
+            assert!(worth <= r(1));
+        }
+
+        let mut stock = CuttingStock::new(218, &[81, 70, 68], &demands);
+        let master = stock.master();
+        let options = ColumnOptions {
+            max_rounds: 0,
+            ..ColumnOptions::default()
+        };
+        let res = column_generation(&master, &mut stock, &options);
+        assert!(!res.converged && res.columns.is_empty());
+    }
+
+    /// An oracle with a fixed list of columns of a maximization
+    struct Listed(Vec<Column<f64>>);
+
+    impl PricingOracle<f64> for Listed {
+        fn price(&mut self, duals: &[f64]) -> Vec<Column<f64>> {
+            let mut columns = self.0.clone();
+            columns.sort_by(|a, b| {
+                b.reduced_cost(duals)
+                    .partial_cmp(&a.reduced_cost(duals))
+                    .unwrap()
+            });
+            columns.truncate(1);
+            columns
+        }
+    }
+
+    #[test]
+    fn column_generation_test_maximize() {
+        // 7x + 5y -> max, 2x + 3y <= 90, 3x + 2y <= 120, the columns x and y generated
+        let mut master = LinearProgram::new();
+        master.direction = Direction::Maximize;
+        let c1 = master.add_constraint("c1", &[], Sense::Le, 90.0);
+        let c2 = master.add_constraint("c2", &[], Sense::Le, 120.0);
+        let mut oracle = Listed(vec![
+            Column {
+                name: "x".to_string(),
+                cost: 7.0,
+                terms: vec![(c1, 2.0), (c2, 3.0)],
+            },
+            Column {
+                name: "y".to_string(),
+                cost: 5.0,
+                terms: vec![(c1, 3.0), (c2, 2.0)],
+            },
+        ]);
+        let res = column_generation(&master, &mut oracle, &ColumnOptions::default());
+        assert!(res.converged);
+        assert_eq!(res.rounds, 2);
+        assert!((res.solution.objective - 282.0).abs() < 1E-9);
+        assert!((res.solution.value_of(&res.program, "y").unwrap() - 6.0).abs() < 1E-9);
+    }
+}
@@ -0,0 +1,428 @@
+/// The dual simplex method and warm starts. After a requirement or a bound changes,
+/// or a constraint is added, the last optimal basis still prices every column right
+/// (it is dual feasible), only some basic variables may be out of their bounds.
+/// The dual simplex method drives them back one pivot at a time, which usually takes
+/// far fewer pivots than solving the problem from scratch.
+/// [https://en.wikipedia.org/wiki/Dual_simplex_method]
+use super::observer::{Initialization, Phase, Termination};
+use super::revised::{at_bound, logical_bounds, Outcome, Revised};
+use super::sensitivity::Interval;
+use super::sparse::SparseMatrix;
+use super::{Number, Options, Sense, Solution};
+
+impl<T: Number> Revised<T> {
+    /// Pivots until every basic variable is within its bounds. The basic variable
+    /// furthest out of them leaves, the entering one keeps the reduced costs optimal.
+    pub(super) fn run_dual(
+        &mut self,
+        cost: &[T],
+        options: &Options<T>,
+        iterations: &mut usize,
+    ) -> Outcome<T> {
+        self.farkas = None;
+        if self.crossed() {
+            return Outcome::Infeasible;
+        }
+        loop {
+            // The position, the bound the variable leaves at and the way it has to move
+            let mut leaving: Option<(usize, T, T, T)> = None;
+            for (p, &j) in self.basis.iter().enumerate() {
+                let below = (self.lower[j].as_ref())
+                    .filter(|&l| self.x[j] < l.clone() - T::eps())
+                    .map(|l| (l.clone() - &self.x[j], l.clone(), T::one()));
+                let above = (self.upper[j].as_ref())
+                    .filter(|&u| self.x[j] > u.clone() + T::eps())
+                    .map(|u| (self.x[j].clone() - u, u.clone(), -T::one()));
+                if let Some((gap, bound, way)) = below.or(above) {
+                    if leaving.as_ref().is_none_or(|(_, _, _, best)| gap > *best) {
+                        leaving = Some((p, bound, way, gap));
+                    }
+                }
+            }
+            let (p, bound, way) = match leaving {
+                Some((p, bound, way, _)) => (p, bound, way),
+                None => return Outcome::Optimal,
+            };
+            if *iterations >= options.max_iterations {
+                return Outcome::IterationLimit;
+            }
+            *iterations += 1;
+
+            // The row of the leaving variable: x_B[p] changes by -row_j per unit of x_j
+            let mut unit = vec![T::zero(); self.m()];
+            unit[p] = T::one();
+            let row = self.lu.btran(unit);
+            let y = self.prices(cost);
+            // The ratio test on the reduced costs, the ties go to the largest pivot
+            let mut entering: Option<(usize, T, T, T)> = None;
+            for (j, c) in cost.iter().enumerate() {
+                if self.position[j].is_some() || self.is_fixed(j) {
+                    continue;
+                }
+                let a = self.dot_column(j, &row);
+                if a.abs() <= T::eps() {
+                    continue;
+                }
+                let dir = if (-a.clone() * &way).is_positive() {
+                    T::one()
+                } else {
+                    -T::one()
+                };
+                let can_move = if dir.is_positive() {
+                    self.upper[j].as_ref().is_none_or(|u| self.x[j] < *u)
+                } else {
+                    self.lower[j].as_ref().is_none_or(|l| self.x[j] > *l)
+                };
+                if !can_move {
+                    continue;
+                }
+                let d = c.clone() - self.dot_column(j, &y);
+                let ratio = d.abs() / a.abs();
+                let better = match &entering {
+                    None => true,
+                    Some((_, _, best, pivot)) if (ratio.clone() - best).abs() <= T::eps() => {
+                        a.abs() > *pivot
+                    }
+                    Some((_, _, best, _)) => ratio < *best,
+                };
+                if better {
+                    entering = Some((j, dir, ratio, a.abs()));
+                }
+            }
+            // Nothing can bring the leaving variable back, so its row can't be satisfied:
+            // the row itself (turned the way the variable has to move) proves it
+            let (q, dir) = match entering {
+                Some((q, dir, _, _)) => (q, dir),
+                None => {
+                    self.farkas = Some(row.into_iter().map(|r| r * &way).collect());
+                    return Outcome::Infeasible;
+                }
+            };
+            let alpha = self.lu.ftran(self.dense_column(q));
+            let j = self.basis[p];
+            let step = (self.x[j].clone() - &bound) / (dir.clone() * &alpha[p]);
+            self.pivot(q, p, &alpha, &dir, &step, bound, options);
+            self.report(Phase::Dual, *iterations, q, Some(j), cost, options);
+        }
+    }
+}
+
+/// A problem of `revised_simplex` kept along with the basis of its last solution.
+/// Adding constraints and changing requirements or bounds keep the basis,
+/// so solving the problem again starts off with the dual simplex method from there.
+#[derive(Debug, Clone)]
+pub struct WarmStart<T: Number> {
+    revised: Revised<T>,
+    obj: Vec<T>,
+    /// Whether the basis is the one of the last solution
+    warm: bool,
+    /// The pivots the last solution took
+    iterations: usize,
+}
+
+impl<T: Number> WarmStart<T> {
+    /// Takes the problem of `revised_simplex`, which is solved by the first call to `solve`
+    pub fn new(
+        constr: SparseMatrix<T>,
+        senses: Vec<Sense>,
+        req: Vec<T>,
+        obj: Vec<T>,
+        bounds: &[Interval<T>],
+    ) -> Self {
+        assert_eq!(constr.ncols(), obj.len(), "One coefficient per variable");
+        WarmStart {
+            revised: Revised::new(constr, senses, req, bounds),
+            obj,
+            warm: false,
+            iterations: 0,
+        }
+    }
+
+    /// Solves the problem, starting from the last basis if there was a solution before.
+    /// The dual simplex method restores the bounds, the primal one finishes off
+    /// in case the basis wasn't optimal for the objective function.
+    pub fn solve(&mut self, options: &Options<T>) -> Solution<T> {
+        self.iterations = 0;
+        options.observer.initialized(&Initialization {
+            method: if self.warm {
+                "dual simplex"
+            } else {
+                "revised simplex"
+            },
+            rows: self.revised.m(),
+            columns: self.revised.n(),
+        });
+        let outcome = if self.warm {
+            let cost = self.revised.cost(&self.obj);
+            match self.revised.run_dual(&cost, options, &mut self.iterations) {
+                Outcome::Optimal => {
+                    let iterations = &mut self.iterations;
+                    self.revised.run(&cost, Phase::Two, options, iterations)
+                }
+                outcome => outcome,
+            }
+        } else {
+            self.revised = self.fresh();
+            self.revised
+                .optimize(&self.obj, options, &mut self.iterations)
+        };
+        // An infeasible Phase I leaves the artificial variables behind, so it starts over
+        let (n, m) = (self.revised.n(), self.revised.m());
+        self.warm = match outcome {
+            Outcome::Optimal | Outcome::Unbounded(..) => true,
+            _ => self.warm && self.revised.x[n + m..].iter().all(|a| a.is_zero()),
+        };
+        let solution = self.revised.solution(outcome, &self.obj);
+        options.observer.terminated(&Termination {
+            status: solution.status,
+            objective: solution.objective.clone(),
+            iterations: self.iterations,
+        });
+        solution
+    }
+
+    /// The number of pivots the last call to `solve` took
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Adds the constraint with the given (variable, coefficient) terms,
+    /// returns its index. Its slack variable joins the basis.
+    pub fn add_row(&mut self, terms: &[(usize, T)], sense: Sense, rhs: T) -> usize {
+        let revised = &mut self.revised;
+        let (n, m) = (revised.n(), revised.m());
+        revised.constr.push_row(terms);
+        revised.senses.push(sense);
+        revised.req.push(rhs);
+        // The new slack variable goes after the others, which moves the artificial ones
+        let (lower, upper) = logical_bounds(sense);
+        revised.lower.insert(n + m, lower);
+        revised.upper.insert(n + m, upper);
+        revised.x.insert(n + m, T::zero());
+        revised.lower.push(Some(T::zero()));
+        revised.upper.push(Some(T::zero()));
+        revised.x.push(T::zero());
+        revised.artificial.push(T::zero());
+        for j in revised.basis.iter_mut().filter(|j| **j >= n + m) {
+            *j += 1;
+        }
+        revised.basis.push(n + m);
+        revised.refactor();
+        m
+    }
+
+    /// Changes the requirement of the constraint
+    pub fn set_rhs(&mut self, row: usize, rhs: T) {
+        self.revised.req[row] = rhs;
+        self.revised.refactor();
+    }
+
+    /// Changes the bounds of the variable, a non-basic one stays at the same side if it can
+    pub fn set_bounds(&mut self, var: usize, lower: Option<T>, upper: Option<T>) {
+        let revised = &mut self.revised;
+        if revised.position[var].is_none() {
+            let at_upper = (revised.upper[var].as_ref()).is_some_and(|u| revised.x[var] == *u)
+                && (revised.lower[var].as_ref()).is_none_or(|l| revised.x[var] != *l);
+            revised.x[var] = match (&lower, &upper) {
+                (_, Some(upper)) if at_upper => upper.clone(),
+                _ => at_bound(&lower, &upper),
+            };
+        }
+        revised.lower[var] = lower;
+        revised.upper[var] = upper;
+        revised.refactor();
+    }
+
+    /// The problem as it is now, ready to be solved from scratch
+    fn fresh(&self) -> Revised<T> {
+        let revised = &self.revised;
+        let n = revised.n();
+        let bounds: Vec<_> = (revised.lower.iter().zip(&revised.upper))
+            .take(n)
+            .map(|(lower, upper)| (lower.clone(), upper.clone()))
+            .collect();
+        Revised::new(
+            revised.constr.clone(),
+            revised.senses.clone(),
+            revised.req.clone(),
+            &bounds,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{revised_simplex, Status};
+    use super::*;
+
+    fn problem2() -> WarmStart<f64> {
+        /*
+        Objective function = 7x + 5y
+        Constraints:
+        2x + 3y <= 90
+        3x + 2y <= 120
+        x, y >= 0
+        */
+        let constraints = SparseMatrix::from_triplets(
+            2,
+            2,
+            &[(0, 0, 2.0), (0, 1, 3.0), (1, 0, 3.0), (1, 1, 2.0)],
+        );
+        let bounds = vec![(Some(0.0), None); 2];
+        WarmStart::new(
+            constraints,
+            vec![Sense::Le; 2],
+            vec![90.0, 120.0],
+            vec![7.0, 5.0],
+            &bounds,
+        )
+    }
+
+    #[test]
+    fn dual_test_add_cut() {
+        let mut warm = problem2();
+        let res = warm.solve(&Options::default());
+        assert!((res.objective - 282.0).abs() < 1E-9, "{}", res.objective);
+
+        // The cut x <= 30 moves the optimum to (30, 10)
+        assert_eq!(warm.add_row(&[(0, 1.0)], Sense::Le, 30.0), 2);
+        let res = warm.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.objective - 260.0).abs() < 1E-9, "{}", res.objective);
+        assert!((res.x[0] - 30.0).abs() < 1E-9 && (res.x[1] - 10.0).abs() < 1E-9);
+        assert_eq!(res.slack.len(), 3);
+        assert_eq!(warm.iterations(), 1);
+
+        // The same as from scratch
+        let constraints = SparseMatrix::from_triplets(
+            3,
+            2,
+            &[
+                (0, 0, 2.0),
+                (0, 1, 3.0),
+                (1, 0, 3.0),
+                (1, 1, 2.0),
+                (2, 0, 1.0),
+            ],
+        );
+        let scratch = revised_simplex(
+            constraints,
+            vec![Sense::Le; 3],
+            vec![90.0, 120.0, 30.0],
+            vec![7.0, 5.0],
+            &[(Some(0.0), None); 2],
+            &Options::default(),
+        );
+        assert!((scratch.objective - res.objective).abs() < 1E-9);
+        for (a, b) in scratch.duals.iter().zip(&res.duals) {
+            assert!((a - b).abs() < 1E-9, "{:?} {:?}", scratch.duals, res.duals);
+        }
+
+        // A cut of the other sense: x + y >= 45 can't hold along with the rest
+        warm.add_row(&[(0, 1.0), (1, 1.0)], Sense::Ge, 45.0);
+        assert_eq!(warm.solve(&Options::default()).status, Status::Infeasible);
+    }
+
+    #[test]
+    fn dual_test_rhs_and_bounds() {
+        let mut warm = problem2();
+        warm.solve(&Options::default());
+
+        // Less of the first resource: 2x + 3y <= 60 gives (30, 0)
+        warm.set_rhs(0, 60.0);
+        let res = warm.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.objective - 210.0).abs() < 1E-9, "{}", res.objective);
+
+        // y >= 5 leaves 45 of the first resource to x
+        warm.set_bounds(1, Some(5.0), None);
+        let res = warm.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.x[0] - 22.5).abs() < 1E-9 && (res.x[1] - 5.0).abs() < 1E-9);
+        assert!((res.objective - 182.5).abs() < 1E-9, "{}", res.objective);
+
+        // y >= 25 needs more than 60 of the first resource, until it's given back
+        warm.set_bounds(1, Some(25.0), None);
+        assert_eq!(warm.solve(&Options::default()).status, Status::Infeasible);
+        warm.set_rhs(0, 90.0);
+        let res = warm.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.x[0] - 7.5).abs() < 1E-9 && (res.x[1] - 25.0).abs() < 1E-9);
+
+        // An upper bound that cuts the optimum off
+        warm.set_bounds(1, Some(0.0), Some(4.0));
+        let res = warm.solve(&Options::default());
+        let scratch = revised_simplex(
+            SparseMatrix::from_triplets(
+                2,
+                2,
+                &[(0, 0, 2.0), (0, 1, 3.0), (1, 0, 3.0), (1, 1, 2.0)],
+            ),
+            vec![Sense::Le; 2],
+            vec![90.0, 120.0],
+            vec![7.0, 5.0],
+            &[(Some(0.0), None), (Some(0.0), Some(4.0))],
+            &Options::default(),
+        );
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.objective - scratch.objective).abs() < 1E-9);
+    }
+
+    #[test]
+    fn dual_test_crossed_bounds() {
+        let mut warm = problem2();
+        warm.solve(&Options::default());
+        warm.set_bounds(0, Some(5.0), Some(2.0));
+        assert_eq!(warm.solve(&Options::default()).status, Status::Infeasible);
+
+        // Uncrossing them brings the optimum back
+        warm.set_bounds(0, Some(0.0), None);
+        let res = warm.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.objective - 282.0).abs() < 1E-9, "{}", res.objective);
+    }
+
+    #[test]
+    fn dual_test_equality_rows() {
+        /*
+        Objective function = -x - y - z
+        Constraints:
+        x + y + z = 10
+        x - y >= 2
+        x, y, z >= 0
+        */
+        let constraints = SparseMatrix::from_triplets(
+            2,
+            3,
+            &[
+                (0, 0, 1.0),
+                (0, 1, 1.0),
+                (0, 2, 1.0),
+                (1, 0, 1.0),
+                (1, 1, -1.0),
+            ],
+        );
+        let mut warm = WarmStart::<f64>::new(
+            constraints,
+            vec![Sense::Eq, Sense::Ge],
+            vec![10.0, 2.0],
+            vec![-1.0, -1.0, -1.0],
+            &[(Some(0.0), None); 3],
+        );
+        let res = warm.solve(&Options::default());
+        assert!((res.objective + 10.0).abs() < 1E-9);
+        // z <= 1 and x - y >= 2 leave x + y = 9 with x >= 5.5
+        warm.set_bounds(2, Some(0.0), Some(1.0));
+        warm.add_row(&[(0, 1.0)], Sense::Le, 5.0);
+        assert_eq!(warm.solve(&Options::default()).status, Status::Infeasible);
+        warm.set_rhs(2, 6.0);
+        let res = warm.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.objective + 10.0).abs() < 1E-9);
+        assert!(
+            res.x[0] >= 5.5 - 1E-9 && res.x[2] <= 1.0 + 1E-9,
+            "{:?}",
+            res.x
+        );
+    }
+}
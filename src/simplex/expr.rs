@@ -0,0 +1,390 @@
+/// Linear expressions over the variables of a `LinearProgram`, and the `lp!` macro
+/// stating a whole program the way it's written on paper:
+/// `lp! { [maximize 7*x + 5*y] [2*x + 3*y <= 90] [3*x + 2*y <= 120] }`.
+use super::{Constraint, Direction, LinearProgram, Number, Sense, Variable};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A sum of variables times coefficients plus a constant
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearExpr<T: Number> {
+    pub terms: Vec<(Variable, T)>,
+    pub constant: T,
+}
+
+impl<T: Number> LinearExpr<T> {
+    /// The expression with no variables
+    pub fn constant(value: T) -> Self {
+        LinearExpr {
+            terms: Vec::new(),
+            constant: value,
+        }
+    }
+
+    fn scale(mut self, factor: &T) -> Self {
+        for (_, coef) in self.terms.iter_mut() {
+            *coef *= factor;
+        }
+        self.constant *= factor;
+        self
+    }
+}
+
+impl<T: Number> From<T> for LinearExpr<T> {
+    fn from(value: T) -> Self {
+        LinearExpr::constant(value)
+    }
+}
+
+impl<T: Number> From<Variable> for LinearExpr<T> {
+    fn from(var: Variable) -> Self {
+        LinearExpr {
+            terms: vec![(var, T::one())],
+            constant: T::zero(),
+        }
+    }
+}
+
+impl<T: Number> Add for LinearExpr<T> {
+    type Output = Self;
+    fn add(mut self, other: Self) -> Self {
+        for (var, coef) in other.terms {
+            match self.terms.iter_mut().find(|(v, _)| *v == var) {
+                Some((_, c)) => *c += coef,
+                None => self.terms.push((var, coef)),
+            }
+        }
+        self.constant += other.constant;
+        self
+    }
+}
+
+impl<T: Number> Neg for LinearExpr<T> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.scale(&-T::one())
+    }
+}
+
+impl<T: Number> Sub for LinearExpr<T> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+/// One of the factors has to be a constant, the product of two variables isn't linear
+impl<T: Number> Mul for LinearExpr<T> {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        match (self.terms.is_empty(), other.terms.is_empty()) {
+            (true, _) => other.scale(&self.constant),
+            (_, true) => self.scale(&other.constant),
+            _ => panic!("The product of two variables isn't linear"),
+        }
+    }
+}
+
+/// Only division by a constant is linear
+impl<T: Number> Div for LinearExpr<T> {
+    type Output = Self;
+    fn div(self, other: Self) -> Self {
+        assert!(
+            other.terms.is_empty(),
+            "Division by a variable isn't linear"
+        );
+        self.scale(&(T::one() / other.constant))
+    }
+}
+
+impl<T: Number> LinearProgram<T> {
+    /// The variable called `name`, added with the bounds [0, infinity) if there is none yet
+    pub fn variable_named(&mut self, name: &str) -> Variable {
+        match self.variable(name) {
+            Some(var) => var,
+            None => self.add_variable(name, T::zero(), None),
+        }
+    }
+
+    /// Adds the constraint `lhs <= rhs` (or `>=`, `=`), the constants moving to the right
+    pub fn add_expr_constraint(
+        &mut self,
+        name: &str,
+        lhs: LinearExpr<T>,
+        sense: Sense,
+        rhs: LinearExpr<T>,
+    ) -> Constraint {
+        let expr = lhs - rhs;
+        self.add_constraint(name, &expr.terms, sense, -expr.constant)
+    }
+
+    /// Sets the objective function, which can't have a constant
+    pub fn set_objective_expr(&mut self, direction: Direction, expr: LinearExpr<T>) {
+        assert!(
+            expr.constant.is_zero(),
+            "Constants in the objective function are not supported"
+        );
+        match direction {
+            Direction::Maximize => self.maximize(&expr.terms),
+            Direction::Minimize => self.minimize(&expr.terms),
+        }
+    }
+}
+
+/// States a `LinearProgram<f64>`, or one of any other number with `type Rational;` first.
+/// Each statement goes in brackets:
+/// - `[maximize <expression>]` or `[minimize <expression>]`,
+/// - `[<expression> <= <expression>]` (or `>=`, `==`), optionally named `[name: ...]`,
+///   otherwise called `R<number of the constraint>` as in the LP format,
+/// - `[integer x, y]`, `[binary b]` and `[free z]` declare the kind of variables.
+///
+/// Any other name in an expression is a variable, created with the bounds [0, infinity)
+/// when it first turns up. Numbers are written as they are, while `{...}` takes
+/// the value of a Rust expression (a number of the program or a `Variable`).
+/// Each token of a statement is a step of macro expansion, so only very long statements
+/// may need a higher `#![recursion_limit]`, however many of them there are.
+#[macro_export]
+macro_rules! lp {
+    (type $t:ty; $([$($stmt:tt)*])*) => {{
+        let mut program = $crate::simplex::LinearProgram::<$t>::new();
+        $(
+            $crate::lp!(@apply program $($stmt)*);
+        )*
+        program
+    }};
+    (@apply $m:ident) => {};
+    (@apply $m:ident maximize $($e:tt)+) => {
+        let objective = $crate::lp!(@expr $m [] $($e)+);
+        $m.set_objective_expr($crate::simplex::Direction::Maximize, objective);
+    };
+    (@apply $m:ident minimize $($e:tt)+) => {
+        let objective = $crate::lp!(@expr $m [] $($e)+);
+        $m.set_objective_expr($crate::simplex::Direction::Minimize, objective);
+    };
+    (@apply $m:ident integer $($v:ident),+) => {
+        $(
+            let var = $m.variable_named(stringify!($v));
+            $m.set_integer(var, true);
+        )+
+    };
+    (@apply $m:ident binary $($v:ident),+) => {
+        $(
+            let var = $m.variable_named(stringify!($v));
+            $m.set_integer(var, true);
+            $m.set_bounds(var, Some($crate::simplex::num(0.0)), Some($crate::simplex::num(1.0)));
+        )+
+    };
+    (@apply $m:ident free $($v:ident),+) => {
+        $(
+            let var = $m.variable_named(stringify!($v));
+            $m.set_bounds(var, None, None);
+        )+
+    };
+    (@apply $m:ident $name:ident : $($c:tt)+) => {
+        $crate::lp!(@split $m [stringify!($name)] [] $($c)+);
+    };
+    (@apply $m:ident $($c:tt)+) => {
+        let name = format!("R{}", $m.constraints.len() + 1);
+        $crate::lp!(@split $m [&name] [] $($c)+);
+    };
+    // Splits a constraint at its comparison
+    (@split $m:ident [$name:expr] [$($lhs:tt)+] <= $($rhs:tt)+) => {
+        $crate::lp!(@constraint $m [$name] [$($lhs)+] Le [$($rhs)+]);
+    };
+    (@split $m:ident [$name:expr] [$($lhs:tt)+] >= $($rhs:tt)+) => {
+        $crate::lp!(@constraint $m [$name] [$($lhs)+] Ge [$($rhs)+]);
+    };
+    (@split $m:ident [$name:expr] [$($lhs:tt)+] == $($rhs:tt)+) => {
+        $crate::lp!(@constraint $m [$name] [$($lhs)+] Eq [$($rhs)+]);
+    };
+    (@split $m:ident [$name:expr] [$($lhs:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::lp!(@split $m [$name] [$($lhs)* $t] $($rest)*);
+    };
+    (@split $m:ident [$name:expr] [$($lhs:tt)*]) => {
+        compile_error!("A constraint needs one of <=, >= and ==")
+    };
+    (@constraint $m:ident [$name:expr] [$($lhs:tt)+] $sense:ident [$($rhs:tt)+]) => {
+        let lhs = $crate::lp!(@expr $m [] $($lhs)+);
+        let rhs = $crate::lp!(@expr $m [] $($rhs)+);
+        $m.add_expr_constraint($name, lhs, $crate::simplex::Sense::$sense, rhs);
+    };
+    // Turns every operand of an expression into a `LinearExpr`
+    (@expr $m:ident [$($acc:tt)*]) => { $($acc)* };
+    (@expr $m:ident [$($acc:tt)*] - $($rest:tt)*) => {
+        $crate::lp!(@expr $m [$($acc)* -] $($rest)*)
+    };
+    (@expr $m:ident [$($acc:tt)*] ($($inner:tt)+) $($rest:tt)*) => {
+        $crate::lp!(@expr $m [$($acc)* ($crate::lp!(@expr $m [] $($inner)+))] $($rest)*)
+    };
+    (@expr $m:ident [$($acc:tt)*] {$e:expr} $($rest:tt)*) => {
+        $crate::lp!(@expr $m [$($acc)* ($crate::simplex::LinearExpr::from($e))] $($rest)*)
+    };
+    (@expr $m:ident [$($acc:tt)*] $v:ident $($rest:tt)*) => {
+        $crate::lp!(@expr $m [$($acc)* (
+            $crate::simplex::LinearExpr::from($m.variable_named(stringify!($v)))
+        )] $($rest)*)
+    };
+    (@expr $m:ident [$($acc:tt)*] $l:literal $($rest:tt)*) => {
+        $crate::lp!(@expr $m [$($acc)* (
+            $crate::simplex::LinearExpr::constant($crate::simplex::num($l as f64))
+        )] $($rest)*)
+    };
+    (@expr $m:ident [$($acc:tt)*] $op:tt $($rest:tt)*) => {
+        $crate::lp!(@expr $m [$($acc)* $op] $($rest)*)
+    };
+    ($([$($stmt:tt)*])*) => {
+        $crate::lp!(type f64; $([$($stmt)*])*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::milp::{branch_and_bound, MilpOptions};
+    use crate::simplex::{Options, Rational, Status};
+
+    #[test]
+    fn expr_test_macro_matches_manual() {
+        let lp = lp! {
+            [maximize 7*x + 5*y]
+            [2*x + 3*y <= 90]
+            [3*x + 2*y <= 120]
+        };
+        let mut manual = LinearProgram::<f64>::new();
+        let x = manual.add_variable("x", 0.0, None);
+        let y = manual.add_variable("y", 0.0, None);
+        manual.add_constraint("R1", &[(x, 2.0), (y, 3.0)], Sense::Le, 90.0);
+        manual.add_constraint("R2", &[(x, 3.0), (y, 2.0)], Sense::Le, 120.0);
+        manual.maximize(&[(x, 7.0), (y, 5.0)]);
+        assert_eq!(format!("{:?}", lp), format!("{:?}", manual));
+
+        let res = lp.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.objective - 282.0).abs() < 1E-9);
+        assert!((res.value_of(&lp, "x").unwrap() - 36.0).abs() < 1E-9);
+        assert!((res.value_of(&lp, "y").unwrap() - 6.0).abs() < 1E-9);
+        assert_eq!(res.value_of(&lp, "z"), None);
+        let names: Vec<_> = res.named_values(&lp).into_iter().map(|(n, _)| n).collect();
+        assert_eq!(names, vec!["x", "y"]);
+    }
+
+    #[test]
+    fn expr_test_expressions() {
+        // Constants move to the right, variables to the left, coefficients add up
+        let limit = 4.0;
+        let lp = lp! {
+            [minimize 2*x + 3*y]
+            [demand: x + y - 1 >= 3]
+            [x + 3*y >= 6 - (x - x)]
+            [cap: 2*(x + 1) / 2 <= {limit} + 2 - 1 + x - x]
+            [-y == -y + 0*x]
+        };
+        let demand = &lp.constraints[0];
+        assert_eq!(demand.name, "demand");
+        assert_eq!(demand.rhs, 4.0);
+        let second = &lp.constraints[1];
+        assert_eq!(second.name, "R2");
+        assert_eq!(second.terms.iter().find(|(v, _)| v.0 == 0).unwrap().1, 1.0);
+        assert_eq!(lp.constraints[2].name, "cap");
+        assert_eq!(lp.constraints[2].sense, Sense::Le);
+        assert_eq!(lp.constraints[2].rhs, 4.0);
+        assert_eq!(lp.constraints[3].sense, Sense::Eq);
+
+        let res = lp.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.objective - 9.0).abs() < 1E-9);
+        assert!((res.value_of(&lp, "x").unwrap() - 3.0).abs() < 1E-9);
+
+        let x = lp.variable("x").unwrap();
+        let e: LinearExpr<f64> = LinearExpr::from(x) * LinearExpr::from(3.0) - 1.0.into();
+        assert_eq!(e.terms, vec![(x, 3.0)]);
+        assert_eq!(e.constant, -1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "isn't linear")]
+    fn expr_test_product_of_variables() {
+        let _ = lp! { [maximize x*y] [x <= 1] };
+    }
+
+    #[test]
+    fn expr_test_integer_and_rational() {
+        let r = |n: i64, d: i64| Rational::new(n, d);
+        let lp = lp! {
+            type Rational;
+            [integer x, y]
+            [binary b]
+            [free z]
+            [maximize 5*x + 4*y + 3*b]
+            [6*x + 4*y <= 24]
+            [x + 2*y <= 6]
+            [z == x - y]
+        };
+        assert!(lp.variables[0].integer && lp.variables[2].integer);
+        assert_eq!(lp.variables[2].upper, Some(r(1, 1)));
+        assert_eq!(lp.variables[3].lower, None);
+
+        let relaxed = lp.solve(&Options::default());
+        assert_eq!(relaxed.objective, r(24, 1));
+        assert_eq!(relaxed.value_of(&lp, "x"), Some(r(3, 1)));
+        assert_eq!(relaxed.value_of(&lp, "y"), Some(r(3, 2)));
+        assert_eq!(relaxed.value_of(&lp, "z"), Some(r(3, 2)));
+
+        let res = branch_and_bound(&lp, &MilpOptions::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert_eq!(res.objective, r(23, 1));
+        let x = lp.variable("x").unwrap();
+        assert_eq!(res.x[x.0], r(4, 1));
+    }
+
+    #[test]
+    fn expr_test_realistic_size() {
+        // A production plan of 8 products under 10 constraints, well past the default
+        // recursion limit in tokens
+        let lp = lp! {
+            [maximize 3*x1 + 2*x2 + 4*x3 + 5*x4 + 1*x5 + 2*x6 + 3*x7 + 4*x8]
+            [c1: 2*x1 + 1*x2 + 3*x3 + 1*x4 + 2*x5 + 1*x6 + 1*x7 + 2*x8 <= 100]
+            [c2: 1*x1 + 3*x2 + 1*x3 + 2*x4 + 1*x5 + 2*x6 + 3*x7 + 1*x8 <= 120]
+            [c3: 4*x1 + 1*x2 + 2*x3 + 3*x4 + 1*x5 + 1*x6 + 2*x7 + 1*x8 <= 150]
+            [c4: 1*x1 + 1*x2 + 1*x3 + 1*x4 + 1*x5 + 1*x6 + 1*x7 + 1*x8 <= 60]
+            [c5: 3*x1 + 2*x2 + 1*x3 + 1*x4 + 4*x5 + 2*x6 + 1*x7 + 3*x8 <= 140]
+            [c6: 1*x1 + 2*x2 + 3*x3 + 4*x4 + 1*x5 + 2*x6 + 3*x7 + 4*x8 <= 200]
+            [c7: 2*x1 + 2*x2 + 2*x3 + 1*x4 + 1*x5 + 1*x6 + 3*x7 + 3*x8 <= 130]
+            [c8: 1*x1 + 1*x2 + 1*x3 + 1*x4 + 1*x5 + 1*x6 + 1*x7 + 1*x8 >= 10]
+            [c9: 1*x1 + 1*x2 + 1*x3 + 1*x4 >= 5]
+            [c10: 1*x5 + 1*x6 + 1*x7 + 1*x8 == 20]
+        };
+        #[rustfmt::skip]
+        let rows: [([f64; 8], Sense, f64); 10] = [
+            ([2.0, 1.0, 3.0, 1.0, 2.0, 1.0, 1.0, 2.0], Sense::Le, 100.0),
+            ([1.0, 3.0, 1.0, 2.0, 1.0, 2.0, 3.0, 1.0], Sense::Le, 120.0),
+            ([4.0, 1.0, 2.0, 3.0, 1.0, 1.0, 2.0, 1.0], Sense::Le, 150.0),
+            ([1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0], Sense::Le, 60.0),
+            ([3.0, 2.0, 1.0, 1.0, 4.0, 2.0, 1.0, 3.0], Sense::Le, 140.0),
+            ([1.0, 2.0, 3.0, 4.0, 1.0, 2.0, 3.0, 4.0], Sense::Le, 200.0),
+            ([2.0, 2.0, 2.0, 1.0, 1.0, 1.0, 3.0, 3.0], Sense::Le, 130.0),
+            ([1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0], Sense::Ge, 10.0),
+            ([1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0], Sense::Ge, 5.0),
+            ([0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0], Sense::Eq, 20.0),
+        ];
+        let mut manual = LinearProgram::<f64>::new();
+        let x: Vec<_> = (1..=8)
+            .map(|j| manual.add_variable(&format!("x{}", j), 0.0, None))
+            .collect();
+        for (i, (coefs, sense, rhs)) in rows.iter().enumerate() {
+            let terms: Vec<_> = (x.iter().zip(coefs))
+                .filter(|(_, c)| **c != 0.0)
+                .map(|(&v, &c)| (v, c))
+                .collect();
+            manual.add_constraint(&format!("c{}", i + 1), &terms, *sense, *rhs);
+        }
+        let obj = [3.0, 2.0, 4.0, 5.0, 1.0, 2.0, 3.0, 4.0];
+        let terms: Vec<_> = x.iter().cloned().zip(obj.iter().cloned()).collect();
+        manual.maximize(&terms);
+        assert_eq!(format!("{:?}", lp), format!("{:?}", manual));
+
+        let res = lp.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        let expected = manual.solve(&Options::default());
+        assert!((res.objective - expected.objective).abs() < 1E-9);
+    }
+}
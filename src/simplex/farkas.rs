@@ -0,0 +1,307 @@
+/// Explanations of infeasibility. By Farkas' lemma, the constraints A x <sense> b with
+/// bounds l <= x <= u have no solution exactly when there are multipliers y of the
+/// constraints (y_i >= 0 for `<=`, y_i <= 0 for `>=`, any sign for `=`) such that
+/// y^T A x > y^T b for every x within the bounds: summing the constraints up with these
+/// multipliers gives y^T A x <= y^T b, which no such x satisfies.
+/// Phase I of the simplex method finds the multipliers as its shadow prices.
+/// An irreducible infeasible subsystem (IIS) is a set of constraints that can't hold
+/// together, while any smaller part of it can: the conflict to show to a person.
+/// [https://en.wikipedia.org/wiki/Farkas%27_lemma]
+use super::model::{Constraint, LinearProgram};
+use super::revised::{Outcome, Revised};
+use super::sensitivity::Interval;
+use super::sparse::SparseMatrix;
+use super::{Number, Options, Sense, Status};
+
+/// Whether the multipliers `y` prove that the constraints can't hold within the bounds
+/// (up to the tolerance of the numbers)
+pub fn is_certificate<T: Number>(
+    constr: &SparseMatrix<T>,
+    senses: &[Sense],
+    req: &[T],
+    bounds: &[Interval<T>],
+    y: &[T],
+) -> bool {
+    assert_eq!(constr.nrows(), y.len(), "One multiplier per constraint");
+    let signs = senses.iter().zip(y).all(|(sense, y)| match sense {
+        Sense::Le => *y >= -T::eps(),
+        Sense::Ge => *y <= T::eps(),
+        Sense::Eq => true,
+    });
+    if !signs {
+        return false;
+    }
+    // The least value of y^T A x within the bounds
+    let mut least = T::zero();
+    for (j, (lower, upper)) in bounds.iter().enumerate() {
+        let coef = constr.dot_column(j, y);
+        if coef.abs() <= T::eps() {
+            continue;
+        }
+        let bound = if coef.is_positive() { lower } else { upper };
+        match bound {
+            Some(bound) => least += coef * bound,
+            None => return false,
+        }
+    }
+    let rhs = super::dot(y, req);
+    least - &rhs > T::eps() * (T::one() + rhs.abs())
+}
+
+/// The multipliers proving the constraints infeasible, found by Phase I
+/// of the revised simplex method. `None` if the constraints are feasible
+/// or the bounds of a variable cross.
+pub(super) fn certificate<T: Number>(
+    constr: SparseMatrix<T>,
+    senses: Vec<Sense>,
+    req: Vec<T>,
+    bounds: &[Interval<T>],
+) -> Option<Vec<T>> {
+    let obj = vec![T::zero(); constr.ncols()];
+    let mut revised = Revised::new(constr, senses, req, bounds);
+    match revised.optimize(&obj, &Options::default(), &mut 0) {
+        Outcome::Infeasible => revised.farkas,
+        _ => None,
+    }
+}
+
+/// Finds an irreducible infeasible subsystem of the constraints of an infeasible program,
+/// `None` if the program is feasible (or the solver gives up on it).
+/// The bounds of the variables are kept throughout: they are part of every subsystem,
+/// which is empty if they conflict by themselves.
+/// The constraints with non-zero Farkas multipliers are infeasible together,
+/// then the deletion filter drops every one of them the rest stays infeasible without.
+pub fn irreducible_infeasible_subsystem<T: Number>(
+    lp: &LinearProgram<T>,
+    options: &Options<T>,
+) -> Option<Vec<Constraint>> {
+    let infeasible = |rows: &[usize]| {
+        let mut sub = lp.clone();
+        sub.constraints = rows.iter().map(|&i| lp.constraints[i].clone()).collect();
+        sub.objective = vec![T::zero(); lp.variables.len()];
+        let solution = sub.solve_revised(options);
+        match solution.status {
+            Status::Infeasible => Some(solution.farkas),
+            _ => None,
+        }
+    };
+    let all: Vec<usize> = (0..lp.constraints.len()).collect();
+    let mut rows = match infeasible(&all)? {
+        Some(y) => (all.into_iter())
+            .filter(|&i| y[i].abs() > T::eps())
+            .collect(),
+        None => all,
+    };
+    if !rows.is_empty() && infeasible(&rows).is_none() {
+        rows = (0..lp.constraints.len()).collect();
+    }
+    let mut k = 0;
+    while k < rows.len() {
+        let mut rest = rows.clone();
+        rest.remove(k);
+        if infeasible(&rest).is_some() {
+            rows = rest;
+        } else {
+            k += 1;
+        }
+    }
+    Some(rows.into_iter().map(Constraint).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{bounded_simplex, interior_point, revised_simplex, Rational, WarmStart};
+    use super::*;
+
+    /// The problems of the tests, all of them infeasible
+    fn problems() -> Vec<(LinearProgram<f64>, &'static str)> {
+        let mut problems = Vec::new();
+
+        // x + y <= 4 and x + y >= 6
+        let mut lp = LinearProgram::new();
+        let x = lp.add_variable("x", 0.0, None);
+        let y = lp.add_variable("y", 0.0, None);
+        lp.add_constraint("cap", &[(x, 1.0), (y, 1.0)], Sense::Le, 4.0);
+        lp.add_constraint("need", &[(x, 1.0), (y, 1.0)], Sense::Ge, 6.0);
+        lp.maximize(&[(x, 1.0)]);
+        problems.push((lp, "opposite rows"));
+
+        // x - y = 3, y >= 2 (a row), x <= 4 (a bound), with a free z on the side
+        let mut lp = LinearProgram::new();
+        let x = lp.add_variable("x", 0.0, Some(4.0));
+        let y = lp.add_variable("y", 0.0, None);
+        let z = lp.add_free_variable("z");
+        lp.add_constraint("diff", &[(x, 1.0), (y, -1.0)], Sense::Eq, 3.0);
+        lp.add_constraint("floor", &[(y, 1.0)], Sense::Ge, 2.0);
+        lp.add_constraint("side", &[(z, 1.0), (x, 1.0)], Sense::Le, 10.0);
+        lp.minimize(&[(z, 1.0), (y, 1.0)]);
+        problems.push((lp, "equality and bounds"));
+
+        // -x - y >= -3 (negative requirements), 2x + y = 8, x + 2y >= 7, x, y in [0, 5]
+        let mut lp = LinearProgram::new();
+        let x = lp.add_variable("x", 0.0, Some(5.0));
+        let y = lp.add_variable("y", 0.0, Some(5.0));
+        lp.add_constraint("c1", &[(x, -1.0), (y, -1.0)], Sense::Ge, -3.0);
+        lp.add_constraint("c2", &[(x, 2.0), (y, 1.0)], Sense::Eq, 8.0);
+        lp.add_constraint("c3", &[(x, 1.0), (y, 2.0)], Sense::Ge, 7.0);
+        lp.maximize(&[(x, 1.0), (y, 1.0)]);
+        problems.push((lp, "negative requirements"));
+        problems
+    }
+
+    #[test]
+    fn farkas_test_certificates() {
+        for (lp, name) in problems() {
+            let (constr, senses, req, obj, bounds) = lp.to_sparse_form();
+            let check = |y: Option<Vec<f64>>, method: &str| {
+                let y = y.unwrap_or_else(|| panic!("No certificate from {} for {}", method, name));
+                assert!(
+                    is_certificate(&constr, &senses, &req, &bounds, &y),
+                    "Not a certificate from {} for {}: {:?}",
+                    method,
+                    name,
+                    y
+                );
+            };
+            let table = bounded_simplex(
+                constr.to_dense(),
+                senses.clone(),
+                req.clone(),
+                obj.clone(),
+                &bounds,
+                &Options::default(),
+            );
+            assert_eq!(table.status, Status::Infeasible);
+            check(table.farkas, "the table");
+            let revised = revised_simplex(
+                constr.clone(),
+                senses.clone(),
+                req.clone(),
+                obj.clone(),
+                &bounds,
+                &Options::default(),
+            );
+            assert_eq!(revised.status, Status::Infeasible);
+            check(revised.farkas, "the revised method");
+            let interior = interior_point(
+                constr.to_dense(),
+                senses.clone(),
+                req.clone(),
+                obj.clone(),
+                &bounds,
+                false,
+                &Options::default(),
+            );
+            assert_eq!(interior.status, Status::Infeasible);
+            check(interior.farkas, "the interior point");
+            let presolved = lp.solve(&Options {
+                presolve: true,
+                ..Options::default()
+            });
+            check(presolved.farkas, "presolve");
+        }
+    }
+
+    #[test]
+    fn farkas_test_dual_simplex_and_rationals() {
+        // max x + y, x + y <= 4, then a cut x + y >= 5 makes it infeasible
+        let constr = SparseMatrix::<f64>::from_triplets(1, 2, &[(0, 0, 1.0), (0, 1, 1.0)]);
+        let bounds = vec![(Some(0.0), None); 2];
+        let mut warm = WarmStart::new(constr, vec![Sense::Le], vec![4.0], vec![1.0, 1.0], &bounds);
+        assert_eq!(warm.solve(&Options::default()).status, Status::Optimal);
+        warm.add_row(&[(0, 1.0), (1, 1.0)], Sense::Ge, 5.0);
+        let res = warm.solve(&Options::default());
+        assert_eq!(res.status, Status::Infeasible);
+        let y = res.farkas.unwrap();
+        let constr = SparseMatrix::from_triplets(
+            2,
+            2,
+            &[(0, 0, 1.0), (0, 1, 1.0), (1, 0, 1.0), (1, 1, 1.0)],
+        );
+        let senses = [Sense::Le, Sense::Ge];
+        assert!(
+            is_certificate(&constr, &senses, &[4.0, 5.0], &bounds, &y),
+            "{:?}",
+            y
+        );
+        // Not a certificate: the multipliers have the wrong signs
+        assert!(!is_certificate(
+            &constr,
+            &senses,
+            &[4.0, 5.0],
+            &bounds,
+            &[-1.0, 1.0]
+        ));
+
+        let r = |n: i64| Rational::from_integer(n);
+        let constr = SparseMatrix::from_triplets(2, 1, &[(0, 0, r(3)), (1, 0, r(2))]);
+        let senses = vec![Sense::Le, Sense::Ge];
+        let req = vec![r(2), r(1)];
+        let bounds = vec![(Some(r(0)), Some(r(1)))];
+        // 3x <= 2 and 2x >= 1 hold for x in [1/2, 2/3], with 2x >= 2 they don't
+        let feasible = revised_simplex(
+            constr.clone(),
+            senses.clone(),
+            req,
+            vec![r(1)],
+            &bounds,
+            &Options::default(),
+        );
+        assert_eq!(feasible.status, Status::Optimal);
+        assert_eq!(feasible.farkas, None);
+        let req = vec![r(2), r(2)];
+        let res = revised_simplex(
+            constr.clone(),
+            senses.clone(),
+            req.clone(),
+            vec![r(1)],
+            &bounds,
+            &Options::default(),
+        );
+        // The multipliers are exact, there is no tolerance with rationals
+        let y = res.farkas.unwrap();
+        assert!(is_certificate(&constr, &senses, &req, &bounds, &y));
+        let y = vec![r(2), r(-3)];
+        assert!(is_certificate(&constr, &senses, &req, &bounds, &y));
+        let y = vec![r(1), r(-1)];
+        assert!(!is_certificate(&constr, &senses, &req, &bounds, &y));
+    }
+
+    #[test]
+    fn farkas_test_irreducible_subsystem() {
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 0.0, None);
+        let y = lp.add_variable("y", 0.0, None);
+        let z = lp.add_variable("z", 0.0, Some(3.0));
+        lp.add_constraint("budget", &[(x, 1.0), (y, 1.0)], Sense::Le, 10.0);
+        lp.add_constraint("extra", &[(z, 1.0), (y, 1.0)], Sense::Le, 20.0);
+        lp.add_constraint("x_min", &[(x, 1.0)], Sense::Ge, 6.0);
+        lp.add_constraint("y_min", &[(y, 1.0)], Sense::Ge, 5.0);
+        lp.add_constraint("z_y", &[(z, 1.0), (y, -1.0)], Sense::Ge, -10.0);
+        lp.maximize(&[(x, 1.0)]);
+
+        let iis = irreducible_infeasible_subsystem(&lp, &Options::default()).unwrap();
+        let names: Vec<_> = (iis.iter())
+            .map(|c| lp.constraints[c.0].name.as_str())
+            .collect();
+        assert_eq!(names, vec!["budget", "x_min", "y_min"]);
+
+        // Without the budget it's feasible
+        lp.constraints.remove(0);
+        assert_eq!(
+            irreducible_infeasible_subsystem(&lp, &Options::default()),
+            None
+        );
+
+        // The bounds conflict by themselves
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 0.0, None);
+        lp.add_constraint("c", &[(x, 1.0)], Sense::Le, 5.0);
+        lp.variables[0].lower = Some(6.0);
+        lp.variables[0].upper = Some(4.0);
+        assert_eq!(
+            irreducible_infeasible_subsystem(&lp, &Options::default()),
+            Some(Vec::new())
+        );
+    }
+}
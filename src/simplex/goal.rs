@@ -0,0 +1,348 @@
+/// Programs with several objectives. Lexicographic optimization solves them in order
+/// of importance: once an objective reaches its optimum, a constraint keeps it there
+/// while the next ones are optimized. Goal programming turns targets into constraints
+/// with deviation variables, sum a_j x_j + under - over = target, and minimizes
+/// the weighted deviations, the goals of each priority level after the ones before it.
+/// [https://en.wikipedia.org/wiki/Lexicographic_optimization]
+/// [https://en.wikipedia.org/wiki/Goal_programming]
+use super::{
+    Constraint, Direction, LinearProgram, Number, Options, Sense, Solution, Status, Variable,
+};
+
+/// One of the objectives of lexicographic optimization
+#[derive(Debug, Clone, PartialEq)]
+pub struct Objective<T: Number> {
+    pub direction: Direction,
+    pub terms: Vec<(Variable, T)>,
+}
+
+impl<T: Number> Objective<T> {
+    pub fn maximize(terms: &[(Variable, T)]) -> Self {
+        Objective {
+            direction: Direction::Maximize,
+            terms: terms.to_vec(),
+        }
+    }
+
+    pub fn minimize(terms: &[(Variable, T)]) -> Self {
+        Objective {
+            direction: Direction::Minimize,
+            terms: terms.to_vec(),
+        }
+    }
+}
+
+/// The knobs of lexicographic optimization
+pub struct LexOptions<'a, T: Number> {
+    /// The options of the simplex method solving every level,
+    /// whose `algorithm` and `presolve` are ignored
+    pub lp: Options<'a, T>,
+    /// How far the later levels may push an objective from its optimum,
+    /// relative to the optimum (at least 1)
+    pub tolerance: T,
+}
+
+impl<T: Number> Default for LexOptions<'static, T> {
+    fn default() -> Self {
+        LexOptions {
+            lp: Options::default(),
+            tolerance: T::eps(),
+        }
+    }
+}
+
+/// The result of lexicographic optimization
+#[derive(Debug, Clone)]
+pub struct Lexicographic<T: Number> {
+    /// `Optimal` if every level has been solved, otherwise the status of the one that failed
+    pub status: Status,
+    /// The program with the last objective and a constraint called `lex1`, `lex2`, ...
+    /// keeping each of the others at its optimum
+    pub program: LinearProgram<T>,
+    /// The solution of the last level solved
+    pub solution: Solution<T>,
+    /// The optimum of each level solved
+    pub optima: Vec<T>,
+}
+
+/// Optimizes the objectives one after another, each within the optima of the ones before.
+/// Without objectives, any point satisfying the constraints does.
+pub fn lexicographic<T: Number>(
+    lp: &LinearProgram<T>,
+    objectives: &[Objective<T>],
+    options: &LexOptions<T>,
+) -> Lexicographic<T> {
+    let lp_options = options.lp.table_method();
+    let mut program = lp.clone();
+    if objectives.is_empty() {
+        program.maximize(&[]);
+        let solution = program.solve(&lp_options);
+        return Lexicographic {
+            status: solution.status,
+            program,
+            solution,
+            optima: Vec::new(),
+        };
+    }
+    let mut optima = Vec::new();
+    let mut level = 0;
+    loop {
+        let objective = &objectives[level];
+        match objective.direction {
+            Direction::Maximize => program.maximize(&objective.terms),
+            Direction::Minimize => program.minimize(&objective.terms),
+        }
+        let solution = program.solve(&lp_options);
+        if solution.status != Status::Optimal || level + 1 == objectives.len() {
+            if solution.status == Status::Optimal {
+                optima.push(solution.objective.clone());
+            }
+            return Lexicographic {
+                status: solution.status,
+                program,
+                solution,
+                optima,
+            };
+        }
+        let optimum = solution.objective.clone();
+        let scale = if optimum.abs() > T::one() {
+            optimum.abs()
+        } else {
+            T::one()
+        };
+        let slack = options.tolerance.clone() * scale;
+        let name = format!("lex{}", level + 1);
+        match objective.direction {
+            Direction::Maximize => {
+                let bound = optimum.clone() - slack;
+                program.add_constraint(&name, &objective.terms, Sense::Ge, bound)
+            }
+            Direction::Minimize => {
+                let bound = optimum.clone() + slack;
+                program.add_constraint(&name, &objective.terms, Sense::Le, bound)
+            }
+        };
+        optima.push(optimum);
+        level += 1;
+    }
+}
+
+/// A target for sum a_j x_j, with penalties per unit of falling short and of overshooting
+#[derive(Debug, Clone, PartialEq)]
+pub struct Goal<T: Number> {
+    pub name: String,
+    pub terms: Vec<(Variable, T)>,
+    pub target: T,
+    /// The penalty per unit below the target
+    pub under: T,
+    /// The penalty per unit above the target
+    pub over: T,
+    /// The goals of priority 0 are met as well as they can be first, then those of 1, ...
+    pub priority: usize,
+}
+
+impl<T: Number> Goal<T> {
+    /// A goal of priority 0
+    pub fn new(name: &str, terms: &[(Variable, T)], target: T, under: T, over: T) -> Self {
+        Goal {
+            name: name.to_string(),
+            terms: terms.to_vec(),
+            target,
+            under,
+            over,
+            priority: 0,
+        }
+    }
+}
+
+/// The result of goal programming
+#[derive(Debug, Clone)]
+pub struct GoalSolution<T: Number> {
+    /// `Optimal` if every priority level has been solved
+    pub status: Status,
+    /// The program with a constraint per goal, named after it, and its deviation variables
+    /// `<goal>_under` and `<goal>_over`, as solved by `lexicographic`
+    pub program: LinearProgram<T>,
+    /// The solution of the last level solved
+    pub solution: Solution<T>,
+    /// How far each goal falls short of its target
+    pub under: Vec<T>,
+    /// How far each goal overshoots its target
+    pub over: Vec<T>,
+    /// The weighted deviations of each priority level solved, in order
+    pub penalties: Vec<T>,
+    /// The constraint of each goal
+    pub goals: Vec<Constraint>,
+}
+
+/// Minimizes the weighted deviations from the goals, one priority level after another.
+/// With a single level it's weighted goal programming, with a goal per level
+/// and unit penalties it's preemptive goal programming. Without goals,
+/// any point satisfying the constraints does.
+pub fn goal_programming<T: Number>(
+    lp: &LinearProgram<T>,
+    goals: &[Goal<T>],
+    options: &LexOptions<T>,
+) -> GoalSolution<T> {
+    let mut program = lp.clone();
+    let mut deviations = Vec::new();
+    let mut constraints = Vec::new();
+    for goal in goals {
+        let under = program.add_variable(&format!("{}_under", goal.name), T::zero(), None);
+        let over = program.add_variable(&format!("{}_over", goal.name), T::zero(), None);
+        let mut terms = goal.terms.clone();
+        terms.push((under, T::one()));
+        terms.push((over, -T::one()));
+        let c = program.add_constraint(&goal.name, &terms, Sense::Eq, goal.target.clone());
+        deviations.push((under, over));
+        constraints.push(c);
+    }
+    let mut priorities: Vec<usize> = goals.iter().map(|goal| goal.priority).collect();
+    priorities.sort_unstable();
+    priorities.dedup();
+    let objectives: Vec<_> = (priorities.iter())
+        .map(|&priority| {
+            let terms: Vec<_> = (goals.iter().zip(&deviations))
+                .filter(|(goal, _)| goal.priority == priority)
+                .flat_map(|(goal, &(under, over))| {
+                    vec![(under, goal.under.clone()), (over, goal.over.clone())]
+                })
+                .collect();
+            Objective::minimize(&terms)
+        })
+        .collect();
+    let res = lexicographic(&program, &objectives, options);
+    let solution = res.solution;
+    let (under, over) = match solution.status {
+        Status::Optimal => deviations
+            .iter()
+            .map(|&(under, over)| (solution.value(under), solution.value(over)))
+            .unzip(),
+        _ => (Vec::new(), Vec::new()),
+    };
+    GoalSolution {
+        status: res.status,
+        program: res.program,
+        solution,
+        under,
+        over,
+        penalties: res.optima,
+        goals: constraints,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplex::Rational;
+
+    #[test]
+    fn goal_test_lexicographic() {
+        // x + y <= 10, y <= 8: the most of x + y, then the least of x, then the most of z
+        let r = |n: i64| Rational::from_integer(n);
+        let mut lp = LinearProgram::new();
+        let x = lp.add_variable("x", r(0), None);
+        let y = lp.add_variable("y", r(0), Some(r(8)));
+        let z = lp.add_variable("z", r(0), None);
+        lp.add_constraint("total", &[(x, r(1)), (y, r(1))], Sense::Le, r(10));
+        lp.add_constraint("link", &[(z, r(1)), (x, r(-2))], Sense::Le, r(1));
+        let objectives = [
+            Objective::maximize(&[(x, r(1)), (y, r(1))]),
+            Objective::minimize(&[(x, r(1))]),
+            Objective::maximize(&[(z, r(1))]),
+        ];
+        let res = lexicographic(&lp, &objectives, &LexOptions::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert_eq!(res.optima, vec![r(10), r(2), r(5)]);
+        assert_eq!(res.solution.x, vec![r(2), r(8), r(5)]);
+        assert_eq!(res.program.constraints.len(), 4);
+        assert_eq!(res.program.constraints[3].name, "lex2");
+
+        // The second level is unbounded
+        let objectives = [
+            Objective::maximize(&[(x, r(1))]),
+            Objective::minimize(&[(z, r(-1))]),
+        ];
+        lp.constraints.pop();
+        let res = lexicographic(&lp, &objectives, &LexOptions::default());
+        assert_eq!(res.status, Status::Unbounded);
+        assert_eq!(res.optima, vec![r(10)]);
+    }
+
+    /// Bowls x1 and mugs x2: 40 hours of labour, a profit of 1600, 120 pounds of clay
+    /// Taylor: Introduction to Management Science, chapter 9
+    fn pottery(penalties: [f64; 4], priorities: [usize; 4]) -> GoalSolution<f64> {
+        let mut lp = LinearProgram::new();
+        let x1 = lp.add_variable("x1", 0.0, None);
+        let x2 = lp.add_variable("x2", 0.0, None);
+        let mut goals = vec![
+            Goal::new("labour", &[(x1, 1.0), (x2, 2.0)], 40.0, penalties[0], 0.0),
+            Goal::new(
+                "profit",
+                &[(x1, 40.0), (x2, 50.0)],
+                1600.0,
+                penalties[1],
+                0.0,
+            ),
+            Goal::new("clay", &[(x1, 4.0), (x2, 3.0)], 120.0, 0.0, penalties[2]),
+            Goal::new("overtime", &[(x1, 1.0), (x2, 2.0)], 40.0, 0.0, penalties[3]),
+        ];
+        for (goal, &priority) in goals.iter_mut().zip(&priorities) {
+            goal.priority = priority;
+        }
+        goal_programming(&lp, &goals, &LexOptions::default())
+    }
+
+    #[test]
+    fn goal_test_preemptive_and_weighted() {
+        let preemptive = pottery([1.0; 4], [0, 1, 2, 3]);
+        assert_eq!(preemptive.status, Status::Optimal);
+        assert_eq!(preemptive.penalties.len(), 4);
+        assert!(preemptive.penalties[..3].iter().all(|p| p.abs() < 1E-9));
+        assert!((preemptive.penalties[3] - 15.0).abs() < 1E-9);
+        let x1 = preemptive
+            .solution
+            .value_of(&preemptive.program, "x1")
+            .unwrap();
+        let x2 = preemptive
+            .solution
+            .value_of(&preemptive.program, "x2")
+            .unwrap();
+        assert!((x1 - 15.0).abs() < 1E-9 && (x2 - 20.0).abs() < 1E-9);
+        assert!((preemptive.over[3] - 15.0).abs() < 1E-9);
+        assert_eq!(
+            preemptive.program.constraint("clay"),
+            Some(preemptive.goals[2])
+        );
+
+        // Weights far enough apart give the same plan in a single solve
+        let weighted = pottery([1E6, 1E4, 1E2, 1.0], [0; 4]);
+        assert_eq!(weighted.status, Status::Optimal);
+        assert_eq!(weighted.penalties.len(), 1);
+        assert!((weighted.penalties[0] - 15.0).abs() < 1E-6);
+        let x = &weighted.solution.x;
+        assert!((x[0] - 15.0).abs() < 1E-9 && (x[1] - 20.0).abs() < 1E-9);
+
+        // Overtime matters more than profit now: keep to 40 hours and give up on profit
+        let weighted = pottery([5.0, 1.0, 10.0, 100.0], [0; 4]);
+        let x = &weighted.solution.x;
+        assert!((x[0] + 2.0 * x[1] - 40.0).abs() < 1E-9);
+        assert!(weighted.under[1] > 0.0 && weighted.over[3].abs() < 1E-9);
+    }
+
+    #[test]
+    fn goal_test_no_goals() {
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 0.0, None);
+        lp.add_constraint("c1", &[(x, 1.0)], Sense::Ge, 3.0);
+        let res = goal_programming(&lp, &[], &LexOptions::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!(res.solution.x[0] >= 3.0 - 1E-9);
+        assert!(res.penalties.is_empty() && res.under.is_empty() && res.goals.is_empty());
+
+        // Nothing satisfies the constraints
+        lp.add_constraint("c2", &[(x, 1.0)], Sense::Le, 2.0);
+        let res = goal_programming(&lp, &[], &LexOptions::default());
+        assert_eq!(res.status, Status::Infeasible);
+    }
+}
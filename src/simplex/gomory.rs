@@ -0,0 +1,420 @@
+/// Gomory mixed-integer cuts: a row of the final table whose basic integer variable
+/// takes a fractional value f0 states x_B + sum a_j v_j = b with every non-basic column
+/// v_j >= 0 at zero. Rounding the row in the two ways x_B can go yields
+/// sum g_j v_j >= 1, where g_j = f_j / f0 or (1 - f_j) / (1 - f0) for integer columns
+/// (f_j the fractional part of a_j) and a_j / f0 or -a_j / (1 - f0) for continuous ones.
+/// Every whole solution satisfies the cut, the current vertex doesn't.
+/// The cut loop adds the best of them to the program and solves it again, round after round.
+/// [https://en.wikipedia.org/wiki/Cutting-plane_method#Gomory's_cut]
+use super::{num, LinearProgram, Number, Options, Sense, Solution, Status, Variable};
+
+/// The knobs of the cut loop
+pub struct CutOptions<'a, T: Number> {
+    /// The options of the simplex method solving every relaxation,
+    /// whose `algorithm` and `presolve` are ignored
+    pub lp: Options<'a, T>,
+    /// The number of rounds of cuts the loop stops after
+    pub max_rounds: usize,
+    /// The number of cuts added in a round, the most efficacious ones
+    pub max_cuts: usize,
+    /// Cuts passing the current vertex by less than this distance are left out
+    pub min_efficacy: T,
+    /// Cuts whose largest coefficient is more than this many times the smallest one
+    /// are left out, they are numerically risky
+    pub max_dynamism: T,
+    /// Values this close to a whole number count as whole
+    pub integrality: T,
+}
+
+impl<T: Number> Default for CutOptions<'static, T> {
+    fn default() -> Self {
+        CutOptions {
+            lp: Options::default(),
+            max_rounds: 20,
+            max_cuts: 10,
+            min_efficacy: num(1E-4),
+            max_dynamism: num(1E6),
+            integrality: num(1E-5),
+        }
+    }
+}
+
+/// A cut in terms of the variables of the program: sum terms >= rhs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cut<T: Number> {
+    pub terms: Vec<(Variable, T)>,
+    pub rhs: T,
+    /// The distance between the cut and the vertex it cuts off
+    pub efficacy: T,
+}
+
+/// The result of the cut loop
+#[derive(Debug, Clone)]
+pub struct CutLoop<T: Number> {
+    /// The program with the cuts added as `>=` constraints called `gmi1`, `gmi2`, ...
+    pub program: LinearProgram<T>,
+    /// The solution of the last relaxation
+    pub solution: Solution<T>,
+    /// The cuts added, in order
+    pub cuts: Vec<Cut<T>>,
+    /// The number of rounds of cuts added
+    pub rounds: usize,
+    /// Whether every integer variable is whole in `solution`, which solves the program then
+    pub integral: bool,
+}
+
+/// Strengthens the relaxation of the program with rounds of Gomory mixed-integer cuts,
+/// until its solution is whole, no cut passes the filter or the rounds run out.
+/// The bounds of integer variables are rounded inwards first.
+pub fn gomory_cuts<T: Number>(lp: &LinearProgram<T>, options: &CutOptions<T>) -> CutLoop<T> {
+    let lp_options = options.lp.table_method();
+    let mut program = lp.clone();
+    for var in program.variables.iter_mut().filter(|var| var.integer) {
+        var.lower = var.lower.as_ref().map(|lower| -(-lower.clone()).floor());
+        var.upper = var.upper.as_ref().map(|upper| upper.floor());
+    }
+    let mut cuts = Vec::new();
+    let mut rounds = 0;
+    let solution = loop {
+        let solution = program.solve(&lp_options);
+        if solution.status != Status::Optimal || rounds == options.max_rounds {
+            break solution;
+        }
+        let mut round = mixed_integer_cuts(&program, &solution, &options.integrality);
+        round.retain(|cut| cut.efficacy >= options.min_efficacy && dynamism(cut, options));
+        round.sort_by(|a, b| b.efficacy.partial_cmp(&a.efficacy).unwrap());
+        round.truncate(options.max_cuts);
+        if round.is_empty() {
+            break solution;
+        }
+        for cut in round {
+            let name = format!("gmi{}", cuts.len() + 1);
+            program.add_constraint(&name, &cut.terms, Sense::Ge, cut.rhs.clone());
+            cuts.push(cut);
+        }
+        rounds += 1;
+    };
+    let integral = solution.status == Status::Optimal
+        && (program.variables.iter().zip(&solution.x))
+            .all(|(var, v)| !var.integer || is_whole(v, &options.integrality));
+    CutLoop {
+        program,
+        solution,
+        cuts,
+        rounds,
+        integral,
+    }
+}
+
+/// The Gomory mixed-integer cut of every row of the final table whose basic integer
+/// variable is fractional (by more than `integrality`). The solution has to come from
+/// `LinearProgram::solve` with the table method, and the bounds of integer variables
+/// have to be whole. Rows involving a non-basic free column give no cut, and solutions
+/// that aren't optimal or have no table give none at all.
+pub fn mixed_integer_cuts<T: Number>(
+    lp: &LinearProgram<T>,
+    solution: &Solution<T>,
+    integrality: &T,
+) -> Vec<Cut<T>> {
+    let table = &solution.table;
+    if solution.status != Status::Optimal || table.is_empty() {
+        return Vec::new();
+    }
+    let layout = &solution.layout;
+    let n_vars = lp.variables.len();
+    let last_col = table.ncols() - 1;
+    // A column is integer if its values are whole whenever the variables are
+    let integer: Vec<bool> = (0..last_col)
+        .map(|col| match col {
+            0 => false,
+            _ if col <= n_vars => {
+                lp.variables[col - 1].integer && is_whole(&layout.offset(col), &T::eps())
+            }
+            // The artificial columns of Phase I
+            _ if col > n_vars + lp.constraints.len() => false,
+            _ => {
+                let c = &lp.constraints[col - n_vars - 1];
+                is_whole(&c.rhs, &T::eps())
+                    && (c.terms.iter())
+                        .all(|(var, a)| lp.variables[var.0].integer && is_whole(a, &T::eps()))
+            }
+        })
+        .collect();
+    let mut cuts = Vec::new();
+    for (i, &basic) in solution.basis.iter().enumerate() {
+        let (row, col) = (i + 1, basic + 1);
+        if col >= integer.len() {
+            continue;
+        }
+        let f0 = fraction(&table[(row, last_col)]);
+        if !integer[col] || f0 <= *integrality || T::one() - &f0 <= *integrality {
+            continue;
+        }
+        // The cut in terms of the columns
+        let mut gamma = vec![T::zero(); last_col];
+        let mut usable = true;
+        for j in (1..last_col).filter(|j| !solution.basis.contains(&(j - 1))) {
+            let a = table[(row, j)].clone();
+            if a.abs() <= T::eps() || is_locked(lp, n_vars, j) {
+                continue;
+            }
+            if layout.is_free(j) {
+                usable = false;
+                break;
+            }
+            gamma[j] = if integer[j] {
+                let f = fraction(&a);
+                if f <= f0 {
+                    f / &f0
+                } else {
+                    (T::one() - f) / (T::one() - &f0)
+                }
+            } else if a.is_negative() {
+                -a / (T::one() - &f0)
+            } else {
+                a / &f0
+            };
+        }
+        if usable {
+            cuts.push(to_variables(lp, solution, &gamma));
+        }
+    }
+    cuts
+}
+
+/// The slack column of an `=` constraint stays at zero
+fn is_locked<T: Number>(lp: &LinearProgram<T>, n_vars: usize, col: usize) -> bool {
+    col > n_vars && lp.constraints[col - n_vars - 1].sense == Sense::Eq
+}
+
+/// Turns sum gamma_j v_j >= 1 into a cut on the variables. A decision column holds
+/// v = sign * (x - offset), the slack column of `a x <= b` holds b - a x and
+/// the one of `a x >= b` holds a x - b.
+fn to_variables<T: Number>(lp: &LinearProgram<T>, solution: &Solution<T>, gamma: &[T]) -> Cut<T> {
+    let n_vars = lp.variables.len();
+    let layout = &solution.layout;
+    let mut alpha = vec![T::zero(); n_vars];
+    let mut rhs = T::one();
+    for (col, g) in gamma.iter().enumerate().filter(|(_, g)| !g.is_zero()) {
+        if col <= n_vars {
+            let sign = layout.sign(col);
+            alpha[col - 1] += g.clone() * &sign;
+            rhs += g.clone() * sign * layout.offset(col);
+        } else {
+            let c = &lp.constraints[col - n_vars - 1];
+            let sign = match c.sense {
+                Sense::Le => -T::one(),
+                _ => T::one(),
+            };
+            for (var, a) in &c.terms {
+                alpha[var.0] += g.clone() * &sign * a;
+            }
+            rhs += g.clone() * sign * &c.rhs;
+        }
+    }
+    let terms: Vec<(Variable, T)> = (alpha.into_iter().enumerate())
+        .filter(|(_, a)| a.abs() > T::eps())
+        .map(|(j, a)| (Variable(j), a))
+        .collect();
+    let norm = (terms.iter())
+        .fold(T::zero(), |acc, (_, a)| acc + a.clone() * a)
+        .to_f64()
+        .sqrt();
+    let lhs = (terms.iter()).fold(T::zero(), |acc, (var, a)| {
+        acc + a.clone() * &solution.x[var.0]
+    });
+    let efficacy = if norm > 0.0 {
+        (rhs.clone() - lhs) / T::from_f64(norm)
+    } else {
+        T::zero()
+    };
+    Cut {
+        terms,
+        rhs,
+        efficacy,
+    }
+}
+
+/// The largest coefficient of the cut is at most `max_dynamism` times the smallest one
+fn dynamism<T: Number>(cut: &Cut<T>, options: &CutOptions<T>) -> bool {
+    let magnitudes = cut.terms.iter().map(|(_, a)| a.abs());
+    let largest = magnitudes
+        .clone()
+        .fold(T::zero(), |acc, a| if a > acc { a } else { acc });
+    match magnitudes.reduce(|acc, a| if a < acc { a } else { acc }) {
+        Some(smallest) => largest <= smallest * &options.max_dynamism,
+        None => false,
+    }
+}
+
+/// The distance to the whole number below
+fn fraction<T: Number>(x: &T) -> T {
+    x.clone() - x.floor()
+}
+
+/// Whether the number is this close to a whole one, on either side
+fn is_whole<T: Number>(x: &T, tolerance: &T) -> bool {
+    let f = fraction(x);
+    f <= *tolerance || T::one() - f <= *tolerance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::milp::{branch_and_bound, MilpOptions};
+    use crate::simplex::{Algorithm, Rational};
+
+    /*
+    Objective function = 5x + 4y
+    Constraints:
+    6x + 4y <= 24
+    x + 2y <= 6
+    x, y >= 0 and whole
+    The relaxation reaches 21 at (3, 1.5), the whole optimum is 20 at (4, 0)
+    */
+    fn small<T: Number>() -> LinearProgram<T> {
+        let mut lp = LinearProgram::new();
+        let x = lp.add_integer_variable("x", T::zero(), None);
+        let y = lp.add_integer_variable("y", T::zero(), None);
+        lp.add_constraint("c1", &[(x, num(6.0)), (y, num(4.0))], Sense::Le, num(24.0));
+        lp.add_constraint("c2", &[(x, num(1.0)), (y, num(2.0))], Sense::Le, num(6.0));
+        lp.maximize(&[(x, num(5.0)), (y, num(4.0))]);
+        lp
+    }
+
+    /// Every whole point of the original program satisfies every cut
+    fn check_valid<T: Number>(lp: &LinearProgram<T>, cuts: &[Cut<T>], range: i64) {
+        for x in 0..=range {
+            for y in 0..=range {
+                let point = [T::from_f64(x as f64), T::from_f64(y as f64)];
+                let feasible = lp.constraints.iter().all(|c| {
+                    let lhs = (c.terms.iter())
+                        .fold(T::zero(), |acc, (var, a)| acc + a.clone() * &point[var.0]);
+                    lhs <= c.rhs.clone() + T::eps()
+                });
+                for cut in cuts.iter().filter(|_| feasible) {
+                    let lhs = (cut.terms.iter())
+                        .fold(T::zero(), |acc, (var, a)| acc + a.clone() * &point[var.0]);
+                    assert!(
+                        lhs >= cut.rhs.clone() - num::<T>(1E-9),
+                        "{:?} {} {}",
+                        cut,
+                        x,
+                        y
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gomory_test_pure_integer() {
+        let lp = small::<Rational>();
+        let res = gomory_cuts(&lp, &CutOptions::default());
+        assert!(res.integral);
+        assert_eq!(res.solution.status, Status::Optimal);
+        assert_eq!(res.solution.objective, Rational::from_integer(20));
+        assert_eq!(
+            res.solution.x,
+            vec![Rational::from_integer(4), Rational::from_integer(0)]
+        );
+        assert!(res.rounds >= 1);
+        assert_eq!(res.program.constraints.len(), 2 + res.cuts.len());
+        assert_eq!(res.program.constraints[2].name, "gmi1");
+        check_valid(&lp, &res.cuts, 6);
+
+        let lp = small::<f64>();
+        let res = gomory_cuts(&lp, &CutOptions::default());
+        assert!(res.integral);
+        assert!((res.solution.objective - 20.0).abs() < 1E-6);
+        check_valid(&lp, &res.cuts, 6);
+        // The first round cuts the vertex (3, 1.5) off
+        let first = lp.solve(&Options::default());
+        let cuts = mixed_integer_cuts(&lp, &first, &num(1E-5));
+        assert!(!cuts.is_empty());
+        for cut in &cuts {
+            let lhs = (cut.terms.iter()).fold(0.0, |acc, (var, a)| acc + a * first.x[var.0]);
+            assert!(lhs < cut.rhs - 1E-6);
+            assert!(cut.efficacy > 0.0);
+        }
+
+        // Neither a solution without a table nor an infeasible one gives cuts
+        let options = Options {
+            algorithm: Algorithm::RevisedSimplex,
+            ..Options::default()
+        };
+        let revised = lp.solve(&options);
+        assert_eq!(revised.status, Status::Optimal);
+        assert!(mixed_integer_cuts(&lp, &revised, &num(1E-5)).is_empty());
+        let mut infeasible = lp.clone();
+        infeasible.add_constraint("none", &[(Variable(0), 1.0)], Sense::Ge, 100.0);
+        let res = infeasible.solve(&Options::default());
+        assert_eq!(res.status, Status::Infeasible);
+        assert!(mixed_integer_cuts(&infeasible, &res, &num(1E-5)).is_empty());
+    }
+
+    #[test]
+    fn gomory_test_limits_and_filter() {
+        let lp = small::<f64>();
+        let options = CutOptions {
+            max_rounds: 0,
+            ..CutOptions::default()
+        };
+        let res = gomory_cuts(&lp, &options);
+        assert_eq!((res.rounds, res.cuts.len(), res.integral), (0, 0, false));
+        assert!((res.solution.objective - 21.0).abs() < 1E-9);
+
+        let options = CutOptions {
+            min_efficacy: 10.0,
+            ..CutOptions::default()
+        };
+        assert!(gomory_cuts(&lp, &options).cuts.is_empty());
+
+        let options = CutOptions {
+            max_rounds: 1,
+            max_cuts: 1,
+            ..CutOptions::default()
+        };
+        let res = gomory_cuts(&lp, &options);
+        assert_eq!((res.rounds, res.cuts.len()), (1, 1));
+        // One cut tightens the bound without going past the whole optimum
+        assert!(res.solution.objective < 21.0 - 1E-6);
+        assert!(res.solution.objective >= 20.0 - 1E-9);
+    }
+
+    #[test]
+    fn gomory_test_mixed_integer() {
+        /*
+        A small scheduling model: x shifts of 8 hours and y shifts of 5 hours (whole, y <= 3)
+        plus z hours of overtime (continuous, at most 2.5) cover a demand of 21.5 hours
+        Objective function = 10x + 7y + 3z -> min
+        Constraints:
+        8x + 5y + z >= 21.5
+        x - y <= 1
+        x + y + z = 4.5, so z takes up the fractional part
+        */
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_integer_variable("x", 0.0, None);
+        let y = lp.add_integer_variable("y", 0.0, Some(3.0));
+        let z = lp.add_variable("z", 0.0, Some(2.5));
+        lp.add_constraint("hours", &[(x, 8.0), (y, 5.0), (z, 1.0)], Sense::Ge, 21.5);
+        lp.add_constraint("balance", &[(x, 1.0), (y, -1.0)], Sense::Le, 1.0);
+        lp.add_constraint("shifts", &[(x, 1.0), (y, 1.0), (z, 1.0)], Sense::Eq, 4.5);
+        lp.minimize(&[(x, 10.0), (y, 7.0), (z, 3.0)]);
+        let relaxation = lp.solve(&Options::default()).objective;
+        let whole = branch_and_bound(&lp, &MilpOptions::default());
+        assert_eq!(whole.status, Status::Optimal);
+
+        let res = gomory_cuts(&lp, &CutOptions::default());
+        assert!(!res.cuts.is_empty());
+        // The bound moves towards the whole optimum and never past it
+        assert!(res.solution.objective > relaxation + 1E-6);
+        assert!(res.solution.objective <= whole.objective + 1E-6);
+        // Cutting never changes the whole optimum
+        let strengthened = branch_and_bound(&res.program, &MilpOptions::default());
+        assert!((strengthened.objective - whole.objective).abs() < 1E-6);
+        if res.integral {
+            assert!((res.solution.objective - whole.objective).abs() < 1E-6);
+        }
+    }
+}
@@ -0,0 +1,701 @@
+/// Mehrotra's predictor-corrector primal-dual interior-point method.
+/// The problem is brought to the standard form min c^T x, A x = b, x >= 0 and every step
+/// solves the normal equations (A D A^T) dy = r with a Cholesky factorization.
+/// The predictor (affine) step tells how much centering the corrector step needs.
+/// The iterates stay inside, so the optimal point they reach is not a vertex in general:
+/// the crossover moves it to one with the revised simplex method.
+/// The method works with floats, other numbers are converted along the way
+/// (the crossover is exact though, as it only uses the interior point to pick the basis).
+/// [https://en.wikipedia.org/wiki/Interior-point_method]
+/// [https://en.wikipedia.org/wiki/Mehrotra_predictor%E2%80%93corrector_method]
+use super::farkas::certificate;
+use super::observer::{BarrierStep, Initialization, Phase, Termination};
+use super::revised::{Outcome, Revised};
+use super::sensitivity::Interval;
+use super::sparse::SparseMatrix;
+use super::{dot, Layout, Number, Options, Sense, Solution, Status};
+use nalgebra as na;
+
+/// The relative residuals and duality gap of an optimal point
+const TOLERANCE: f64 = 1E-9;
+/// The part of the way to the boundary a step goes
+const STEP_FACTOR: f64 = 0.99;
+/// The size of the objective values beyond which the problem is taken for infeasible or unbounded
+const DIVERGENCE: f64 = 1E8;
+/// The distance to a bound, relative to the bound, within which the crossover puts a variable at it
+const AT_BOUND: f64 = 1E-6;
+
+/// How a variable of the problem is made of the standard form ones: x = offset + sign * x'
+/// with an extra column x'' for the negative part of free ones, and an extra row
+/// x' + w = upper - lower for the ones bounded on both sides
+struct Column {
+    offset: f64,
+    sign: f64,
+    /// The column of x'
+    at: usize,
+    /// The column of the negative part of a free variable
+    negative: Option<usize>,
+    /// The width of the bounds
+    width: Option<f64>,
+}
+
+/// The outcome of the method in the standard form
+struct Iterate {
+    status: Status,
+    x: na::DVector<f64>,
+    y: na::DVector<f64>,
+}
+
+/// Solves the problem of `bounded_simplex` with the interior-point method.
+/// With `crossover`, the solution is an optimal vertex with a basis, as the one of
+/// `revised_simplex`. Without it, the solution is the interior point itself: `basis` is
+/// empty and the values are within the tolerance of the method.
+/// Infeasible and unbounded problems are told apart from the objective values that grow
+/// without limit, which is a heuristic: an unbounded one is checked with Phase I of the
+/// simplex method. Should the normal equations break down, the revised simplex method
+/// solves the problem from scratch instead. The table of the solution is empty.
+pub fn interior_point<T: Number>(
+    constr: na::DMatrix<T>,
+    senses: Vec<Sense>,
+    req: Vec<T>,
+    obj: Vec<T>,
+    bounds: &[Interval<T>],
+    crossover: bool,
+    options: &Options<T>,
+) -> Solution<T> {
+    options.observer.initialized(&Initialization {
+        method: "interior point",
+        rows: constr.nrows(),
+        columns: constr.ncols(),
+    });
+    let mut iterations = 0;
+    let solution = barrier(
+        constr,
+        senses,
+        req,
+        obj,
+        bounds,
+        crossover,
+        options,
+        &mut iterations,
+    );
+    options.observer.terminated(&Termination {
+        status: solution.status,
+        objective: solution.objective.clone(),
+        iterations,
+    });
+    solution
+}
+
+/// The body of `interior_point`, counting the steps and the pivots of the crossover
+#[allow(clippy::too_many_arguments)]
+fn barrier<T: Number>(
+    constr: na::DMatrix<T>,
+    senses: Vec<Sense>,
+    req: Vec<T>,
+    obj: Vec<T>,
+    bounds: &[Interval<T>],
+    crossover: bool,
+    options: &Options<T>,
+    iterations: &mut usize,
+) -> Solution<T> {
+    let (m, n) = (constr.nrows(), constr.ncols());
+    assert_eq!(m, senses.len(), "One sense per constraint");
+    assert_eq!(m, req.len(), "One requirement per constraint");
+    assert_eq!(n, obj.len(), "One coefficient per variable");
+    assert_eq!(n, bounds.len(), "One pair of bounds per variable");
+    let crossed = bounds.iter().any(|bound| match bound {
+        (Some(lower), Some(upper)) => upper < lower,
+        _ => false,
+    });
+
+    // The columns of the variables, then the slack variables and those of the bound rows
+    let mut columns = Vec::with_capacity(n);
+    let mut n_std = 0;
+    let mut n_bounded = 0;
+    for (lower, upper) in bounds {
+        let (lower, upper) = (lower.as_ref().map(T::to_f64), upper.as_ref().map(T::to_f64));
+        let (offset, sign) = match (lower, upper) {
+            (Some(lower), _) => (lower, 1.0),
+            (None, Some(upper)) => (upper, -1.0),
+            (None, None) => (0.0, 1.0),
+        };
+        let negative = match (lower, upper) {
+            (None, None) => Some(n_std + 1),
+            _ => None,
+        };
+        let width = match (lower, upper) {
+            (Some(lower), Some(upper)) => Some(upper - lower),
+            _ => None,
+        };
+        n_bounded += width.is_some() as usize;
+        columns.push(Column {
+            offset,
+            sign,
+            at: n_std,
+            negative,
+            width,
+        });
+        n_std += 1 + negative.is_some() as usize;
+    }
+    let n_slack = senses.iter().filter(|&&s| s != Sense::Eq).count();
+    let rows = m + n_bounded;
+    let cols = n_std + n_slack + n_bounded;
+
+    let mut a = na::DMatrix::<f64>::zeros(rows, cols);
+    let mut b = na::DVector::<f64>::zeros(rows);
+    let mut c = na::DVector::<f64>::zeros(cols);
+    for i in 0..m {
+        b[i] = req[i].to_f64();
+    }
+    let mut bound_row = m;
+    for (j, column) in columns.iter().enumerate() {
+        // The problem is maximized, the standard form is minimized
+        let cost = -obj[j].to_f64();
+        c[column.at] = cost * column.sign;
+        if let Some(neg) = column.negative {
+            c[neg] = -cost;
+        }
+        for i in 0..m {
+            let coef = constr[(i, j)].to_f64();
+            b[i] -= coef * column.offset;
+            a[(i, column.at)] = coef * column.sign;
+            if let Some(neg) = column.negative {
+                a[(i, neg)] = -coef;
+            }
+        }
+        if let Some(width) = column.width {
+            a[(bound_row, column.at)] = 1.0;
+            a[(bound_row, n_std + n_slack + bound_row - m)] = 1.0;
+            b[bound_row] = width;
+            bound_row += 1;
+        }
+    }
+    let mut slack_col = n_std;
+    for (i, sense) in senses.iter().enumerate() {
+        match sense {
+            Sense::Le => a[(i, slack_col)] = 1.0,
+            Sense::Ge => a[(i, slack_col)] = -1.0,
+            Sense::Eq => continue,
+        }
+        slack_col += 1;
+    }
+
+    let iterate = if crossed {
+        Iterate {
+            status: Status::Infeasible,
+            x: na::DVector::zeros(cols),
+            y: na::DVector::zeros(rows),
+        }
+    } else {
+        match mehrotra(&a, &b, &c, options, iterations) {
+            Some(iterate) => iterate,
+            // The normal equations broke down, the simplex method takes over
+            None => {
+                let constr = SparseMatrix::from_dense(&constr);
+                let mut revised = Revised::new(constr, senses, req, bounds);
+                let outcome = revised.optimize(&obj, options, iterations);
+                return revised.solution(outcome, &obj);
+            }
+        }
+    };
+    let x: Vec<T> = (columns.iter())
+        .map(|column| {
+            let mut value = column.offset + column.sign * iterate.x[column.at];
+            if let Some(neg) = column.negative {
+                value -= iterate.x[neg];
+            }
+            T::from_f64(value)
+        })
+        .collect();
+
+    if crossover && iterate.status == Status::Optimal {
+        return cross_over(constr, senses, req, obj, bounds, &x, options, iterations);
+    }
+
+    // An unbounded problem needs a feasible point, Phase I of the simplex method tells.
+    // It also finds the certificate of infeasibility, which the method itself doesn't.
+    let farkas = match iterate.status {
+        Status::Infeasible | Status::Unbounded => certificate(
+            SparseMatrix::from_dense(&constr),
+            senses.clone(),
+            req.clone(),
+            bounds,
+        ),
+        _ => None,
+    };
+    let status = match iterate.status {
+        Status::Unbounded if farkas.is_some() => Status::Infeasible,
+        status => status,
+    };
+    let activity: Vec<T> = (0..m)
+        .map(|i| (0..n).fold(T::zero(), |acc, j| acc + constr[(i, j)].clone() * &x[j]))
+        .collect();
+    // The slack of a `>=` constraint is its surplus
+    let slack = (senses.iter().zip(req.iter().zip(activity)))
+        .map(|(sense, (b, ax))| match sense {
+            Sense::Ge => ax - b,
+            _ => b.clone() - ax,
+        })
+        .collect();
+    let (duals, reduced_costs) = if status == Status::Optimal {
+        let duals: Vec<T> = (0..m).map(|i| T::from_f64(-iterate.y[i])).collect();
+        let reduced_costs = (0..n)
+            .map(|j| {
+                (0..m).fold(obj[j].clone(), |acc, i| {
+                    acc - duals[i].clone() * &constr[(i, j)]
+                })
+            })
+            .collect();
+        (duals, reduced_costs)
+    } else {
+        (Vec::new(), Vec::new())
+    };
+    Solution {
+        status,
+        objective: dot(&obj, &x),
+        x,
+        slack,
+        basis: Vec::new(),
+        ray: None,
+        duals,
+        reduced_costs,
+        farkas,
+        table: na::DMatrix::zeros(0, 0),
+        layout: Layout::new(bounds, n + m + 1),
+    }
+}
+
+/// The iterations of the method on min c^T x, A x = b, x >= 0,
+/// `None` if the normal equations can't be factorized
+fn mehrotra<T: Number>(
+    a: &na::DMatrix<f64>,
+    b: &na::DVector<f64>,
+    c: &na::DVector<f64>,
+    options: &Options<T>,
+    iterations: &mut usize,
+) -> Option<Iterate> {
+    let (rows, cols) = a.shape();
+    let at = a.transpose();
+    let scale = 1.0 + b.amax().max(c.amax());
+
+    // Mehrotra's starting point: the least squares solutions moved inside
+    let start = regularized_cholesky(a * &at);
+    let (mut x, mut y) = match &start {
+        Some(chol) => (&at * chol.solve(b), chol.solve(&(a * c))),
+        None => (na::DVector::zeros(cols), na::DVector::zeros(rows)),
+    };
+    let mut s = c - &at * &y;
+    let shift = |v: &mut na::DVector<f64>| {
+        let low = v.iter().cloned().fold(f64::INFINITY, f64::min);
+        let by = (-1.5 * low).max(0.0);
+        v.add_scalar_mut(by);
+    };
+    shift(&mut x);
+    shift(&mut s);
+    let xs = x.dot(&s);
+    let (sum_x, sum_s) = (x.sum(), s.sum());
+    if sum_x > 0.0 && sum_s > 0.0 && xs > 0.0 {
+        x.add_scalar_mut(0.5 * xs / sum_s);
+        s.add_scalar_mut(0.5 * xs / sum_x);
+    } else {
+        x.fill(1.0);
+        s.fill(1.0);
+    }
+
+    for iteration in 0..=options.max_iterations {
+        let r_b = a * &x - b;
+        let r_c = &at * &y + &s - c;
+        let mu = if cols > 0 {
+            x.dot(&s) / cols as f64
+        } else {
+            0.0
+        };
+        let gap = (c.dot(&x) - b.dot(&y)).abs() / (1.0 + c.dot(&x).abs());
+        options.observer.stepped(&BarrierStep {
+            iteration,
+            primal_residual: r_b.amax(),
+            dual_residual: r_c.amax(),
+            gap,
+        });
+        if r_b.norm() / (1.0 + b.norm()) < TOLERANCE
+            && r_c.norm() / (1.0 + c.norm()) < TOLERANCE
+            && gap < TOLERANCE
+        {
+            return Some(Iterate {
+                status: Status::Optimal,
+                x,
+                y,
+            });
+        }
+        // A primal objective falling forever follows a ray, a dual one growing forever
+        // proves there is no point (the parts of a free variable may grow together,
+        // so the sizes of the points tell nothing)
+        if c.dot(&x) < -DIVERGENCE * scale {
+            return Some(Iterate {
+                status: Status::Unbounded,
+                x,
+                y,
+            });
+        }
+        if b.dot(&y) > DIVERGENCE * scale {
+            return Some(Iterate {
+                status: Status::Infeasible,
+                x,
+                y,
+            });
+        }
+        if iteration == options.max_iterations {
+            break;
+        }
+
+        let d = x.component_div(&s);
+        let mut ad = a.clone();
+        for (j, mut column) in ad.column_iter_mut().enumerate() {
+            column *= d[j];
+        }
+        let chol = regularized_cholesky(&ad * &at)?;
+        // The Newton direction with r_xs on the right-hand side of S dx + X ds = r_xs
+        let direction = |r_xs: &na::DVector<f64>| {
+            let rhs = -&r_b - a * (r_xs.component_div(&s) + d.component_mul(&r_c));
+            let dy = chol.solve(&rhs);
+            let ds = -&r_c - &at * &dy;
+            let dx = (r_xs - x.component_mul(&ds)).component_div(&s);
+            (dx, dy, ds)
+        };
+
+        // The predictor aims straight for the optimum
+        let (dx_aff, _, ds_aff) = direction(&-x.component_mul(&s));
+        let (alpha_p, alpha_d) = (max_step(&x, &dx_aff), max_step(&s, &ds_aff));
+        let sigma = if cols > 0 && mu > 0.0 {
+            let mu_aff = (&x + &dx_aff * alpha_p).dot(&(&s + &ds_aff * alpha_d)) / cols as f64;
+            (mu_aff / mu).powi(3)
+        } else {
+            0.0
+        };
+        // The corrector makes up for the second order term and keeps to the central path
+        let r_xs = (-x.component_mul(&s) - dx_aff.component_mul(&ds_aff)).add_scalar(sigma * mu);
+        let (dx, dy, ds) = direction(&r_xs);
+        let alpha_p = (STEP_FACTOR * max_step(&x, &dx)).min(1.0);
+        let alpha_d = (STEP_FACTOR * max_step(&s, &ds)).min(1.0);
+        x += dx * alpha_p;
+        y += dy * alpha_d;
+        s += ds * alpha_d;
+        *iterations += 1;
+    }
+    Some(Iterate {
+        status: Status::IterationLimit,
+        x,
+        y,
+    })
+}
+
+/// The longest step along `dv` keeping `v` non-negative, infinite if nothing stops it
+fn max_step(v: &na::DVector<f64>, dv: &na::DVector<f64>) -> f64 {
+    (v.iter().zip(dv.iter()))
+        .filter(|(_, d)| **d < 0.0)
+        .map(|(v, d)| -v / d)
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// The Cholesky factorization of a matrix that may be singular (e.g. the constraints repeat),
+/// with a growing multiple of the identity added until it goes through
+fn regularized_cholesky(mut matrix: na::DMatrix<f64>) -> Option<na::Cholesky<f64, na::Dynamic>> {
+    let size = matrix.nrows();
+    let diagonal = matrix.diagonal().amax().max(1.0);
+    let mut delta = 1E-14 * diagonal;
+    for i in 0..size {
+        matrix[(i, i)] += delta;
+    }
+    for _ in 0..8 {
+        if let Some(chol) = na::Cholesky::new(matrix.clone()) {
+            return Some(chol);
+        }
+        for i in 0..size {
+            matrix[(i, i)] += 99.0 * delta;
+        }
+        delta *= 100.0;
+    }
+    None
+}
+
+/// Moves the interior point to a vertex: the variables at their bounds are held there,
+/// the revised simplex method picks a basis among the rest and then releases the others
+/// (which usually takes a few pivots). Should the held variables be too many,
+/// it solves the problem from scratch.
+#[allow(clippy::too_many_arguments)]
+fn cross_over<T: Number>(
+    constr: na::DMatrix<T>,
+    senses: Vec<Sense>,
+    req: Vec<T>,
+    obj: Vec<T>,
+    bounds: &[Interval<T>],
+    x: &[T],
+    options: &Options<T>,
+    iterations: &mut usize,
+) -> Solution<T> {
+    let near = |value: &T, bound: &T| {
+        (value.clone() - bound).to_f64().abs() <= AT_BOUND * (1.0 + bound.to_f64().abs())
+    };
+    let held: Vec<Interval<T>> = (bounds.iter().zip(x))
+        .map(|((lower, upper), value)| {
+            let at = (lower.iter().chain(upper)).find(|bound| near(value, bound));
+            match at {
+                Some(bound) => (Some(bound.clone()), Some(bound.clone())),
+                None => (lower.clone(), upper.clone()),
+            }
+        })
+        .collect();
+    let constr = SparseMatrix::from_dense(&constr);
+    let mut revised = Revised::new(constr.clone(), senses.clone(), req.clone(), &held);
+    let outcome = match revised.optimize(&obj, options, iterations) {
+        Outcome::Optimal => {
+            for (j, (lower, upper)) in bounds.iter().enumerate() {
+                revised.lower[j] = lower.clone();
+                revised.upper[j] = upper.clone();
+            }
+            let cost = revised.cost(&obj);
+            revised.run(&cost, Phase::Two, options, iterations)
+        }
+        _ => {
+            revised = Revised::new(constr, senses, req, bounds);
+            revised.optimize(&obj, options, iterations)
+        }
+    };
+    revised.solution(outcome, &obj)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::table_problems;
+    use super::super::{bounded_simplex, revised_simplex, simplex_method, Rational, Silent};
+    use super::*;
+
+    #[test]
+    fn interior_test_problems() {
+        for (constraints, req, obj) in table_problems() {
+            let (rows, cols) = constraints.shape();
+            let expected = simplex_method(constraints.clone(), req.clone(), obj.clone(), &Silent);
+            for &crossover in &[false, true] {
+                let res = interior_point(
+                    constraints.clone(),
+                    vec![Sense::Le; rows],
+                    req.clone(),
+                    obj.clone(),
+                    &vec![(Some(0.0), None); cols],
+                    crossover,
+                    &Options::default(),
+                );
+                assert_eq!(res.status, Status::Optimal);
+                assert!(
+                    (res.objective - expected.objective).abs() < 1E-6 * expected.objective,
+                    "The expected optimal value: {}, the value we got: {}",
+                    expected.objective,
+                    res.objective
+                );
+                for (a, b) in res.duals.iter().zip(&expected.duals) {
+                    assert!((a - b).abs() < 1E-4 * (1.0 + b.abs()), "{:?}", res.duals);
+                }
+                assert_eq!(res.basis.len(), if crossover { rows } else { 0 });
+            }
+        }
+    }
+
+    #[test]
+    fn interior_test_bounds_and_senses() {
+        /*
+        Objective function = 3x + 2y - z - w
+        Constraints:
+        x + y - z <= 3
+        x - w >= -2
+        y + w = 4
+        -1 <= x <= 1, 0 <= y <= 5, z free, w <= 3
+        */
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::<f64>::from_row_slice(3, 4, &[
+            1.0, 1.0, -1.0, 0.0,
+            1.0, 0.0, 0.0, -1.0,
+            0.0, 1.0, 0.0, 1.0,
+        ]);
+        let senses = vec![Sense::Le, Sense::Ge, Sense::Eq];
+        let req = vec![3.0, -2.0, 4.0];
+        let obj = vec![3.0, 2.0, -1.0, -1.0];
+        let bounds = vec![
+            (Some(-1.0), Some(1.0)),
+            (Some(0.0), Some(5.0)),
+            (None, None),
+            (None, Some(3.0)),
+        ];
+        let expected = bounded_simplex(
+            constraints.clone(),
+            senses.clone(),
+            req.clone(),
+            obj.clone(),
+            &bounds,
+            &Options::default(),
+        );
+        for &crossover in &[false, true] {
+            let res = interior_point(
+                constraints.clone(),
+                senses.clone(),
+                req.clone(),
+                obj.clone(),
+                &bounds,
+                crossover,
+                &Options::default(),
+            );
+            assert_eq!(res.status, Status::Optimal);
+            assert!(
+                (res.objective - expected.objective).abs() < 1E-6,
+                "{} {}",
+                res.objective,
+                expected.objective
+            );
+            for (a, b) in res.slack.iter().zip(&expected.slack) {
+                assert!((a - b).abs() < 1E-5, "{:?} {:?}", res.slack, expected.slack);
+            }
+            for (a, b) in res.duals.iter().zip(&expected.duals) {
+                assert!((a - b).abs() < 1E-5, "{:?} {:?}", res.duals, expected.duals);
+            }
+            for (a, b) in res.reduced_costs.iter().zip(&expected.reduced_costs) {
+                assert!((a - b).abs() < 1E-5, "{:?}", res.reduced_costs);
+            }
+        }
+    }
+
+    #[test]
+    fn interior_test_crossover_vertex() {
+        /*
+        Objective function = x + y
+        Constraints:
+        x + y <= 4
+        x, y <= 3
+        Every point between (1, 3) and (3, 1) is optimal,
+        the interior point lies in between and the crossover picks an end.
+        */
+        let constraints = na::DMatrix::<f64>::from_row_slice(1, 2, &[1.0, 1.0]);
+        let bounds = vec![(Some(0.0), Some(3.0)); 2];
+        let solve = |crossover| {
+            interior_point(
+                constraints.clone(),
+                vec![Sense::Le],
+                vec![4.0],
+                vec![1.0, 1.0],
+                &bounds,
+                crossover,
+                &Options::default(),
+            )
+        };
+        let inside = solve(false);
+        assert!((inside.objective - 4.0).abs() < 1E-6);
+        assert!((inside.x[0] - 2.0).abs() < 1E-3, "{:?}", inside.x);
+
+        let vertex = solve(true);
+        assert!((vertex.objective - 4.0).abs() < 1E-9);
+        let mut x = vertex.x.clone();
+        x.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!(
+            (x[0] - 1.0).abs() < 1E-9 && (x[1] - 3.0).abs() < 1E-9,
+            "{:?}",
+            x
+        );
+    }
+
+    #[test]
+    fn interior_test_infeasible_and_unbounded() {
+        let constraints = na::DMatrix::<f64>::from_row_slice(2, 2, &[1.0, 1.0, 1.0, 1.0]);
+        let res = interior_point(
+            constraints,
+            vec![Sense::Le, Sense::Ge],
+            vec![1.0, 2.0],
+            vec![1.0, 1.0],
+            &[(Some(0.0), None); 2],
+            false,
+            &Options::default(),
+        );
+        assert_eq!(res.status, Status::Infeasible);
+
+        let constraints = na::DMatrix::<f64>::from_row_slice(1, 2, &[1.0, -1.0]);
+        let res = interior_point(
+            constraints,
+            vec![Sense::Le],
+            vec![1.0],
+            vec![1.0, 0.0],
+            &[(Some(0.0), None); 2],
+            true,
+            &Options::default(),
+        );
+        assert_eq!(res.status, Status::Unbounded);
+    }
+
+    #[test]
+    fn interior_test_dense() {
+        // A dense problem with 30 rows and 60 columns, every entry non-zero
+        let (rows, cols) = (30, 60);
+        let constraints =
+            na::DMatrix::<f64>::from_fn(rows, cols, |i, j| ((i * 31 + j * 17) % 23 + 1) as f64);
+        let req: Vec<f64> = (0..rows).map(|i| (100 + 10 * (i % 7)) as f64).collect();
+        let obj: Vec<f64> = (0..cols).map(|j| ((j * 11) % 13 + 1) as f64).collect();
+        let bounds = vec![(Some(0.0), None); cols];
+        let expected = bounded_simplex(
+            constraints.clone(),
+            vec![Sense::Le; rows],
+            req.clone(),
+            obj.clone(),
+            &bounds,
+            &Options::default(),
+        );
+        for &crossover in &[false, true] {
+            let res = interior_point(
+                constraints.clone(),
+                vec![Sense::Le; rows],
+                req.clone(),
+                obj.clone(),
+                &bounds,
+                crossover,
+                &Options::default(),
+            );
+            assert_eq!(res.status, Status::Optimal);
+            assert!(
+                (res.objective - expected.objective).abs() < 1E-6 * expected.objective,
+                "{} {}",
+                res.objective,
+                expected.objective
+            );
+        }
+    }
+
+    #[test]
+    fn interior_test_exact_crossover() {
+        let r = |n: i64| Rational::from_integer(n);
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(3, 3, &[
+            r(20), r(15), r(20),
+            r(27), r(35), r(50),
+            r(10), r(5), r(10),
+        ]);
+        let req = vec![r(550), r(900), r(550)];
+        let obj = vec![r(35), r(45), r(55)];
+        let bounds = vec![(Some(r(0)), None); 3];
+        let res = interior_point(
+            constraints.clone(),
+            vec![Sense::Le; 3],
+            req.clone(),
+            obj.clone(),
+            &bounds,
+            true,
+            &Options::default(),
+        );
+        let exact = revised_simplex(
+            SparseMatrix::from_dense(&constraints),
+            vec![Sense::Le; 3],
+            req,
+            obj,
+            &bounds,
+            &Options::default(),
+        );
+        // The crossover lands on the exact optimal vertex: 68600 / 59
+        assert_eq!(res.objective, exact.objective);
+        assert_eq!(res.objective, Rational::new(68600, 59));
+    }
+}
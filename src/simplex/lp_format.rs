@@ -0,0 +1,749 @@
+/// Reading and writing linear programs in the CPLEX LP format, the one meant for people:
+/// `Maximize obj: 7x + 5y Subject To c1: 2x + 3y <= 90 ... End`.
+/// [https://www.ibm.com/docs/en/icos/22.1.0?topic=cplex-lp-file-format-algebraic-representation]
+use super::{Direction, LinearProgram, Number, ParseError, Sense, Variable};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    Number(f64),
+    Name(String),
+    Colon,
+    Plus,
+    Minus,
+    Compare(Sense),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Token {
+    kind: Kind,
+    line: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Objective(Direction),
+    Constraints,
+    Bounds,
+    General,
+    Binary,
+    End,
+}
+
+/// Reads a linear program written in the LP format.
+/// Variables are numbered in the order they first appear in. Constraints without
+/// a name are called `R<number of the constraint>`. Free variables are declared
+/// in the Bounds section as `x free`, or given a lower bound of `-inf`. Quadratic terms
+/// are not supported.
+pub fn read_lp<T: Number>(text: &str) -> Result<LinearProgram<T>, ParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        pos: 0,
+        lp: LinearProgram::new(),
+        variables: HashMap::new(),
+    };
+    match parser.section() {
+        Some((Section::Objective(_), _)) => {}
+        _ => return Err(parser.error("Expected Maximize or Minimize")),
+    }
+    while let Some((section, length)) = parser.section() {
+        parser.pos += length;
+        match section {
+            Section::Objective(direction) => {
+                parser.lp.direction = direction;
+                parser.label();
+                let (terms, constant) = parser.expression()?;
+                parser.lp.constant = T::from_f64(constant);
+                for (var, coef) in terms {
+                    parser.lp.objective[var.0] += coef;
+                }
+            }
+            Section::Constraints => {
+                while parser.pos < parser.tokens.len() && parser.section().is_none() {
+                    parser.constraint()?;
+                }
+            }
+            Section::Bounds => {
+                while parser.pos < parser.tokens.len() && parser.section().is_none() {
+                    parser.bound()?;
+                }
+            }
+            Section::General | Section::Binary => {
+                while parser.pos < parser.tokens.len() && parser.section().is_none() {
+                    let var = match parser.next() {
+                        Some(Kind::Name(name)) => parser.variable(&name),
+                        _ => return Err(parser.error_before("Expected the name of a variable")),
+                    };
+                    parser.lp.set_integer(var, true);
+                    if section == Section::Binary {
+                        parser.lp.variables[var.0].lower = Some(T::zero());
+                        parser.lp.variables[var.0].upper = Some(T::one());
+                    }
+                }
+            }
+            Section::End => {
+                if parser.pos < parser.tokens.len() {
+                    return Err(parser.error("Nothing may follow End"));
+                }
+                return Ok(parser.lp);
+            }
+        }
+    }
+    if parser.pos < parser.tokens.len() {
+        Err(parser.error("Expected a section"))
+    } else {
+        Err(parser.error_before("Expected End"))
+    }
+}
+
+/// The characters names are made of, besides letters and digits
+const NAME_SYMBOLS: &str = "!\"#$%&()/,.;?@_`'{}|~[]";
+
+fn tokenize(text: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        // Comments run from a backslash to the end of the line
+        let line = line.split('\\').next().unwrap_or("");
+        let chars: Vec<char> = line.chars().collect();
+        let mut k = 0;
+        while k < chars.len() {
+            let c = chars[k];
+            let start = k;
+            k += 1;
+            let kind = match c {
+                _ if c.is_whitespace() => continue,
+                ':' => Kind::Colon,
+                '+' => Kind::Plus,
+                '-' => Kind::Minus,
+                '<' | '>' | '=' => {
+                    if k < chars.len() && "<>=".contains(chars[k]) {
+                        k += 1;
+                    }
+                    let sense = match chars[start..k].iter().collect::<String>().as_str() {
+                        "<" | "<=" | "=<" => Sense::Le,
+                        ">" | ">=" | "=>" => Sense::Ge,
+                        "=" | "==" => Sense::Eq,
+                        other => {
+                            return Err(ParseError::new(i + 1, format!("Unknown sense {}", other)))
+                        }
+                    };
+                    Kind::Compare(sense)
+                }
+                _ if c.is_ascii_digit()
+                    || c == '.' && chars.get(k).is_some_and(char::is_ascii_digit) =>
+                {
+                    while k < chars.len() && (chars[k].is_ascii_digit() || chars[k] == '.') {
+                        k += 1;
+                    }
+                    // An exponent needs digits, otherwise the `e` starts a name
+                    if k < chars.len() && (chars[k] == 'e' || chars[k] == 'E') {
+                        let mut e = k + 1;
+                        if e < chars.len() && (chars[e] == '+' || chars[e] == '-') {
+                            e += 1;
+                        }
+                        if e < chars.len() && chars[e].is_ascii_digit() {
+                            k = e;
+                            while k < chars.len() && chars[k].is_ascii_digit() {
+                                k += 1;
+                            }
+                        }
+                    }
+                    let number: String = chars[start..k].iter().collect();
+                    match number.parse() {
+                        Ok(x) => Kind::Number(x),
+                        Err(_) => {
+                            return Err(ParseError::new(
+                                i + 1,
+                                format!("Invalid number {}", number),
+                            ))
+                        }
+                    }
+                }
+                _ if c.is_alphanumeric() || NAME_SYMBOLS.contains(c) => {
+                    while k < chars.len()
+                        && (chars[k].is_alphanumeric() || NAME_SYMBOLS.contains(chars[k]))
+                    {
+                        k += 1;
+                    }
+                    Kind::Name(chars[start..k].iter().collect())
+                }
+                _ => {
+                    return Err(ParseError::new(
+                        i + 1,
+                        format!("Unexpected character {}", c),
+                    ))
+                }
+            };
+            tokens.push(Token { kind, line: i + 1 });
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<T: Number> {
+    tokens: Vec<Token>,
+    pos: usize,
+    lp: LinearProgram<T>,
+    variables: HashMap<String, Variable>,
+}
+
+impl<T: Number> Parser<T> {
+    fn peek(&self) -> Option<&Kind> {
+        self.tokens.get(self.pos).map(|t| &t.kind)
+    }
+
+    fn next(&mut self) -> Option<Kind> {
+        let kind = self.tokens.get(self.pos).map(|t| t.kind.clone());
+        self.pos += 1;
+        kind
+    }
+
+    /// An error at the current token
+    fn error(&self, message: &str) -> ParseError {
+        let line = match self.tokens.get(self.pos) {
+            Some(token) => token.line,
+            None => self.tokens.last().map_or(1, |t| t.line),
+        };
+        ParseError::new(line, message)
+    }
+
+    /// An error at the token just taken
+    fn error_before(&self, message: &str) -> ParseError {
+        let pos = self.pos.min(self.tokens.len()).saturating_sub(1);
+        ParseError::new(self.tokens.get(pos).map_or(1, |t| t.line), message)
+    }
+
+    /// The section keyword at the current token and the number of its words
+    fn section(&self) -> Option<(Section, usize)> {
+        let word = |pos: usize| match self.tokens.get(pos).map(|t| &t.kind) {
+            Some(Kind::Name(name)) => Some(name.to_lowercase()),
+            _ => None,
+        };
+        // A name followed by a colon is a label, whatever it reads
+        if self.tokens.get(self.pos + 1).map(|t| &t.kind) == Some(&Kind::Colon) {
+            return None;
+        }
+        let section = match (word(self.pos)?.as_str(), word(self.pos + 1).as_deref()) {
+            ("maximize", _) | ("maximise", _) | ("maximum", _) | ("max", _) => {
+                (Section::Objective(Direction::Maximize), 1)
+            }
+            ("minimize", _) | ("minimise", _) | ("minimum", _) | ("min", _) => {
+                (Section::Objective(Direction::Minimize), 1)
+            }
+            ("subject", Some("to")) | ("such", Some("that")) => (Section::Constraints, 2),
+            ("st", _) | ("s.t.", _) | ("st.", _) => (Section::Constraints, 1),
+            ("bounds", _) | ("bound", _) => (Section::Bounds, 1),
+            ("general", _) | ("generals", _) | ("gen", _) => (Section::General, 1),
+            ("binary", _) | ("binaries", _) | ("bin", _) => (Section::Binary, 1),
+            ("end", _) => (Section::End, 1),
+            _ => return None,
+        };
+        Some(section)
+    }
+
+    /// Takes the `name:` in front of an objective function or a constraint
+    fn label(&mut self) -> Option<String> {
+        match (self.peek(), self.tokens.get(self.pos + 1).map(|t| &t.kind)) {
+            (Some(Kind::Name(name)), Some(Kind::Colon)) => {
+                let name = name.clone();
+                self.pos += 2;
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    fn variable(&mut self, name: &str) -> Variable {
+        if let Some(&var) = self.variables.get(name) {
+            return var;
+        }
+        let var = self.lp.add_variable(name, T::zero(), None);
+        self.variables.insert(name.to_string(), var);
+        var
+    }
+
+    /// Reads terms like `+ 2.5 x` up to anything that can't continue the sum.
+    /// Returns the terms and the sum of the constants.
+    fn expression(&mut self) -> Result<(Vec<(Variable, T)>, f64), ParseError> {
+        let mut terms = Vec::new();
+        let mut constant = 0.0;
+        for first in (0..).map(|i| i == 0) {
+            let mut sign = 1.0;
+            let mut signed = false;
+            while let Some(kind) = self.peek() {
+                match kind {
+                    Kind::Plus => {}
+                    Kind::Minus => sign = -sign,
+                    _ => break,
+                }
+                signed = true;
+                self.pos += 1;
+            }
+            // Every term but the first one starts with a sign
+            if !signed && !first {
+                break;
+            }
+            let coef = match self.peek() {
+                Some(&Kind::Number(x)) => {
+                    self.pos += 1;
+                    Some(x)
+                }
+                _ => None,
+            };
+            let is_variable = match self.peek() {
+                Some(Kind::Name(_)) => self.section().is_none() && self.label_ahead().is_none(),
+                _ => false,
+            };
+            match (coef, is_variable) {
+                (coef, true) => {
+                    let name = match self.next() {
+                        Some(Kind::Name(name)) => name,
+                        _ => unreachable!("Checked to be a name"),
+                    };
+                    let var = self.variable(&name);
+                    terms.push((var, T::from_f64(sign * coef.unwrap_or(1.0))));
+                }
+                (Some(x), false) => constant += sign * x,
+                (None, false) if signed => return Err(self.error("Expected a term")),
+                (None, false) => break,
+            }
+        }
+        Ok((terms, constant))
+    }
+
+    /// Whether a label starts at the current token
+    fn label_ahead(&self) -> Option<()> {
+        match self.tokens.get(self.pos + 1).map(|t| &t.kind) {
+            Some(Kind::Colon) => Some(()),
+            _ => None,
+        }
+    }
+
+    /// `[name:] terms sense [sign] number`, where no terms at all stand for 0
+    fn constraint(&mut self) -> Result<(), ParseError> {
+        let name = self
+            .label()
+            .unwrap_or_else(|| format!("R{}", self.lp.constraints.len() + 1));
+        let (terms, constant) = self.expression()?;
+        let sense = match self.next() {
+            Some(Kind::Compare(sense)) => sense,
+            _ => return Err(self.error_before("Expected <=, >= or =")),
+        };
+        let rhs = match self.value()? {
+            Some(x) if x.is_finite() => x,
+            _ => return Err(self.error_before("Expected the right-hand side of the constraint")),
+        };
+        // Constants on the left-hand side move over to the right
+        self.lp
+            .add_constraint(&name, &terms, sense, T::from_f64(rhs - constant));
+        Ok(())
+    }
+
+    /// A signed number or infinity, `None` if there is no number at all
+    fn value(&mut self) -> Result<Option<f64>, ParseError> {
+        let start = self.pos;
+        let mut sign = 1.0;
+        while let Some(kind) = self.peek() {
+            match kind {
+                Kind::Plus => {}
+                Kind::Minus => sign = -sign,
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        let value = match self.peek() {
+            Some(&Kind::Number(x)) => x,
+            Some(Kind::Name(name))
+                if name.eq_ignore_ascii_case("inf") || name.eq_ignore_ascii_case("infinity") =>
+            {
+                f64::INFINITY
+            }
+            _ if self.pos == start => return Ok(None),
+            _ => return Err(self.error("Expected a number")),
+        };
+        self.pos += 1;
+        Ok(Some(sign * value))
+    }
+
+    /// `x <= 4`, `x >= 1`, `x = 2`, `1 <= x <= 4` or `x free`
+    fn bound(&mut self) -> Result<(), ParseError> {
+        let lower = match self.value()? {
+            Some(value) => match self.next() {
+                Some(Kind::Compare(Sense::Le)) => Some((value, Sense::Ge)),
+                Some(Kind::Compare(Sense::Ge)) => Some((value, Sense::Le)),
+                Some(Kind::Compare(Sense::Eq)) => Some((value, Sense::Eq)),
+                _ => return Err(self.error_before("Expected <=, >= or =")),
+            },
+            None => None,
+        };
+        let var = match self.next() {
+            Some(Kind::Name(name)) => self.variable(&name),
+            _ => return Err(self.error_before("Expected the name of a variable")),
+        };
+        let mut bounds: Vec<(f64, Sense)> = lower.into_iter().collect();
+        match self.peek() {
+            Some(&Kind::Compare(sense)) => {
+                self.pos += 1;
+                match self.value()? {
+                    Some(value) => bounds.push((value, sense)),
+                    None => return Err(self.error("Expected a number")),
+                }
+            }
+            Some(Kind::Name(word)) if bounds.is_empty() && word.eq_ignore_ascii_case("free") => {
+                self.pos += 1;
+                self.lp.set_bounds(var, None, None);
+                return Ok(());
+            }
+            _ if bounds.is_empty() => return Err(self.error("Expected a bound")),
+            _ => {}
+        }
+
+        let line = self.tokens[self.pos - 1].line;
+        let data = &mut self.lp.variables[var.0];
+        for (value, sense) in bounds {
+            if value.is_infinite() && (sense == Sense::Eq || (sense == Sense::Le) != (value > 0.0))
+            {
+                return Err(ParseError::new(
+                    line,
+                    format!("Infeasible bound of {}", data.name),
+                ));
+            }
+            let value = Some(value).filter(|v| v.is_finite()).map(T::from_f64);
+            match sense {
+                Sense::Le => data.upper = value,
+                Sense::Ge => data.lower = value,
+                Sense::Eq => {
+                    data.lower = value.clone();
+                    data.upper = value;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes the program down in the LP format.
+/// Every variable appears in the objective function, even with a zero coefficient,
+/// so reading the text back keeps their order.
+pub fn write_lp<T: Number>(lp: &LinearProgram<T>) -> String {
+    let mut out = String::new();
+    out.push_str(match lp.direction {
+        Direction::Maximize => "Maximize\n",
+        Direction::Minimize => "Minimize\n",
+    });
+    let objective: Vec<(usize, &T)> = lp.objective.iter().enumerate().collect();
+    let mut words = sum(lp, &objective, true);
+    if !lp.constant.is_zero() {
+        let constant = lp.constant.to_f64();
+        if !words.is_empty() || constant < 0.0 {
+            words.push(if constant < 0.0 { "-" } else { "+" }.to_string());
+        }
+        words.push(number(constant.abs()));
+    }
+    out.push_str(&wrap(" obj:", &words));
+
+    out.push_str("Subject To\n");
+    for c in &lp.constraints {
+        let terms: Vec<(usize, &T)> = c.terms.iter().map(|(v, coef)| (v.0, coef)).collect();
+        let mut words = sum(lp, &terms, false);
+        words.push(
+            match c.sense {
+                Sense::Le => "<=",
+                Sense::Ge => ">=",
+                Sense::Eq => "=",
+            }
+            .to_string(),
+        );
+        words.push(number(c.rhs.to_f64()));
+        out.push_str(&wrap(&format!(" {}:", c.name), &words));
+    }
+
+    let binary = |v: &super::model::VariableData<T>| {
+        v.integer
+            && v.lower.as_ref().is_some_and(|l| l.is_zero())
+            && v.upper.as_ref().is_some_and(|u| u.is_one())
+    };
+    let at_zero = |v: &super::model::VariableData<T>| {
+        v.lower.as_ref().is_some_and(|l| l.is_zero()) && v.upper.is_none()
+    };
+    let bounded: Vec<_> = (lp.variables.iter())
+        .filter(|v| !binary(v) && !at_zero(v))
+        .collect();
+    if !bounded.is_empty() {
+        out.push_str("Bounds\n");
+        for v in bounded {
+            let lower = v
+                .lower
+                .as_ref()
+                .map_or("-inf".to_string(), |l| number(l.to_f64()));
+            let line = match (&v.lower, &v.upper) {
+                (None, None) => format!(" {} free\n", v.name),
+                (Some(l), Some(upper)) if upper == l => format!(" {} = {}\n", v.name, lower),
+                (Some(l), Some(upper)) if l.is_zero() => {
+                    format!(" {} <= {}\n", v.name, number(upper.to_f64()))
+                }
+                (_, Some(upper)) => {
+                    format!(" {} <= {} <= {}\n", lower, v.name, number(upper.to_f64()))
+                }
+                (_, None) => format!(" {} >= {}\n", v.name, lower),
+            };
+            out.push_str(&line);
+        }
+    }
+    for (title, binaries) in &[("Generals", false), ("Binaries", true)] {
+        let names: Vec<String> = (lp.variables.iter())
+            .filter(|v| v.integer && binary(v) == *binaries)
+            .map(|v| v.name.clone())
+            .collect();
+        if !names.is_empty() {
+            out.push_str(title);
+            out.push('\n');
+            out.push_str(&wrap("", &names));
+        }
+    }
+    out.push_str("End\n");
+    out
+}
+
+impl<T: Number> fmt::Display for LinearProgram<T> {
+    /// The program in the LP format
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&write_lp(self))
+    }
+}
+
+/// The words of `7 x + 5 y`, skipping the zeros unless asked to keep them
+fn sum<T: Number>(lp: &LinearProgram<T>, terms: &[(usize, &T)], zeros: bool) -> Vec<String> {
+    let mut words = Vec::new();
+    for &(j, coef) in terms.iter().filter(|(_, c)| zeros || !c.is_zero()) {
+        let coef = coef.to_f64();
+        let name = lp.variables[j].name.clone();
+        if !words.is_empty() || coef < 0.0 {
+            words.push(if coef < 0.0 { "-" } else { "+" }.to_string());
+        }
+        if coef.abs() != 1.0 {
+            words.push(number(coef.abs()));
+        }
+        words.push(name);
+    }
+    words
+}
+
+/// Joins the words into lines of about 80 characters, continued lines are indented
+fn wrap(head: &str, words: &[String]) -> String {
+    let mut out = String::new();
+    let mut line = head.to_string();
+    let mut empty = true;
+    for word in words {
+        if line.len() + word.len() >= 80 && !empty {
+            out.push_str(&line);
+            out.push('\n');
+            line = String::from("  ");
+        }
+        line.push(' ');
+        line.push_str(word);
+        empty = false;
+    }
+    out.push_str(&line);
+    out.push('\n');
+    out
+}
+
+fn number(x: f64) -> String {
+    format!("{}", x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{read_mps, write_mps, MpsFormat, Options, Status};
+    use super::*;
+
+    #[test]
+    fn lp_format_test_one_line() {
+        let text = "Maximize obj: 7x + 5y Subject To c1: 2x + 3y <= 90 c2: 3x + 2y <= 120 End";
+        let lp = read_lp::<f64>(text).unwrap();
+        assert_eq!(lp.direction, Direction::Maximize);
+        assert_eq!(lp.objective, vec![7.0, 5.0]);
+        assert_eq!(lp.constraints[1].name, "c2");
+        assert_eq!(
+            lp.constraints[1].terms,
+            vec![(Variable(0), 3.0), (Variable(1), 2.0)]
+        );
+        let res = lp.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.objective - 282.0).abs() < 1E-9);
+    }
+
+    #[test]
+    fn lp_format_test_objective_constant() {
+        let text = "Minimize obj: x - 2 + y - 0.5 Subject To c1: x + y >= 1 End";
+        let lp = read_lp::<f64>(text).unwrap();
+        assert_eq!(lp.constant, -2.5);
+        let res = lp.solve(&Options::default());
+        assert!((res.objective + 1.5).abs() < 1E-9, "{}", res.objective);
+        let written = write_lp(&lp);
+        assert!(written.contains(" obj: x + y - 2.5\n"), "{}", written);
+        assert_eq!(read_lp::<f64>(&written).unwrap().constant, -2.5);
+    }
+
+    #[test]
+    fn lp_format_test_sections() {
+        let text = r"\ The lp_solve example of the MPS format
+Minimize
+ COST: X1 + 2 X2 - X3 + 0 X4
+Subject To
+ LIM1: X1 + X2 <= 4
+ LIM2: X1 >= 1
+ MYEQN: - X2 + X3
+        = 7
+ -3 X4 - 1e1 X1 + 2 >= -2.5E+1
+ X4 + X1 <= 3
+Bounds
+ X1 <= 4
+ -1 <= X2 <= 1
+ X4 >= -infinity
+Generals
+ X2
+Binaries
+ X4
+End
+";
+        let lp = read_lp::<f64>(text).unwrap();
+        assert_eq!(lp.direction, Direction::Minimize);
+        assert_eq!(lp.objective, vec![1.0, 2.0, -1.0, 0.0]);
+        assert_eq!(
+            lp.constraints[2].terms,
+            vec![(Variable(1), -1.0), (Variable(2), 1.0)]
+        );
+        assert_eq!(lp.constraints[2].rhs, 7.0);
+        // The constant moves to the right-hand side
+        let c = &lp.constraints[3];
+        assert_eq!((c.name.as_str(), c.sense, c.rhs), ("R4", Sense::Ge, -27.0));
+        assert_eq!(c.terms, vec![(Variable(3), -3.0), (Variable(0), -10.0)]);
+        assert_eq!(lp.constraints[4].name, "R5");
+        let bounds: Vec<_> = lp.variables.iter().map(|v| (v.lower, v.upper)).collect();
+        assert_eq!(
+            bounds,
+            vec![
+                (Some(0.0), Some(4.0)),
+                (Some(-1.0), Some(1.0)),
+                (Some(0.0), None),
+                (Some(0.0), Some(1.0))
+            ]
+        );
+        let integer: Vec<_> = lp.variables.iter().map(|v| v.integer).collect();
+        assert_eq!(integer, vec![false, true, false, true]);
+
+        let lp = read_lp::<f64>(&text.replace("X4 >= -infinity", "X3 free")).unwrap();
+        assert_eq!((lp.variables[2].lower, lp.variables[2].upper), (None, None));
+        let lp = read_lp::<f64>(&text.replace("X4 >= -infinity", "-inf <= X3 <= -2")).unwrap();
+        assert_eq!(
+            (lp.variables[2].lower, lp.variables[2].upper),
+            (None, Some(-2.0))
+        );
+    }
+
+    #[test]
+    fn lp_format_test_round_trip() {
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 1.5, None);
+        let y = lp.add_integer_variable("y", 0.0, Some(10.0));
+        let z = lp.add_variable("z", 0.0, Some(7.0));
+        let b = lp.add_binary_variable("b");
+        let w = lp.add_variable("w", -2.0, Some(-2.0));
+        let f = lp.add_free_variable("f");
+        let m = lp.add_variable("m", 0.0, None);
+        lp.set_bounds(m, None, Some(5.0));
+        lp.add_constraint("c1", &[(x, 10.0), (y, 15.0), (z, 10.0)], Sense::Le, 720.0);
+        lp.add_constraint("c2", &[(x, -1.0), (y, 0.5), (b, 1.0)], Sense::Ge, -3.0);
+        lp.add_constraint("c3", &[(x, 20.0), (w, 1.0)], Sense::Eq, 550.0);
+        lp.add_constraint("c4", &[(f, 1.0), (m, -1.0)], Sense::Ge, 0.0);
+        lp.minimize(&[(x, -20_000.0), (y, 45_000.0), (z, 1.0)]);
+
+        let text = write_lp(&lp);
+        let expected = "\
+Minimize
+ obj: - 20000 x + 45000 y + z + 0 b + 0 w + 0 f + 0 m
+Subject To
+ c1: 10 x + 15 y + 10 z <= 720
+ c2: - x + 0.5 y + b >= -3
+ c3: 20 x + w = 550
+ c4: f - m >= 0
+Bounds
+ x >= 1.5
+ y <= 10
+ z <= 7
+ w = -2
+ f free
+ -inf <= m <= 5
+Generals
+ y
+Binaries
+ b
+End
+";
+        assert_eq!(text, expected);
+        assert_eq!(lp.to_string(), expected);
+        let read = read_lp::<f64>(&text).unwrap();
+        assert_eq!(write_lp(&read), text);
+        // Both formats describe the same program
+        let mps = write_mps(&read, MpsFormat::Free).unwrap();
+        assert_eq!(
+            write_lp(&read_mps::<f64>(&mps, MpsFormat::Free).unwrap()),
+            text
+        );
+    }
+
+    #[test]
+    fn lp_format_test_empty_rows() {
+        // The rows of a master problem before column generation adds its columns
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 0.0, None);
+        lp.add_constraint("demand1", &[], Sense::Ge, 97.0);
+        lp.add_constraint("zeros", &[(x, 0.0)], Sense::Le, 1.0);
+        lp.add_constraint("c3", &[(x, 1.0)], Sense::Le, 4.0);
+        lp.minimize(&[(x, 1.0)]);
+        let text = write_lp(&lp);
+        assert!(text.contains(" demand1: >= 97\n zeros: <= 1\n"), "{}", text);
+        let read = read_lp::<f64>(&text).unwrap();
+        assert_eq!(read.constraints.len(), 3);
+        assert!(read.constraints[0].terms.is_empty());
+        assert_eq!(write_lp(&read), text);
+        let read = read_lp::<f64>("Min\n x\nSubject To\n c1: 2 >= 3\nEnd").unwrap();
+        assert_eq!(
+            (read.constraints[0].terms.len(), read.constraints[0].rhs),
+            (0, 1.0)
+        );
+    }
+
+    #[test]
+    fn lp_format_test_long_lines() {
+        let mut lp = LinearProgram::<f64>::new();
+        let vars: Vec<_> = (0..30)
+            .map(|i| lp.add_variable(&format!("x{}", i), 0.0, None))
+            .collect();
+        let terms: Vec<_> = vars.iter().map(|&v| (v, 1.25)).collect();
+        lp.add_constraint("sum", &terms, Sense::Le, 100.0);
+        lp.maximize(&terms);
+        let text = write_lp(&lp);
+        assert!(text.lines().all(|line| line.len() <= 80), "{}", text);
+        assert_eq!(write_lp(&read_lp::<f64>(&text).unwrap()), text);
+    }
+
+    #[test]
+    fn lp_format_test_errors() {
+        let error = read_lp::<f64>("Subject To\n c1: x <= 1\nEnd").unwrap_err();
+        assert_eq!(error, ParseError::new(1, "Expected Maximize or Minimize"));
+
+        let error = read_lp::<f64>("Max\n x + y\nSubject To\n c1: x + y < = 1\nEnd").unwrap_err();
+        assert_eq!(error.line, 4);
+
+        let text = "Max\n x + y\nSubject To\n c1: x + y <= 1\n c2: x + * y >= 0\nEnd";
+        let error = read_lp::<f64>(text).unwrap_err();
+        assert_eq!(error, ParseError::new(5, "Unexpected character *"));
+
+        let error = read_lp::<f64>("Max\n x\nSubject To\n c1: x <= 1\n").unwrap_err();
+        assert_eq!(error, ParseError::new(4, "Expected End"));
+    }
+}
@@ -0,0 +1,1376 @@
+/// The main source of inspiration [https://brilliant.org/wiki/linear-programming/]
+use nalgebra as na;
+use sensitivity::Interval;
+
+pub mod column_generation;
+pub mod dual;
+pub mod expr;
+pub mod farkas;
+pub mod goal;
+pub mod gomory;
+pub mod interior;
+pub mod lp_format;
+pub mod model;
+pub mod mps;
+pub mod number;
+pub mod observer;
+pub mod pivot;
+pub mod presolve;
+pub mod quadratic;
+pub mod revised;
+pub mod sensitivity;
+pub mod sparse;
+pub mod worksheet;
+
+pub use column_generation::{
+    column_generation, Column, ColumnGeneration, ColumnOptions, CuttingStock, PricingOracle,
+};
+pub use dual::WarmStart;
+pub use expr::LinearExpr;
+pub use farkas::irreducible_infeasible_subsystem;
+pub use goal::{
+    goal_programming, lexicographic, Goal, GoalSolution, LexOptions, Lexicographic, Objective,
+};
+pub use gomory::{gomory_cuts, mixed_integer_cuts, Cut, CutLoop, CutOptions};
+pub use interior::interior_point;
+pub use lp_format::{read_lp, write_lp};
+pub use model::{Constraint, Direction, LinearProgram, Variable, WarmProgram};
+pub use mps::{read_mps, write_mps, MpsFormat, ParseError, WriteError};
+pub use number::{num, Number, Rational};
+pub use observer::{
+    BarrierStep, Initialization, Logger, Observer, Phase, Pivot, Record, Silent, Tableau,
+    Termination, Trace,
+};
+pub use pivot::{Bland, Dantzig, LargestImprovement, PivotRule, SteepestEdge};
+pub use presolve::{presolve, Infeasibility, Presolved};
+pub use quadratic::{active_set, QpSolution};
+pub use revised::revised_simplex;
+pub use sparse::{SparseLu, SparseMatrix};
+pub use worksheet::{worked_solution, Format, Snapshot, Worksheet};
+
+/// The relation between the left-hand side of a constraint and its requirement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sense {
+    /// a_i * x <= b_i
+    Le,
+    /// a_i * x >= b_i
+    Ge,
+    /// a_i * x = b_i
+    Eq,
+}
+
+/// The outcome of the simplex method
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    /// The optimal solution has been found
+    Optimal,
+    /// No point satisfies all of the constraints
+    Infeasible,
+    /// The objective function can grow without limit
+    Unbounded,
+    /// The solver gave up before reaching any of the above
+    IterationLimit,
+}
+
+/// The result of the simplex method decoded from its final table
+#[derive(Debug, Clone)]
+pub struct Solution<T: Number> {
+    pub status: Status,
+    /// The value of the objective function at `x`
+    pub objective: T,
+    /// The values of the decision variables
+    pub x: Vec<T>,
+    /// The values of the slack (or surplus) variables, one per constraint
+    pub slack: Vec<T>,
+    /// The basic variable of each constraint row, indexed as `x` followed by `slack`.
+    /// An artificial variable that Phase I leaves in the basis stands for the slack
+    /// variable of its row.
+    pub basis: Vec<usize>,
+    /// The direction along which the objective function improves forever,
+    /// indexed as `x` followed by `slack`. Only present for unbounded problems.
+    pub ray: Option<Vec<T>>,
+    /// The shadow price of each constraint: the growth of the objective function
+    /// per unit of growth of its requirement. Only present for optimal solutions.
+    pub duals: Vec<T>,
+    /// The growth of the objective function per unit of each decision variable
+    /// forced into the solution. Only present for optimal solutions.
+    pub reduced_costs: Vec<T>,
+    /// The multipliers y of the constraints proving there is no feasible point
+    /// (see `farkas::is_certificate`). Only present for infeasible problems, unless
+    /// the bounds of a variable cross.
+    pub farkas: Option<Vec<T>>,
+    /// The final simplex table, empty for `revised_simplex` which never forms one
+    pub table: na::DMatrix<T>,
+    /// How the columns of `table` relate to the variables
+    pub(crate) layout: Layout<T>,
+}
+
+/// The knobs of the simplex method
+pub struct Options<'a, T: Number> {
+    /// The rule choosing the entering and leaving variables
+    pub pivot_rule: &'a dyn PivotRule<T>,
+    /// The number of pivots (of both phases) the solver gives up after
+    pub max_iterations: usize,
+    /// Gets told about the start, every pivot and the end of the method
+    pub observer: &'a dyn Observer<T>,
+    /// The number of pivots the revised simplex method applies to the factorization
+    /// of the basis before it factorizes the basis anew
+    pub refactor_every: usize,
+    /// The method `LinearProgram::solve` goes with
+    pub algorithm: Algorithm,
+    /// Let `LinearProgram::solve` reduce the program with `presolve` first
+    pub presolve: bool,
+}
+
+/// The methods solving a `LinearProgram`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Algorithm {
+    /// `bounded_simplex`, which keeps the whole table
+    Simplex,
+    /// `revised_simplex`, which keeps the constraints sparse
+    RevisedSimplex,
+    /// `interior_point`, followed by a crossover to a vertex if asked
+    InteriorPoint { crossover: bool },
+}
+
+impl<T: Number> Default for Options<'static, T> {
+    fn default() -> Self {
+        Options {
+            pivot_rule: &Dantzig,
+            max_iterations: 10_000,
+            observer: &Silent,
+            refactor_every: 64,
+            algorithm: Algorithm::Simplex,
+            presolve: false,
+        }
+    }
+}
+
+impl<'a, T: Number> Options<'a, T> {
+    /// The same options with the table method and without presolve, the only way
+    /// `LinearProgram::solve` gives the final table of the whole program: the methods
+    /// built on top of it read their cuts off that table, or hand it on for ranging
+    pub(crate) fn table_method(&self) -> Options<'a, T> {
+        Options {
+            algorithm: Algorithm::Simplex,
+            presolve: false,
+            ..*self
+        }
+    }
+}
+
+/// What the pivoting rules decided to do next
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Step {
+    /// Exchange the variables. The leaving one may leave at its upper bound.
+    Pivot {
+        entering: usize,
+        leaving: usize,
+        to_upper: bool,
+    },
+    /// The entering variable reaches its own upper bound before any basic variable
+    /// reaches a bound, so it jumps there and stays non-basic
+    Flip(usize),
+    /// There is no entering variable left
+    Optimal,
+    /// Nothing limits the growth of the entering column
+    Unbounded(usize),
+    /// Out of iterations
+    IterationLimit,
+}
+
+/// How the columns of a table relate to the variables of the problem.
+/// Every column holds a value v in [0, upper], unless it is free,
+/// and the variable itself is x = offset + sign * v. So the variables are shifted
+/// by their lower bounds, mirrored when there is only an upper one
+/// and replaced by `upper - v` whenever they sit at their upper bounds.
+/// The slack and artificial columns are plain: v = x >= 0.
+#[derive(Debug, Clone)]
+pub(crate) struct Layout<T: Number> {
+    offset: Vec<T>,
+    sign: Vec<T>,
+    upper: Vec<Option<T>>,
+    free: Vec<bool>,
+    /// The factor of each constraint turning its slack column into
+    /// the change of the basic variables per unit of the requirement
+    factors: Vec<T>,
+}
+
+impl<T: Number> Layout<T> {
+    /// Maps the bounds of the decision variables onto their columns
+    fn new(bounds: &[Interval<T>], n_logical: usize) -> Self {
+        let mut layout = Layout {
+            offset: vec![T::zero(); n_logical],
+            sign: vec![T::one(); n_logical],
+            upper: vec![None; n_logical],
+            free: vec![false; n_logical],
+            factors: Vec::new(),
+        };
+        for (j, bound) in bounds.iter().enumerate() {
+            let (offset, sign) = match bound {
+                (Some(lower), _) => (lower.clone(), T::one()),
+                (None, Some(upper)) => (upper.clone(), -T::one()),
+                (None, None) => {
+                    layout.free[j + 1] = true;
+                    (T::zero(), T::one())
+                }
+            };
+            if let (Some(lower), Some(upper)) = bound {
+                layout.upper[j + 1] = Some(upper.clone() - lower);
+            }
+            layout.offset[j + 1] = offset;
+            layout.sign[j + 1] = sign;
+        }
+        layout
+    }
+
+    /// The largest value of the column, `None` if there is no limit
+    pub(super) fn upper(&self, col: usize) -> Option<&T> {
+        self.upper.get(col).and_then(Option::as_ref)
+    }
+
+    /// Free columns can be negative: they enter from both sides and never leave
+    pub(super) fn is_free(&self, col: usize) -> bool {
+        self.free.get(col).copied().unwrap_or(false)
+    }
+
+    /// The value of the variable when its column is zero
+    pub(super) fn offset(&self, col: usize) -> T {
+        self.offset.get(col).cloned().unwrap_or_else(T::zero)
+    }
+
+    /// The growth of the variable per unit of its column
+    pub(super) fn sign(&self, col: usize) -> T {
+        self.sign.get(col).cloned().unwrap_or_else(T::one)
+    }
+
+    pub(super) fn factors(&self) -> &[T] {
+        &self.factors
+    }
+
+    /// The value of the variable of the column holding v
+    fn value(&self, col: usize, v: T) -> T {
+        match self.offset.get(col) {
+            Some(offset) => offset.clone() + self.sign(col) * v,
+            None => v,
+        }
+    }
+
+    /// Replaces the non-basic v by upper - v, moving the variable to the opposite bound
+    fn flip(&mut self, table: &mut na::DMatrix<T>, col: usize) {
+        let upper = self.upper[col].clone().expect("Only bounded columns flip");
+        let last_col = table.ncols() - 1;
+        for i in 0..table.nrows() {
+            let coef = table[(i, col)].clone();
+            table[(i, last_col)] -= coef.clone() * &upper;
+            table[(i, col)] = -coef;
+        }
+        self.offset[col] += self.sign[col].clone() * upper;
+        self.sign[col] = -self.sign[col].clone();
+    }
+
+    /// Replaces the non-basic free v by -v, so it can enter by growing
+    fn mirror(&mut self, table: &mut na::DMatrix<T>, col: usize) {
+        for i in 0..table.nrows() {
+            table[(i, col)] = -table[(i, col)].clone();
+        }
+        self.sign[col] = -self.sign[col].clone();
+    }
+}
+
+impl<T: Number> Solution<T> {
+    /// Reads the values of the basic variables off the requirements column,
+    /// the non-basic ones are all zeros (before the layout maps them back).
+    /// The optimal objective value is read off the table as well,
+    /// `constant` is the part the offsets of the variables bring in.
+    fn from_table(
+        status: Status,
+        table: na::DMatrix<T>,
+        basis: &[usize],
+        layout: Layout<T>,
+        obj: &[T],
+        constant: T,
+    ) -> Self {
+        let n_vars = obj.len();
+        let n_constr = basis.len();
+        let last_col = table.ncols() - 1;
+        let mut columns = vec![T::zero(); n_vars + n_constr];
+        let mut basic = Vec::with_capacity(n_constr);
+        for (i, &b) in basis.iter().enumerate() {
+            // Artificial variables are left out
+            if b <= columns.len() {
+                columns[b - 1] = table[(i + 1, last_col)].clone();
+            }
+            basic.push(b - 1);
+        }
+        let mut values: Vec<T> = (columns.into_iter().enumerate())
+            .map(|(j, v)| layout.value(j + 1, v))
+            .collect();
+        let slack = values.split_off(n_vars);
+        let objective = match status {
+            Status::Optimal => table[(0, last_col)].clone() + constant,
+            _ => dot(&values, obj),
+        };
+        Solution {
+            status,
+            objective,
+            x: values,
+            slack,
+            basis: basic,
+            ray: None,
+            duals: Vec::new(),
+            reduced_costs: Vec::new(),
+            farkas: None,
+            table,
+            layout,
+        }
+    }
+
+    /// Reports an artificial column left in the basis as the slack column of its row,
+    /// `start` being the basis the table started from
+    fn without_artificials(mut self, start: &[usize]) -> Self {
+        let (n_vars, n_logical) = (self.x.len(), self.x.len() + self.slack.len());
+        for b in self.basis.iter_mut().filter(|b| **b >= n_logical) {
+            let row = (start.iter().position(|&s| s == *b + 1))
+                .expect("Every artificial column starts in the basis");
+            *b = n_vars + row;
+        }
+        self
+    }
+
+    /// Increasing the entering variable by t changes each basic variable
+    /// by -t times its coefficient in the entering column.
+    /// None of them decreases, so every point x + t * ray is feasible.
+    fn unbounded(
+        table: na::DMatrix<T>,
+        basis: &[usize],
+        layout: Layout<T>,
+        obj: &[T],
+        entering: usize,
+    ) -> Self {
+        let mut ray = vec![T::zero(); obj.len() + basis.len()];
+        ray[entering - 1] = layout.sign(entering);
+        for (i, &b) in basis.iter().enumerate() {
+            ray[b - 1] = -layout.sign(b) * &table[(i + 1, entering)];
+        }
+        let mut solution =
+            Solution::from_table(Status::Unbounded, table, basis, layout, obj, T::zero());
+        solution.ray = Some(ray);
+        solution
+    }
+}
+
+/// The sum of the products of the pairs of numbers
+fn dot<T: Number>(a: &[T], b: &[T]) -> T {
+    a.iter()
+        .zip(b)
+        .fold(T::zero(), |acc, (a, b)| acc + a.clone() * b)
+}
+
+/// The simplex algorithm itself.
+/// Every constraint is treated as `<=`.
+pub fn simplex_method<T: Number>(
+    constr: na::DMatrix<T>,
+    req: Vec<T>,
+    obj: Vec<T>,
+    observer: &dyn Observer<T>,
+) -> Solution<T> {
+    let senses = vec![Sense::Le; constr.nrows()];
+    let options = Options {
+        observer,
+        ..Options::default()
+    };
+    two_phase_simplex(constr, senses, req, obj, &options)
+}
+
+/// The two-phase simplex method accepting any mix of `<=`, `>=` and `=` constraints
+/// with requirements of any sign. Phase I minimizes the sum of artificial variables
+/// to find a feasible basis, Phase II optimizes the real objective starting from it.
+/// The final table has the layout of:
+/// z | decision variables | one slack (or surplus) variable per constraint | requirements.
+/// If the problem is infeasible, the table is the one Phase I ended up with.
+pub fn two_phase_simplex<T: Number>(
+    constr: na::DMatrix<T>,
+    senses: Vec<Sense>,
+    req: Vec<T>,
+    obj: Vec<T>,
+    options: &Options<T>,
+) -> Solution<T> {
+    let bounds = vec![(Some(T::zero()), None); obj.len()];
+    bounded_simplex(constr, senses, req, obj, &bounds, options)
+}
+
+/// The two-phase simplex method for variables lying within any bounds (lower, upper),
+/// where `None` stands for no limit. The bounds don't take rows of the table:
+/// a variable reaching its upper bound is substituted by `upper - x`, while
+/// free variables (with no bounds at all) may enter the basis from both sides
+/// and never leave it. The columns of the table hold the shifted variables,
+/// e.g. x - lower, whereas the solution is stated in terms of the variables themselves.
+pub fn bounded_simplex<T: Number>(
+    constr: na::DMatrix<T>,
+    senses: Vec<Sense>,
+    req: Vec<T>,
+    obj: Vec<T>,
+    bounds: &[Interval<T>],
+    options: &Options<T>,
+) -> Solution<T> {
+    options.observer.initialized(&Initialization {
+        method: "simplex",
+        rows: constr.nrows(),
+        columns: constr.ncols(),
+    });
+    let mut iterations = 0;
+    let solution = table_simplex(constr, senses, req, obj, bounds, options, &mut iterations);
+    options.observer.terminated(&Termination {
+        status: solution.status,
+        objective: solution.objective.clone(),
+        iterations,
+    });
+    solution
+}
+
+/// The body of `bounded_simplex`, counting the pivots
+fn table_simplex<T: Number>(
+    mut constr: na::DMatrix<T>,
+    senses: Vec<Sense>,
+    mut req: Vec<T>,
+    obj: Vec<T>,
+    bounds: &[Interval<T>],
+    options: &Options<T>,
+    iterations: &mut usize,
+) -> Solution<T> {
+    assert_eq!(constr.nrows(), senses.len(), "One sense per constraint");
+    assert_eq!(constr.nrows(), req.len(), "One requirement per constraint");
+    assert_eq!(
+        constr.ncols(),
+        bounds.len(),
+        "One pair of bounds per variable"
+    );
+    let n_vars = obj.len();
+    let n_logical = n_vars + constr.nrows() + 1;
+    let mut layout = Layout::new(bounds, n_logical);
+    // A x = A (offset + sign * v): the offsets move over to the requirements
+    let mut table_obj = obj.clone();
+    for j in 0..n_vars {
+        let (offset, sign) = (layout.offset[j + 1].clone(), layout.sign[j + 1].clone());
+        for i in 0..constr.nrows() {
+            req[i] -= constr[(i, j)].clone() * &offset;
+            constr[(i, j)] *= &sign;
+        }
+        table_obj[j] *= sign;
+    }
+    let mut constant = dot(&obj, &layout.offset[1..=n_vars]);
+    let crossed = bounds.iter().any(|bound| match bound {
+        (Some(lower), Some(upper)) => upper < lower,
+        _ => false,
+    });
+
+    let max_req = req.iter().fold(
+        T::zero(),
+        |acc, b| {
+            if b.abs() > acc {
+                b.abs()
+            } else {
+                acc
+            }
+        },
+    );
+    layout.factors = dual_factors(&senses, &req);
+    // Slack variables of equality constraints must stay at zero
+    let mut locked: Vec<bool> = (0..n_logical)
+        .map(|j| j > n_vars && senses[j - n_vars - 1] == Sense::Eq)
+        .collect();
+
+    let (mut table, mut basis, n_artificial) =
+        create_augmented_mat(table_obj.clone(), constr, senses, req);
+    let start = basis.clone();
+    if crossed {
+        return Solution::from_table(Status::Infeasible, table, &basis, layout, &obj, constant)
+            .without_artificials(&start);
+    }
+    // The slack variables of equality constraints are artificial too
+    if n_artificial > 0 || locked.contains(&true) {
+        // Phase I: artificial variables can only leave the basis
+        locked.resize(table.ncols(), true);
+        let is_artificial = |j: usize| j >= n_logical || locked[j];
+        set_phase1_objective(&mut table, &basis, &is_artificial);
+        // The sum of artificial variables is bounded by zero, so it never ends up unbounded
+        let step = iterate(
+            &mut table,
+            &mut basis,
+            &locked,
+            &mut layout,
+            iterations,
+            options,
+            Phase::One,
+            &T::zero(),
+        );
+        if step == Step::IterationLimit {
+            let status = Status::IterationLimit;
+            return Solution::from_table(status, table, &basis, layout, &obj, constant)
+                .without_artificials(&start);
+        }
+        let last_col = table.ncols() - 1;
+        if table[(0, last_col)] < -(T::eps() * (T::one() + max_req)) {
+            // The shadow prices of Phase I prove the infeasibility. The slack column
+            // of an `=` constraint is artificial, its cost is in row(0) as well.
+            let farkas = (layout.factors.iter().enumerate())
+                .map(|(i, f)| {
+                    let j = n_vars + 1 + i;
+                    let cost = if locked[j] { T::one() } else { T::zero() };
+                    f.clone() * (table[(0, j)].clone() - cost)
+                })
+                .collect();
+            let status = Status::Infeasible;
+            let mut solution = Solution::from_table(status, table, &basis, layout, &obj, constant)
+                .without_artificials(&start);
+            solution.farkas = Some(farkas);
+            return solution;
+        }
+        table = drive_out_artificials(table, &mut basis, n_logical, &locked);
+        table = table.remove_columns(n_logical, n_artificial);
+        locked.truncate(n_logical);
+        // Phase II: back to the original objective function,
+        // in terms of the columns as Phase I left them flipped or mirrored
+        for j in 0..n_vars {
+            table_obj[j] = obj[j].clone() * layout.sign(j + 1);
+        }
+        constant = dot(&obj, &layout.offset[1..=n_vars]);
+        set_objective(&mut table, &basis, &table_obj);
+    }
+    let step = iterate(
+        &mut table,
+        &mut basis,
+        &locked,
+        &mut layout,
+        iterations,
+        options,
+        Phase::Two,
+        &constant,
+    );
+    match step {
+        Step::Unbounded(entering) => Solution::unbounded(table, &basis, layout, &obj, entering),
+        Step::IterationLimit => {
+            let status = Status::IterationLimit;
+            Solution::from_table(status, table, &basis, layout, &obj, constant)
+        }
+        _ => {
+            let status = Status::Optimal;
+            let mut solution = Solution::from_table(status, table, &basis, layout, &obj, constant);
+            let row0 = solution.table.row(0);
+            solution.reduced_costs = (1..=n_vars)
+                .map(|j| -row0[j].clone() * solution.layout.sign(j))
+                .collect();
+            solution.duals = (solution.layout.factors.iter().enumerate())
+                .map(|(i, f)| f.clone() * &row0[n_vars + 1 + i])
+                .collect();
+            solution
+        }
+    }
+}
+
+/// The coefficient of row(0) in the slack column of each constraint is its shadow price
+/// up to the sign of the slack variable and of the row itself (rows with negative
+/// requirements are multiplied by -1). The same factor turns the slack column into
+/// the change of the basic variables per unit of the requirement.
+fn dual_factors<T: Number>(senses: &[Sense], req: &[T]) -> Vec<T> {
+    (senses.iter().zip(req))
+        .map(|(sense, b)| match sense {
+            Sense::Le => T::one(),
+            Sense::Ge => -T::one(),
+            Sense::Eq if b.is_negative() => -T::one(),
+            Sense::Eq => T::one(),
+        })
+        .collect()
+}
+
+/// Pivots until there is no entering variable left, the problem turns out unbounded
+/// or the iterations run out. Returns the step that stopped them.
+#[allow(clippy::too_many_arguments)]
+fn iterate<T: Number>(
+    table: &mut na::DMatrix<T>,
+    basis: &mut [usize],
+    locked: &[bool],
+    layout: &mut Layout<T>,
+    iterations: &mut usize,
+    options: &Options<T>,
+    phase: Phase,
+    constant: &T,
+) -> Step {
+    loop {
+        // A free variable improving the objective by decreasing enters mirrored
+        for j in 1..layout.free.len() {
+            if layout.is_free(j) && table[(0, j)] > T::eps() && !basis.contains(&j) {
+                layout.mirror(table, j);
+            }
+        }
+        let step = get_next_pivot(table, basis, locked, layout, options.pivot_rule);
+        let tableau = |table, basis, entering, leaving| Tableau {
+            iteration: *iterations,
+            phase,
+            table,
+            basis,
+            entering,
+            leaving,
+        };
+        let (entering, left) = match step {
+            Step::Pivot { .. } | Step::Flip(_) if *iterations >= options.max_iterations => {
+                (options.observer).tabulated(&tableau(table, basis, None, None));
+                return Step::IterationLimit;
+            }
+            Step::Pivot {
+                entering,
+                leaving,
+                to_upper,
+            } => {
+                if to_upper {
+                    layout.flip(table, basis[leaving - 1]);
+                }
+                let event = tableau(table, basis, Some(entering), Some(leaving));
+                options.observer.tabulated(&event);
+                *table = apply_row_operations((entering, leaving), table.clone());
+                let left = basis[leaving - 1];
+                basis[leaving - 1] = entering;
+                (entering, Some(left - 1))
+            }
+            Step::Flip(col) => {
+                (options.observer).tabulated(&tableau(table, basis, Some(col), None));
+                layout.flip(table, col);
+                (col, None)
+            }
+            step => {
+                (options.observer).tabulated(&tableau(table, basis, None, None));
+                return step;
+            }
+        };
+        *iterations += 1;
+        let last_col = table.ncols() - 1;
+        options.observer.pivoted(&Pivot {
+            iteration: *iterations,
+            phase,
+            entering: entering - 1,
+            leaving: left,
+            objective: table[(0, last_col)].clone() + constant,
+        });
+    }
+}
+
+/// The iterations of the simplex algorithm involve exchanging basic variables
+/// with non-basic variables by using matrix row operations.
+fn apply_row_operations<T: Number>(
+    pivot: (usize, usize),
+    mut table: na::DMatrix<T>,
+) -> na::DMatrix<T> {
+    // Scale the pivot row, so the entering variable gets the coefficient of 1
+    let pivot_value = table[(pivot.1, pivot.0)].clone();
+    for j in 0..table.ncols() {
+        table[(pivot.1, j)] /= &pivot_value;
+    }
+    for i in 0..table.nrows() {
+        if pivot.1 == i {
+            continue;
+        }
+        // The row the entering variable of which we wanna set to 0
+        let target_entry = table[(i, pivot.0)].clone();
+        if target_entry.is_zero() {
+            continue;
+        }
+        for j in 0..table.ncols() {
+            let delta = table[(pivot.1, j)].clone() * &target_entry;
+            table[(i, j)] -= delta;
+        }
+    }
+    table
+}
+
+/// If all coefficients of non-basic variables in row(0) are positive
+/// then you have the optimal solution. Otherwise, let the pivot rule select
+/// a non-basic variable that has a negative coefficient in row(0) to be
+/// the next entering variable and the row to leave, then pivot again.
+/// If nothing limits the entering variable, the problem is unbounded.
+fn get_next_pivot<T: Number>(
+    table: &na::DMatrix<T>,
+    basis: &[usize],
+    locked: &[bool],
+    layout: &Layout<T>,
+    rule: &dyn PivotRule<T>,
+) -> Step {
+    let last_coll = table.ncols() - 1;
+    let candidates: Vec<usize> = (1..last_coll)
+        .filter(|&j| !locked[j] && table[(0, j)] < -T::eps())
+        .collect();
+    // There is no negative non-basic variables left - stop iterations
+    if candidates.is_empty() {
+        return Step::Optimal;
+    }
+    let entering = rule.entering(table, &candidates);
+    match pivot::bounded_ratio(table, entering, basis, layout) {
+        pivot::Ratio::Rows(ties) => {
+            let rows: Vec<usize> = ties.iter().map(|&(i, _)| i).collect();
+            let leaving = rule.leaving(table, basis, &rows);
+            let to_upper = ties.iter().any(|&(i, upper)| i == leaving && upper);
+            Step::Pivot {
+                entering,
+                leaving,
+                to_upper,
+            }
+        }
+        pivot::Ratio::Flip => Step::Flip(entering),
+        pivot::Ratio::Unbounded => Step::Unbounded(entering),
+    }
+}
+
+/// Create an augmented matrix from the given constraints and objective function.
+/// Every constraint gets its own slack variable (+1 for `<=`, -1 for `>=`),
+/// the rows with negative requirements are multiplied by -1.
+/// The constraints whose slack variable cannot start in the basis get an artificial one,
+/// appended right before the requirements column. The slack variable of `=` constraint
+/// plays the role of the artificial one itself.
+/// Returns the table, the basic variable of each constraint and the number of artificial variables.
+fn create_augmented_mat<T: Number>(
+    obj: Vec<T>,
+    constr: na::DMatrix<T>,
+    senses: Vec<Sense>,
+    req: Vec<T>,
+) -> (na::DMatrix<T>, Vec<usize>, usize) {
+    let n_vars = constr.ncols();
+    let n_constr = constr.nrows();
+    // Which rows need an artificial variable
+    let needs_artificial: Vec<bool> = (0..n_constr)
+        .map(|i| match senses[i] {
+            Sense::Le => req[i].is_negative(),
+            Sense::Ge => !req[i].is_negative(),
+            Sense::Eq => false,
+        })
+        .collect();
+    let n_artificial = needs_artificial.iter().filter(|&&a| a).count();
+    // Count the objective function row as well
+    let n_rows = n_constr + 1;
+    let n_cols = n_rows + n_vars + n_artificial + 1;
+    let mut table = na::DMatrix::<T>::zeros(n_rows, n_cols);
+    let mut basis = Vec::with_capacity(n_constr);
+
+    // Setting up objective function row
+    table[(0, 0)] = T::one();
+    for i in 0..obj.len() {
+        table[(0, i + 1)] = -obj[i].clone();
+    }
+
+    // Setting up constraints & requirements
+    let mut artificial_col = n_vars + n_rows;
+    for i in 1..n_rows {
+        let sign = if req[i - 1].is_negative() {
+            -T::one()
+        } else {
+            T::one()
+        };
+        for j in 1..=n_vars {
+            table[(i, j)] = sign.clone() * &constr[(i - 1, j - 1)];
+        }
+        let slack_col = n_vars + i;
+        table[(i, slack_col)] = match senses[i - 1] {
+            Sense::Le => sign.clone(),
+            Sense::Ge => -sign.clone(),
+            Sense::Eq => T::one(),
+        };
+        table[(i, n_cols - 1)] = sign * &req[i - 1];
+        if needs_artificial[i - 1] {
+            table[(i, artificial_col)] = T::one();
+            basis.push(artificial_col);
+            artificial_col += 1;
+        } else {
+            basis.push(slack_col);
+        }
+    }
+
+    (table, basis, n_artificial)
+}
+
+/// Phase I maximizes the negated sum of artificial variables.
+/// The row(0) is expressed in terms of non-basic variables.
+fn set_phase1_objective<T: Number>(
+    table: &mut na::DMatrix<T>,
+    basis: &[usize],
+    is_artificial: &dyn Fn(usize) -> bool,
+) {
+    let last_col = table.ncols() - 1;
+    for j in 1..=last_col {
+        table[(0, j)] = if j < last_col && is_artificial(j) {
+            T::one()
+        } else {
+            T::zero()
+        };
+    }
+    for (i, &b) in basis.iter().enumerate() {
+        if is_artificial(b) {
+            for j in 1..=last_col {
+                let delta = table[(i + 1, j)].clone();
+                table[(0, j)] -= delta;
+            }
+        }
+    }
+}
+
+/// Puts the objective function back into row(0) and
+/// eliminates the coefficients of the basic variables from it.
+fn set_objective<T: Number>(table: &mut na::DMatrix<T>, basis: &[usize], obj: &[T]) {
+    let last_col = table.ncols() - 1;
+    for j in 1..=last_col {
+        table[(0, j)] = if j <= obj.len() {
+            -obj[j - 1].clone()
+        } else {
+            T::zero()
+        };
+    }
+    for (i, &b) in basis.iter().enumerate() {
+        let coef = table[(0, b)].clone();
+        if coef.is_zero() {
+            continue;
+        }
+        for j in 1..=last_col {
+            let delta = coef.clone() * &table[(i + 1, j)];
+            table[(0, j)] -= delta;
+        }
+    }
+}
+
+/// After Phase I the remaining basic artificial variables (and slack variables of `=`
+/// constraints) are zero. They are swapped with any other variable, which is a degenerate pivot.
+/// Every artificial variable can at least be replaced by the surplus variable of its row.
+/// The slack variable of a redundant `=` constraint may stay in the basis at zero,
+/// since its row has no non-zero coefficients left to pivot on.
+fn drive_out_artificials<T: Number>(
+    mut table: na::DMatrix<T>,
+    basis: &mut [usize],
+    n_logical: usize,
+    locked: &[bool],
+) -> na::DMatrix<T> {
+    for i in 1..table.nrows() {
+        if basis[i - 1] < n_logical && !locked[basis[i - 1]] {
+            continue;
+        }
+        let entry = (1..n_logical)
+            .filter(|&j| !locked[j])
+            .find(|&j| table[(i, j)].abs() > T::eps());
+        if let Some(j) = entry {
+            table = apply_row_operations((j, i), table);
+            basis[i - 1] = j;
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The problems of the table tests: the constraints, requirements and objective
+    pub(super) fn table_problems() -> Vec<(na::DMatrix<f64>, Vec<f64>, Vec<f64>)> {
+        type Problem = (usize, usize, Vec<f64>, Vec<f64>, Vec<f64>);
+        #[rustfmt::skip]
+        let problems: Vec<Problem> = vec![
+            (4, 3, vec![
+                10.0, 15.0, 10.0,
+                13.0, 5.0, 5.0,
+                20.0, 5.0, 10.0,
+                0.0, 0.0, 1.0,
+            ], vec![720.0, 680.0, 550.0, 7.0], vec![20_000.0, 45_000.0, 85_000.0]),
+            (2, 2, vec![
+                2.0, 3.0,
+                3.0, 2.0,
+            ], vec![90.0, 120.0], vec![7.0, 5.0]),
+            (4, 3, vec![
+                10.0, 15.0, 20.0,
+                13.0, 15.0, 5.0,
+                10.0, 5.0, 10.0,
+                1.0, 0.0, 0.0,
+            ], vec![620.0, 880.0, 550.0, 10.0], vec![70_000.0, 25_000.0, 55_000.0]),
+            (3, 3, vec![
+                20.0, 15.0, 20.0,
+                27.0, 35.0, 50.0,
+                10.0, 5.0, 10.0,
+            ], vec![550.0, 900.0, 550.0], vec![35.0, 45.0, 55.0]),
+        ];
+        (problems.into_iter())
+            .map(|(rows, cols, constraints, req, obj)| {
+                (
+                    na::DMatrix::from_row_slice(rows, cols, &constraints),
+                    req,
+                    obj,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn simplex_test_problem1() {
+        /*
+        Objective function = 20_000x + 45_000y + 85_000z
+        Constraints:
+        10x + 15y + 10z <= 720
+        13x + 5y + 5z <= 680
+        20x + 5y + 10z <= 550
+        z <= 7
+        x, y, z >= 0
+        */
+        let obj_f: Vec<f64> = vec![20_000.0, 45_000.0, 85_000.0];
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(4, 3, &[
+            10.0, 15.0, 10.0, 
+            13.0, 5.0, 5.0,
+            20.0, 5.0, 10.0,
+            0.0, 0.0, 1.0,
+        ]);
+        let req = vec![720.0, 680.0, 550.0, 7.0];
+        let res = simplex_method(constraints, req, obj_f, &Silent);
+        assert!(
+            ((res.objective - 2_545_000.0) / 2_545_000.0).abs() < 1E-12,
+            "The expected optimal value: 2_545_000, the value we got: {}",
+            res.objective
+        );
+    }
+    #[test]
+    fn simplex_test_problem2() {
+        /*
+        Objective function = 7x + 5y
+        Constraints:
+        2x + 3y <= 90
+        3x + 2y <= 120
+        x, y >= 0
+        */
+        let obj_f: Vec<f64> = vec![7.0, 5.0];
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(2, 2, &[
+            2.0, 3.0,
+            3.0, 2.0,
+        ]);
+        let req = vec![90.0, 120.0];
+
+        let res = simplex_method(constraints, req, obj_f, &Silent);
+        assert!(
+            ((res.objective - 282.0) / 282.0).abs() < 1E-12,
+            "The expected optimal value: 282, the value we got: {}",
+            res.objective
+        );
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.x[0] - 36.0).abs() < 1E-4 && (res.x[1] - 6.0).abs() < 1E-4);
+        assert_eq!(res.slack, vec![0.0, 0.0]);
+        // An extra unit of each resource is worth 0.2 and 2.2 respectively
+        assert!((res.duals[0] - 0.2).abs() < 1E-4 && (res.duals[1] - 2.2).abs() < 1E-4);
+        assert!(res.reduced_costs.iter().all(|d| d.abs() < 1E-4));
+        let mut basis = res.basis.clone();
+        basis.sort();
+        assert_eq!(basis, vec![0, 1]);
+    }
+    #[test]
+    fn simplex_test_problem3() {
+        /*
+        Objective function = 70_000x + 25_000y + 55_000z
+        Constraints:
+        10x + 15y + 20z <= 620
+        13x + 15y + 5z <= 880
+        10x + 5y + 10z <= 550
+        x <= 10
+        x, y, z >= 0
+        */
+        let obj_f: Vec<f64> = vec![70_000.0, 25_000.0, 55_000.0];
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(4, 3, &[
+            10.0, 15.0, 20.0, 
+            13.0, 15.0, 5.0,
+            10.0, 5.0, 10.0,
+            1.0, 0.0, 0.0,
+        ]);
+        let req = vec![620.0, 880.0, 550.0, 10.0];
+        let res = simplex_method(constraints, req, obj_f, &Silent);
+        assert!(
+            ((res.objective - 2_130_000.0) / 2_130_000.0).abs() < 1E-12,
+            "The expected optimal value: 2_130_000, the value we got: {}",
+            res.objective
+        );
+    }
+    #[test]
+    fn simplex_test_problem4() {
+        /*
+        Objective function = 35x + 45y + 55z
+        Constraints:
+        20x + 15y + 20z <= 550
+        27x + 35y + 50z <= 900
+        10x + 5y + 10z <= 550
+        x, y, z >= 0
+        */
+        let obj_f: Vec<f64> = vec![35.0, 45.0, 55.0];
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(3, 3, &[
+            20.0, 15.0, 20.0, 
+            27.0, 35.0, 50.0,
+            10.0, 5.0, 10.0,
+        ]);
+        let req = vec![550.0, 900.0, 550.0];
+        let res = simplex_method(constraints, req, obj_f, &Silent);
+        // Exactly 68600/59, see simplex_test_rational
+        let expected = 68_600.0 / 59.0;
+        assert!(
+            ((res.objective - expected) / expected).abs() < 1E-12,
+            "The expected optimal value: 1162.7118, the value we got: {}",
+            res.objective
+        );
+    }
+    #[test]
+    fn simplex_test_two_phase_ge() {
+        /*
+        Objective function = -2x - 3y
+        Constraints:
+        x + y >= 4
+        x + 3y >= 6
+        x <= 5
+        x, y >= 0
+        */
+        let obj_f: Vec<f32> = vec![-2.0, -3.0];
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(3, 2, &[
+            1.0, 1.0,
+            1.0, 3.0,
+            1.0, 0.0,
+        ]);
+        let senses = vec![Sense::Ge, Sense::Ge, Sense::Le];
+        let req = vec![4.0, 6.0, 5.0];
+        let res = two_phase_simplex(constraints, senses, req, obj_f, &Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert_eq!(res.table.ncols(), 7);
+        assert!((res.x[0] - 3.0).abs() < 1E-4 && (res.x[1] - 1.0).abs() < 1E-4);
+        // The surplus of x + y >= 4 and x + 3y >= 6 is zero, the slack of x <= 5 is 2
+        assert!(res.slack[0].abs() < 1E-4 && res.slack[1].abs() < 1E-4);
+        assert!((res.slack[2] - 2.0).abs() < 1E-4);
+        assert!(
+            (res.objective + 9.0).abs() < 1E-4,
+            "The expected optimal value: -9, the value we got: {}",
+            res.objective
+        );
+    }
+    #[test]
+    fn simplex_test_two_phase_eq_and_negative_req() {
+        /*
+        Objective function = x + 2y
+        Constraints:
+        x + y = 3
+        -x + y >= -1
+        -y >= -2
+        x, y >= 0
+        */
+        let obj_f: Vec<f32> = vec![1.0, 2.0];
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(3, 2, &[
+            1.0, 1.0,
+            -1.0, 1.0,
+            0.0, -1.0,
+        ]);
+        let senses = vec![Sense::Eq, Sense::Ge, Sense::Ge];
+        let req = vec![3.0, -1.0, -2.0];
+        let res = two_phase_simplex(constraints, senses, req, obj_f, &Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.x[0] - 1.0).abs() < 1E-4 && (res.x[1] - 2.0).abs() < 1E-4);
+        assert!(
+            (res.objective - 5.0).abs() < 1E-4,
+            "The expected optimal value: 5, the value we got: {}",
+            res.objective
+        );
+    }
+    #[test]
+    fn simplex_test_two_phase_infeasible_eq() {
+        /*
+        Objective function = x
+        Constraints:
+        2x = 1
+        x <= 0
+        x >= 0
+        */
+        let constraints = na::DMatrix::from_row_slice(2, 1, &[2.0, 1.0]);
+        let senses = vec![Sense::Eq, Sense::Le];
+        let res = two_phase_simplex(
+            constraints,
+            senses,
+            vec![1.0, 0.0],
+            vec![1.0],
+            &Options::default(),
+        );
+        assert_eq!(res.status, Status::Infeasible);
+    }
+    #[test]
+    fn simplex_test_two_phase_infeasible() {
+        /*
+        Constraints:
+        x + y <= 2
+        x + y >= 5
+        */
+        let obj_f: Vec<f32> = vec![1.0, 1.0];
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(2, 2, &[
+            1.0, 1.0,
+            1.0, 1.0,
+        ]);
+        let senses = vec![Sense::Le, Sense::Ge];
+        let req = vec![2.0, 5.0];
+        let res = two_phase_simplex(constraints, senses, req, obj_f, &Options::default());
+        assert_eq!(res.status, Status::Infeasible);
+    }
+    #[test]
+    fn simplex_test_infeasible_basis() {
+        /*
+        Constraints:
+        x <= 1
+        x >= 2
+        */
+        let constraints = na::DMatrix::from_row_slice(2, 1, &[1.0, 1.0]);
+        let senses = vec![Sense::Le, Sense::Ge];
+        let res = two_phase_simplex(
+            constraints,
+            senses,
+            vec![1.0, 2.0],
+            vec![1.0],
+            &Options::default(),
+        );
+        assert_eq!(res.status, Status::Infeasible);
+        // The artificial variable of x >= 2 stays basic in its row, as its surplus
+        assert_eq!(res.basis, vec![0, 2]);
+    }
+    #[test]
+    fn simplex_test_unbounded() {
+        /*
+        Objective function = x + y
+        Constraints:
+        x - y <= 2
+        -x + y <= 3
+        x >= 1
+        x, y >= 0
+        */
+        let obj_f: Vec<f32> = vec![1.0, 1.0];
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(3, 2, &[
+            1.0, -1.0,
+            -1.0, 1.0,
+            1.0, 0.0,
+        ]);
+        let senses = vec![Sense::Le, Sense::Le, Sense::Ge];
+        let req = vec![2.0, 3.0, 1.0];
+        let res = two_phase_simplex(
+            constraints.clone(),
+            senses,
+            req,
+            obj_f.clone(),
+            &Options::default(),
+        );
+        assert_eq!(res.status, Status::Unbounded);
+        let ray = res.ray.expect("Unbounded problems come with a ray");
+        // The ray must keep the constraints satisfied and improve the objective function
+        assert!(ray.iter().all(|&d| d >= 0.0));
+        let d = na::DVector::from_column_slice(&ray[..2]);
+        let ad = &constraints * &d;
+        assert!(ad[0] <= 1E-4 && ad[1] <= 1E-4 && ad[2] >= -1E-4);
+        assert!(obj_f[0] * ray[0] + obj_f[1] * ray[1] > 0.0);
+    }
+    #[test]
+    fn simplex_test_pivot_rules() {
+        /*
+        The example of Beale (as given by Chvátal), which cycles with the textbook rules
+        Objective function = 0.75x4 - 20x5 + 0.5x6 - 6x7
+        Constraints:
+        0.25x4 - 8x5 - x6 + 9x7 <= 0
+        0.5x4 - 12x5 - 0.5x6 + 3x7 <= 0
+        x6 <= 1
+        x4, x5, x6, x7 >= 0
+        */
+        let obj_f: Vec<f32> = vec![0.75, -20.0, 0.5, -6.0];
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(3, 4, &[
+            0.25, -8.0, -1.0, 9.0,
+            0.5, -12.0, -0.5, 3.0,
+            0.0, 0.0, 1.0, 0.0,
+        ]);
+        let req = vec![0.0, 0.0, 1.0];
+        let senses = vec![Sense::Le; 3];
+
+        let rules: [&dyn PivotRule<f32>; 3] = [&Bland, &SteepestEdge, &LargestImprovement];
+        for &rule in rules.iter() {
+            let options = Options {
+                pivot_rule: rule,
+                max_iterations: 100,
+                observer: &Silent,
+                refactor_every: 64,
+                algorithm: Algorithm::Simplex,
+                presolve: false,
+            };
+            let res = two_phase_simplex(
+                constraints.clone(),
+                senses.clone(),
+                req.clone(),
+                obj_f.clone(),
+                &options,
+            );
+            assert_eq!(res.status, Status::Optimal);
+            assert!(
+                (res.objective - 1.25).abs() < 1E-5,
+                "The expected optimal value: 1.25, the value we got: {}",
+                res.objective
+            );
+        }
+
+        let options = Options {
+            max_iterations: 100,
+            ..Options::default()
+        };
+        let res = two_phase_simplex(constraints, senses, req, obj_f, &options);
+        assert_eq!(res.status, Status::IterationLimit);
+    }
+
+    #[test]
+    fn simplex_test_rational() {
+        /*
+        Objective function = 35x + 45y + 55z
+        Constraints:
+        20x + 15y + 20z <= 550
+        27x + 35y + 50z <= 900
+        10x + 5y + 10z <= 550
+        x, y, z >= 0
+        */
+        let obj_f: Vec<Rational> = [35.0, 45.0, 55.0].iter().map(|&c| num(c)).collect();
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_iterator(3, 3, [
+            20.0, 27.0, 10.0,
+            15.0, 35.0, 5.0,
+            20.0, 50.0, 10.0,
+        ].iter().map(|&a| num(a)));
+        let req = [550.0, 900.0, 550.0].iter().map(|&b| num(b)).collect();
+        let res = simplex_method(constraints, req, obj_f, &Silent);
+        assert_eq!(res.status, Status::Optimal);
+        // 1162.7118 in floats, but exactly 68600/59
+        assert_eq!(res.objective, Rational::new(68_600, 59));
+        assert!((res.objective.to_f64() - 1162.7118).abs() < 1E-4);
+    }
+    #[test]
+    fn simplex_test_floats_agree() {
+        let obj_f: Vec<f32> = vec![-2.0, -3.0];
+        let constraints = vec![1.0, 1.0, 1.0, 3.0, 1.0, 0.0];
+        let senses = vec![Sense::Ge, Sense::Ge, Sense::Le];
+        let req = vec![4.0, 6.0, 5.0];
+
+        let single: Solution<f32> = two_phase_simplex(
+            na::DMatrix::from_row_slice(3, 2, &constraints),
+            senses.clone(),
+            req.clone(),
+            obj_f.clone(),
+            &Options::default(),
+        );
+        let double: Solution<f64> = two_phase_simplex(
+            na::DMatrix::from_row_slice(3, 2, &constraints).map(f64::from),
+            senses,
+            req.iter().map(|&b| f64::from(b)).collect(),
+            obj_f.iter().map(|&c| f64::from(c)).collect(),
+            &Options::default(),
+        );
+        assert_eq!(single.basis, double.basis);
+        assert!((f64::from(single.objective) - double.objective).abs() < 1E-5);
+        assert!((double.objective + 9.0).abs() < 1E-12);
+    }
+
+    #[test]
+    fn simplex_test_bounded_problem1() {
+        // simplex_test_problem1 with z <= 7 as a bound rather than a row
+        let obj_f: Vec<f64> = vec![20_000.0, 45_000.0, 85_000.0];
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(3, 3, &[
+            10.0, 15.0, 10.0,
+            13.0, 5.0, 5.0,
+            20.0, 5.0, 10.0,
+        ]);
+        let req = vec![720.0, 680.0, 550.0];
+        let bounds = vec![(Some(0.0), None), (Some(0.0), None), (Some(0.0), Some(7.0))];
+        let res = bounded_simplex(
+            constraints,
+            vec![Sense::Le; 3],
+            req,
+            obj_f,
+            &bounds,
+            &Options::default(),
+        );
+        assert_eq!(res.status, Status::Optimal);
+        assert_eq!(res.table.nrows(), 4);
+        assert!(
+            (res.objective - 2_545_000.0).abs() < 1E-6,
+            "The expected optimal value: 2_545_000, the value we got: {}",
+            res.objective
+        );
+        assert!((res.x[2] - 7.0).abs() < 1E-9);
+    }
+
+    #[test]
+    fn simplex_test_bounded_flip() {
+        /*
+        Objective function = 3x + 2y - z
+        Constraints:
+        x + y - z <= 3
+        -1 <= x <= 1, 0 <= y <= 5, z free
+        */
+        let obj_f: Vec<f64> = vec![3.0, 2.0, -1.0];
+        let constraints = na::DMatrix::from_row_slice(1, 3, &[1.0, 1.0, -1.0]);
+        let bounds = vec![
+            (Some(-1.0), Some(1.0)),
+            (Some(0.0), Some(5.0)),
+            (None, None),
+        ];
+        let res = bounded_simplex(
+            constraints,
+            vec![Sense::Le],
+            vec![3.0],
+            obj_f,
+            &bounds,
+            &Options::default(),
+        );
+        // x and y jump to their upper bounds, z makes room for them at the cost of 1 per unit
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.objective - 10.0).abs() < 1E-9, "{}", res.objective);
+        assert!((res.x[0] - 1.0).abs() < 1E-9 && (res.x[1] - 5.0).abs() < 1E-9);
+        assert!((res.x[2] - 3.0).abs() < 1E-9);
+        assert!((res.duals[0] - 1.0).abs() < 1E-9);
+        assert!((res.reduced_costs[0] - 2.0).abs() < 1E-9);
+        assert!((res.reduced_costs[1] - 1.0).abs() < 1E-9);
+        assert!(res.reduced_costs[2].abs() < 1E-9);
+    }
+
+    #[test]
+    fn simplex_test_bounded_free_and_unbounded() {
+        /*
+        Objective function = -d
+        Constraints:
+        x - d <= 2
+        x = 5
+        x, d free
+        */
+        let constraints = na::DMatrix::from_row_slice(2, 2, &[1.0, -1.0, 1.0, 0.0]);
+        let bounds: Vec<Interval<f64>> = vec![(None, None); 2];
+        let res = bounded_simplex(
+            constraints.clone(),
+            vec![Sense::Le, Sense::Eq],
+            vec![2.0, 5.0],
+            vec![0.0, -1.0],
+            &bounds,
+            &Options::default(),
+        );
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.x[0] - 5.0).abs() < 1E-9 && (res.x[1] - 3.0).abs() < 1E-9);
+        assert!((res.objective + 3.0).abs() < 1E-9);
+
+        // Without the upper bound on x - d, d falls without limit
+        let res = bounded_simplex(
+            constraints,
+            vec![Sense::Ge, Sense::Eq],
+            vec![2.0, 5.0],
+            vec![0.0, -1.0],
+            &bounds,
+            &Options::default(),
+        );
+        assert_eq!(res.status, Status::Unbounded);
+        let ray = res.ray.unwrap();
+        assert!(ray[1] < 0.0, "{:?}", ray);
+    }
+}
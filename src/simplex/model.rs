@@ -0,0 +1,689 @@
+/// A friendlier way to state linear programs: named variables with bounds,
+/// constraints of any sense and the choice between minimization and maximization.
+/// The program is converted into the table form `bounded_simplex` works with.
+use super::farkas::certificate;
+use super::presolve::{infeasible, presolve};
+use super::sensitivity::{self, Interval, Ranging};
+use super::{
+    bounded_simplex, dot, interior_point, revised_simplex, Algorithm, Number, Options, Sense,
+    Solution, SparseMatrix, Status, WarmStart,
+};
+use nalgebra as na;
+
+/// Whether the objective function goes up or down
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Maximize,
+    Minimize,
+}
+
+/// The constraints, senses, requirements, objective function and bounds of the variables
+pub type TableForm<T> = (na::DMatrix<T>, Vec<Sense>, Vec<T>, Vec<T>, Vec<Interval<T>>);
+
+/// The table form with a sparse matrix of the constraints
+pub type SparseForm<T> = (
+    SparseMatrix<T>,
+    Vec<Sense>,
+    Vec<T>,
+    Vec<T>,
+    Vec<Interval<T>>,
+);
+
+/// A handle of a variable added to a `LinearProgram`, its index in `Solution::x`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Variable(pub usize);
+
+/// A handle of a constraint added to a `LinearProgram`, its index in `Solution::slack`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Constraint(pub usize);
+
+#[derive(Debug, Clone)]
+pub struct VariableData<T: Number> {
+    pub name: String,
+    /// `None` if there is no lower bound
+    pub lower: Option<T>,
+    /// `None` if there is no upper bound
+    pub upper: Option<T>,
+    /// Only whole values are allowed, which the simplex method itself ignores
+    pub integer: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConstraintData<T: Number> {
+    pub name: String,
+    /// The non-zero coefficients of the constraint
+    pub terms: Vec<(Variable, T)>,
+    pub sense: Sense,
+    pub rhs: T,
+}
+
+/// The linear program built piece by piece
+#[derive(Debug, Clone)]
+pub struct LinearProgram<T: Number> {
+    pub direction: Direction,
+    pub variables: Vec<VariableData<T>>,
+    /// The coefficients of the objective function, one per variable
+    pub objective: Vec<T>,
+    /// The constant term of the objective function
+    pub constant: T,
+    pub constraints: Vec<ConstraintData<T>>,
+}
+
+impl<T: Number> Default for LinearProgram<T> {
+    fn default() -> Self {
+        LinearProgram {
+            direction: Direction::Maximize,
+            variables: Vec::new(),
+            objective: Vec::new(),
+            constant: T::zero(),
+            constraints: Vec::new(),
+        }
+    }
+}
+
+impl<T: Number> LinearProgram<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a variable lying within [lower, upper]
+    pub fn add_variable(&mut self, name: &str, lower: T, upper: Option<T>) -> Variable {
+        self.variables.push(VariableData {
+            name: name.to_string(),
+            lower: None,
+            upper: None,
+            integer: false,
+        });
+        self.objective.push(T::zero());
+        let var = Variable(self.variables.len() - 1);
+        self.set_bounds(var, Some(lower), upper);
+        var
+    }
+
+    /// Adds a variable that can take any value, e.g. a deviation of either sign
+    pub fn add_free_variable(&mut self, name: &str) -> Variable {
+        let var = self.add_variable(name, T::zero(), None);
+        self.set_bounds(var, None, None);
+        var
+    }
+
+    /// Changes the bounds of the variable, `None` stands for no limit on that side.
+    /// Crossed bounds make the program infeasible.
+    pub fn set_bounds(&mut self, var: Variable, lower: Option<T>, upper: Option<T>) {
+        let data = &mut self.variables[var.0];
+        data.lower = lower;
+        data.upper = upper;
+    }
+
+    /// Adds a variable taking whole values within [lower, upper]
+    pub fn add_integer_variable(&mut self, name: &str, lower: T, upper: Option<T>) -> Variable {
+        let var = self.add_variable(name, lower, upper);
+        self.set_integer(var, true);
+        var
+    }
+
+    /// Adds a variable that is either 0 or 1
+    pub fn add_binary_variable(&mut self, name: &str) -> Variable {
+        self.add_integer_variable(name, T::zero(), Some(T::one()))
+    }
+
+    /// Allows only whole values of the variable or lifts this restriction
+    pub fn set_integer(&mut self, var: Variable, integer: bool) {
+        self.variables[var.0].integer = integer;
+    }
+
+    /// Whether any of the variables has to be whole
+    pub fn is_mixed_integer(&self) -> bool {
+        self.variables.iter().any(|v| v.integer)
+    }
+
+    /// Adds the constraint `sum(coef * var) <sense> rhs`.
+    /// Repeated variables have their coefficients summed up.
+    pub fn add_constraint(
+        &mut self,
+        name: &str,
+        terms: &[(Variable, T)],
+        sense: Sense,
+        rhs: T,
+    ) -> Constraint {
+        let mut merged: Vec<(Variable, T)> = Vec::with_capacity(terms.len());
+        for (var, coef) in terms {
+            assert!(var.0 < self.variables.len(), "Unknown variable {:?}", var);
+            match merged.iter_mut().find(|(v, _)| v == var) {
+                Some((_, c)) => *c += coef,
+                None => merged.push((*var, coef.clone())),
+            }
+        }
+        self.constraints.push(ConstraintData {
+            name: name.to_string(),
+            terms: merged,
+            sense,
+            rhs,
+        });
+        Constraint(self.constraints.len() - 1)
+    }
+
+    /// Adds a variable within [0, infinity) along with its coefficient in the objective
+    /// function and in the constraints, e.g. a column of column generation
+    pub fn add_column(&mut self, name: &str, cost: T, terms: &[(Constraint, T)]) -> Variable {
+        let var = self.add_variable(name, T::zero(), None);
+        self.objective[var.0] = cost;
+        for (c, coef) in terms {
+            assert!(c.0 < self.constraints.len(), "Unknown constraint {:?}", c);
+            let row = &mut self.constraints[c.0].terms;
+            match row.iter_mut().find(|(v, _)| *v == var) {
+                Some((_, a)) => *a += coef,
+                None => row.push((var, coef.clone())),
+            }
+        }
+        var
+    }
+
+    /// Sets the objective function to be maximized
+    pub fn maximize(&mut self, terms: &[(Variable, T)]) {
+        self.set_objective(Direction::Maximize, terms);
+    }
+
+    /// Sets the objective function to be minimized
+    pub fn minimize(&mut self, terms: &[(Variable, T)]) {
+        self.set_objective(Direction::Minimize, terms);
+    }
+
+    fn set_objective(&mut self, direction: Direction, terms: &[(Variable, T)]) {
+        self.direction = direction;
+        self.objective = vec![T::zero(); self.variables.len()];
+        self.constant = T::zero();
+        for (var, coef) in terms {
+            self.objective[var.0] += coef;
+        }
+    }
+
+    /// Looks a variable up by its name
+    pub fn variable(&self, name: &str) -> Option<Variable> {
+        self.variables
+            .iter()
+            .position(|v| v.name == name)
+            .map(Variable)
+    }
+
+    /// Looks a constraint up by its name
+    pub fn constraint(&self, name: &str) -> Option<Constraint> {
+        self.constraints
+            .iter()
+            .position(|c| c.name == name)
+            .map(Constraint)
+    }
+
+    /// Solves the program with the method of `options.algorithm`, by default the bounded
+    /// two-phase simplex method.
+    /// The solution is stated in terms of the program: `x` holds a value per variable,
+    /// `slack` a value per constraint and `objective` follows the chosen direction.
+    /// `basis` and `table` describe the internal table, where every variable is measured from
+    /// one of its bounds (free ones from zero). Only the simplex method forms a table.
+    /// With `options.presolve`, `basis` is that of the reduced program and `table` is empty.
+    pub fn solve(&self, options: &Options<T>) -> Solution<T> {
+        if options.presolve {
+            let presolved = match presolve(self) {
+                Ok(presolved) => presolved,
+                Err(_) => return infeasible(self),
+            };
+            let options = Options {
+                presolve: false,
+                ..*options
+            };
+            return presolved.postsolve(&presolved.program.solve(&options));
+        }
+        let solution = match options.algorithm {
+            Algorithm::Simplex => {
+                let (constr, senses, req, obj, bounds) = self.to_table_form();
+                bounded_simplex(constr, senses, req, obj, &bounds, options)
+            }
+            Algorithm::RevisedSimplex => return self.solve_revised(options),
+            Algorithm::InteriorPoint { crossover } => {
+                let (constr, senses, req, obj, bounds) = self.to_table_form();
+                interior_point(constr, senses, req, obj, &bounds, crossover, options)
+            }
+        };
+        self.map_solution(solution)
+    }
+
+    /// Solves the program with the revised simplex method, which keeps the constraints sparse.
+    /// The solution is stated in terms of the program as the one of `solve`, but has no table.
+    pub fn solve_revised(&self, options: &Options<T>) -> Solution<T> {
+        let (constr, senses, req, obj, bounds) = self.to_sparse_form();
+        let solution = revised_simplex(constr, senses, req, obj, &bounds, options);
+        self.map_solution(solution)
+    }
+
+    /// Keeps the program to be solved again and again after changes,
+    /// each time starting from the basis of the last solution
+    pub fn warm_start(self) -> WarmProgram<T> {
+        let (constr, senses, req, obj, bounds) = self.to_sparse_form();
+        WarmProgram {
+            warm: WarmStart::new(constr, senses, req, obj, &bounds),
+            program: self,
+        }
+    }
+
+    /// Converts the program into the inputs of `bounded_simplex`,
+    /// where the objective function is maximized.
+    pub fn to_table_form(&self) -> TableForm<T> {
+        let (constr, senses, req, obj, bounds) = self.to_sparse_form();
+        (constr.to_dense(), senses, req, obj, bounds)
+    }
+
+    /// Converts the program into the inputs of `revised_simplex`,
+    /// where the objective function is maximized.
+    pub fn to_sparse_form(&self) -> SparseForm<T> {
+        let mut triplets = Vec::new();
+        for (i, c) in self.constraints.iter().enumerate() {
+            for (var, coef) in &c.terms {
+                triplets.push((i, var.0, coef.clone()));
+            }
+        }
+        let constr =
+            SparseMatrix::from_triplets(self.constraints.len(), self.variables.len(), &triplets);
+        let senses = self.constraints.iter().map(|c| c.sense).collect();
+        let req = self.constraints.iter().map(|c| c.rhs.clone()).collect();
+        let bounds = (self.variables.iter())
+            .map(|v| (v.lower.clone(), v.upper.clone()))
+            .collect();
+        let sign = self.sign();
+        let obj = self.objective.iter().map(|c| sign.clone() * c).collect();
+        (constr, senses, req, obj, bounds)
+    }
+
+    /// Brings the solution of the maximized table form back to the direction of the program
+    fn map_solution(&self, mut solution: Solution<T>) -> Solution<T> {
+        let sign = self.sign();
+        if solution.status == Status::Optimal {
+            for d in solution.duals.iter_mut().chain(&mut solution.reduced_costs) {
+                *d *= &sign;
+            }
+        }
+        solution.objective = match solution.status {
+            Status::Optimal => sign * &solution.objective + &self.constant,
+            _ => self.objective_value(&solution.x),
+        };
+        solution
+    }
+
+    /// The ranges of the constraints' right-hand sides and of the objective function
+    /// coefficients over which the basis of an optimal solution of this program stays optimal,
+    /// `None` if the solution isn't optimal or has no table to read them from
+    pub fn ranging(&self, solution: &Solution<T>) -> Option<Ranging<T>> {
+        let (_, senses, req, obj, _) = self.to_table_form();
+        let ranging = sensitivity::ranging(solution, &senses, &req, &obj)?;
+        let objective = ranging
+            .objective
+            .into_iter()
+            .map(|(low, high)| match self.direction {
+                Direction::Maximize => (low, high),
+                Direction::Minimize => (high.map(|h| -h), low.map(|l| -l)),
+            })
+            .collect();
+        Some(Ranging {
+            rhs: ranging.rhs,
+            objective,
+        })
+    }
+
+    /// The multipliers of the constraints proving the program infeasible
+    /// (see `farkas::is_certificate`), `None` if it is feasible
+    pub fn farkas(&self) -> Option<Vec<T>> {
+        let (constr, senses, req, _, bounds) = self.to_sparse_form();
+        certificate(constr, senses, req, &bounds)
+    }
+
+    /// The value of the objective function at the given point
+    pub fn objective_value(&self, x: &[T]) -> T {
+        dot(&self.objective, x) + &self.constant
+    }
+
+    /// 1 for maximization and -1 for minimization
+    pub(crate) fn sign(&self) -> T {
+        match self.direction {
+            Direction::Maximize => T::one(),
+            Direction::Minimize => -T::one(),
+        }
+    }
+}
+
+/// A `LinearProgram` along with the `WarmStart` of its table form,
+/// the changes go to both of them
+#[derive(Debug, Clone)]
+pub struct WarmProgram<T: Number> {
+    program: LinearProgram<T>,
+    warm: WarmStart<T>,
+}
+
+impl<T: Number> WarmProgram<T> {
+    pub fn program(&self) -> &LinearProgram<T> {
+        &self.program
+    }
+
+    /// Solves the program, from the last basis if there was a solution before.
+    /// The solution is stated in terms of the program as the one of `solve_revised`.
+    pub fn solve(&mut self, options: &Options<T>) -> Solution<T> {
+        let solution = self.warm.solve(options);
+        self.program.map_solution(solution)
+    }
+
+    /// The number of pivots the last call to `solve` took
+    pub fn iterations(&self) -> usize {
+        self.warm.iterations()
+    }
+
+    /// Adds a constraint, e.g. a cut
+    pub fn add_constraint(
+        &mut self,
+        name: &str,
+        terms: &[(Variable, T)],
+        sense: Sense,
+        rhs: T,
+    ) -> Constraint {
+        let c = self.program.add_constraint(name, terms, sense, rhs.clone());
+        let terms: Vec<_> = (self.program.constraints[c.0].terms.iter())
+            .map(|(var, coef)| (var.0, coef.clone()))
+            .collect();
+        self.warm.add_row(&terms, sense, rhs);
+        c
+    }
+
+    /// Changes the right-hand side of the constraint
+    pub fn set_rhs(&mut self, c: Constraint, rhs: T) {
+        self.program.constraints[c.0].rhs = rhs.clone();
+        self.warm.set_rhs(c.0, rhs);
+    }
+
+    /// Changes the bounds of the variable, `None` stands for no limit on that side
+    pub fn set_bounds(&mut self, var: Variable, lower: Option<T>, upper: Option<T>) {
+        self.program.set_bounds(var, lower.clone(), upper.clone());
+        self.warm.set_bounds(var.0, lower, upper);
+    }
+}
+
+impl<T: Number> Solution<T> {
+    /// The value of the given variable of a `LinearProgram`
+    pub fn value(&self, var: Variable) -> T {
+        self.x[var.0].clone()
+    }
+
+    /// The value of the variable called `name` in the `LinearProgram` solved
+    pub fn value_of(&self, lp: &LinearProgram<T>, name: &str) -> Option<T> {
+        lp.variable(name).map(|var| self.value(var))
+    }
+
+    /// The name and the value of every variable of the `LinearProgram` solved
+    pub fn named_values<'a>(&self, lp: &'a LinearProgram<T>) -> Vec<(&'a str, T)> {
+        (lp.variables.iter())
+            .zip(&self.x)
+            .map(|(var, value)| (var.name.as_str(), value.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_test_minimize() {
+        /*
+        Objective function = 2x + 3y -> min
+        Constraints:
+        x + y >= 4
+        x + 3y >= 6
+        0 <= x <= 5, y >= 0
+        */
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 0.0, Some(5.0));
+        let y = lp.add_variable("y", 0.0, None);
+        lp.add_constraint("c1", &[(x, 1.0), (y, 1.0)], Sense::Ge, 4.0);
+        let c2 = lp.add_constraint("c2", &[(x, 1.0), (y, 3.0)], Sense::Ge, 6.0);
+        lp.minimize(&[(x, 2.0), (y, 3.0)]);
+
+        let res = lp.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!(
+            (res.objective - 9.0).abs() < 1E-4,
+            "The expected optimal value: 9, the value we got: {}",
+            res.objective
+        );
+        assert!((res.value(x) - 3.0).abs() < 1E-4 && (res.value(y) - 1.0).abs() < 1E-4);
+        assert_eq!(res.slack.len(), 2);
+        assert_eq!(lp.constraint("c2"), Some(c2));
+        assert_eq!(lp.variable("y"), Some(y));
+    }
+
+    #[test]
+    fn model_test_algorithms() {
+        // The problem of `model_test_minimize` with each of the methods
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 0.0, Some(5.0));
+        let y = lp.add_variable("y", 0.0, None);
+        lp.add_constraint("c1", &[(x, 1.0), (y, 1.0)], Sense::Ge, 4.0);
+        lp.add_constraint("c2", &[(x, 1.0), (y, 3.0)], Sense::Ge, 6.0);
+        lp.minimize(&[(x, 2.0), (y, 3.0)]);
+
+        let algorithms = [
+            Algorithm::Simplex,
+            Algorithm::RevisedSimplex,
+            Algorithm::InteriorPoint { crossover: false },
+            Algorithm::InteriorPoint { crossover: true },
+        ];
+        for &algorithm in algorithms.iter() {
+            let options = Options {
+                algorithm,
+                ..Options::default()
+            };
+            let res = lp.solve(&options);
+            assert_eq!(res.status, Status::Optimal);
+            assert!((res.objective - 9.0).abs() < 1E-6, "{:?}", algorithm);
+            assert!((res.value(x) - 3.0).abs() < 1E-6 && (res.value(y) - 1.0).abs() < 1E-6);
+            assert!((res.duals[0] - 1.5).abs() < 1E-6 && (res.duals[1] - 0.5).abs() < 1E-6);
+        }
+    }
+
+    #[test]
+    fn model_test_bounds() {
+        /*
+        Objective function = x + 2y -> min
+        Constraints:
+        x + y + x >= 9
+        1 <= x <= 4, y >= 2
+        */
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 1.0, Some(4.0));
+        let y = lp.add_variable("y", 2.0, None);
+        lp.add_constraint("c1", &[(x, 1.0), (y, 1.0), (x, 1.0)], Sense::Ge, 9.0);
+        lp.minimize(&[(x, 1.0), (y, 2.0)]);
+
+        let res = lp.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!(
+            (res.objective - 7.5).abs() < 1E-4,
+            "The expected optimal value: 7.5, the value we got: {}",
+            res.objective
+        );
+        assert!((res.value(x) - 3.5).abs() < 1E-4 && (res.value(y) - 2.0).abs() < 1E-4);
+    }
+
+    #[test]
+    fn model_test_crossed_bounds() {
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 0.0, None);
+        let y = lp.add_variable("y", 0.0, None);
+        lp.add_constraint("c1", &[(x, 1.0), (y, 1.0)], Sense::Le, 10.0);
+        lp.maximize(&[(x, 1.0), (y, 1.0)]);
+        lp.set_bounds(x, Some(5.0), Some(2.0));
+        for algorithm in [
+            Algorithm::Simplex,
+            Algorithm::RevisedSimplex,
+            Algorithm::InteriorPoint { crossover: true },
+        ] {
+            for presolve in [false, true] {
+                let options = Options {
+                    algorithm,
+                    presolve,
+                    ..Options::default()
+                };
+                assert_eq!(lp.solve(&options).status, Status::Infeasible);
+            }
+        }
+    }
+
+    #[test]
+    fn model_test_free_variables() {
+        /*
+        Objective function = e -> min
+        Constraints:
+        e >= x - 4
+        e >= 4 - x
+        x + y = 1
+        y <= -2, x <= 6, e free
+        */
+        let mut lp = LinearProgram::<f64>::new();
+        let e = lp.add_free_variable("e");
+        let x = lp.add_variable("x", 0.0, Some(6.0));
+        let y = lp.add_free_variable("y");
+        lp.set_bounds(y, None, Some(-2.0));
+        lp.add_constraint("above", &[(e, 1.0), (x, -1.0)], Sense::Ge, -4.0);
+        lp.add_constraint("below", &[(e, 1.0), (x, 1.0)], Sense::Ge, 4.0);
+        lp.add_constraint("sum", &[(x, 1.0), (y, 1.0)], Sense::Eq, 1.0);
+        lp.minimize(&[(e, 1.0)]);
+
+        // The bounds take no rows of the table
+        let (constr, ..) = lp.to_table_form();
+        assert_eq!(constr.nrows(), 3);
+        let res = lp.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!(res.objective.abs() < 1E-9, "{}", res.objective);
+        assert!((res.value(x) - 4.0).abs() < 1E-9 && (res.value(y) + 3.0).abs() < 1E-9);
+        let revised = lp.solve_revised(&Options::default());
+        assert!((revised.objective - res.objective).abs() < 1E-9);
+
+        // With y >= -1, x can't get past 2 and stays 2 away from 4
+        lp.set_bounds(y, Some(-1.0), None);
+        let res = lp.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.objective - 2.0).abs() < 1E-9, "{}", res.objective);
+        assert!((res.value(x) - 2.0).abs() < 1E-9 && (res.value(y) + 1.0).abs() < 1E-9);
+    }
+
+    #[test]
+    fn model_test_warm_start() {
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 0.0, None);
+        let y = lp.add_variable("y", 0.0, None);
+        let z = lp.add_variable("z", 0.0, Some(7.0));
+        lp.add_constraint("c1", &[(x, 10.0), (y, 15.0), (z, 10.0)], Sense::Le, 720.0);
+        let c2 = lp.add_constraint("c2", &[(x, 13.0), (y, 5.0), (z, 5.0)], Sense::Le, 680.0);
+        lp.add_constraint("c3", &[(x, 20.0), (y, 5.0), (z, 10.0)], Sense::Le, 550.0);
+        lp.minimize(&[(x, -20_000.0), (y, -45_000.0), (z, -85_000.0)]);
+
+        let mut warm = lp.warm_start();
+        let res = warm.solve(&Options::default());
+        assert!(
+            (res.objective + 2_545_000.0).abs() < 1E-6,
+            "{}",
+            res.objective
+        );
+        // Every change gives what solving the changed program from scratch does
+        let check = |warm: &mut WarmProgram<f64>| {
+            let res = warm.solve(&Options::default());
+            let scratch = warm.program().solve(&Options::default());
+            assert_eq!(res.status, scratch.status);
+            if res.status == Status::Optimal {
+                assert!(
+                    (res.objective - scratch.objective).abs() < 1E-6,
+                    "{} {}",
+                    res.objective,
+                    scratch.objective
+                );
+            }
+            res.status
+        };
+        warm.add_constraint("cut", &[(y, 1.0), (z, 1.0)], Sense::Le, 40.0);
+        assert_eq!(check(&mut warm), Status::Optimal);
+        warm.set_rhs(c2, 300.0);
+        check(&mut warm);
+        warm.set_bounds(z, Some(2.0), Some(3.0));
+        check(&mut warm);
+        // c2 leaves room for x <= 290 / 13 at most
+        let more = warm.add_constraint("more", &[(x, 1.0)], Sense::Ge, 30.0);
+        assert_eq!(check(&mut warm), Status::Infeasible);
+        warm.set_rhs(more, 20.0);
+        assert_eq!(check(&mut warm), Status::Optimal);
+        assert_eq!(warm.program().constraints.len(), 5);
+    }
+
+    #[test]
+    fn model_test_same_as_simplex_method() {
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 0.0, None);
+        let y = lp.add_variable("y", 0.0, None);
+        let z = lp.add_variable("z", 0.0, Some(7.0));
+        lp.add_constraint("c1", &[(x, 10.0), (y, 15.0), (z, 10.0)], Sense::Le, 720.0);
+        lp.add_constraint("c2", &[(x, 13.0), (y, 5.0), (z, 5.0)], Sense::Le, 680.0);
+        lp.add_constraint("c3", &[(x, 20.0), (y, 5.0), (z, 10.0)], Sense::Le, 550.0);
+        lp.maximize(&[(x, 20_000.0), (y, 45_000.0), (z, 85_000.0)]);
+
+        let res = lp.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!(
+            (res.objective - 2_545_000.0).abs() < 1.0,
+            "The expected optimal value: 2_545_000, the value we got: {}",
+            res.objective
+        );
+        assert!((lp.objective_value(&res.x) - res.objective).abs() < 1.0);
+    }
+
+    #[test]
+    fn model_test_sensitivity() {
+        /*
+        Objective function = 2x + 3y -> min
+        Constraints:
+        x + y >= 4
+        x + 3y >= 6
+        0 <= x <= 2, y >= 0
+        */
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 0.0, Some(2.0));
+        let y = lp.add_variable("y", 0.0, None);
+        lp.add_constraint("c1", &[(x, 1.0), (y, 1.0)], Sense::Ge, 4.0);
+        lp.add_constraint("c2", &[(x, 1.0), (y, 3.0)], Sense::Ge, 6.0);
+        lp.minimize(&[(x, 2.0), (y, 3.0)]);
+
+        let res = lp.solve(&Options::default());
+        assert!((res.objective - 10.0).abs() < 1E-4, "{}", res.objective);
+        // Every extra unit of c1 costs 3 more, c2 is not binding
+        assert_eq!(res.duals.len(), 2);
+        assert!((res.duals[0] - 3.0).abs() < 1E-4 && res.duals[1].abs() < 1E-4);
+        // x sits at its upper bound, each extra unit of it would save 1
+        assert!((res.reduced_costs[0] + 1.0).abs() < 1E-4 && res.reduced_costs[1].abs() < 1E-4);
+        // The revised method prices the constraints and variables the same way
+        let revised = lp.solve_revised(&Options::default());
+        assert!((revised.objective - res.objective).abs() < 1E-9);
+        let prices = revised.duals.iter().chain(&revised.reduced_costs);
+        for (a, b) in prices.zip(res.duals.iter().chain(&res.reduced_costs)) {
+            assert!((a - b).abs() < 1E-9, "{:?} {:?}", revised.duals, res.duals);
+        }
+
+        let ranges = lp.ranging(&res).unwrap();
+        // Neither the revised method nor the interior point method forms a table
+        assert_eq!(lp.ranging(&revised), None);
+        let interior = lp.solve(&Options {
+            algorithm: Algorithm::InteriorPoint { crossover: true },
+            ..Options::default()
+        });
+        assert_eq!(interior.status, Status::Optimal);
+        assert_eq!(lp.ranging(&interior), None);
+        // c1 is binding until y alone has to cover c2: 4 - 2 >= 4/3
+        assert!((ranges.rhs[0].0.unwrap() - 10.0 / 3.0).abs() < 1E-3 && ranges.rhs[0].1.is_none());
+        // x stays at its upper bound while it is cheaper than y
+        assert!(
+            ranges.objective[0].0.is_none() && (ranges.objective[0].1.unwrap() - 3.0).abs() < 1E-4
+        );
+    }
+}
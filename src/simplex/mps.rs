@@ -0,0 +1,768 @@
+/// Reading and writing linear programs in the MPS format, the usual way to exchange
+/// models between solvers. The fixed format keeps every field in its own columns,
+/// so names may contain spaces, the free one separates fields with spaces.
+/// [https://en.wikipedia.org/wiki/MPS_(format)]
+/// [http://lpsolve.sourceforge.net/5.5/mps-format.htm]
+use super::{Direction, LinearProgram, Number, Sense, Variable};
+use std::collections::HashMap;
+use std::{error::Error, fmt};
+
+/// The flavour of the MPS format
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MpsFormat {
+    /// Fields start at the columns 2, 5, 15, 25, 40 and 50, names are at most 8 characters long
+    Fixed,
+    /// Fields are separated by whitespace, names can't contain any
+    Free,
+}
+
+/// A model file that couldn't be read
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// The number of the offending line, starting at 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(line: usize, message: impl Into<String>) -> Self {
+        ParseError {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+/// A program that can't be written down in the chosen flavour of the format
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteError {
+    /// The name of the offending row or column
+    pub name: String,
+    pub message: String,
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.message)
+    }
+}
+
+impl Error for WriteError {}
+
+/// The first columns of the fields of the fixed format (counting from 0)
+const FIELD_STARTS: [usize; 6] = [1, 4, 14, 24, 39, 49];
+const FIELD_ENDS: [usize; 6] = [3, 12, 22, 36, 47, 61];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Name,
+    ObjSense,
+    Rows,
+    Columns,
+    Rhs,
+    Ranges,
+    Bounds,
+    End,
+}
+
+/// What a row of the file stands for
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Row {
+    Objective,
+    /// Free rows after the first one carry no meaning for the program
+    Ignored,
+    Constraint(usize),
+}
+
+/// Reads a linear program out of an MPS file.
+/// The first free row (`N`) is the objective function, which is minimized
+/// unless an `OBJSENSE` section says otherwise. Integer variables are marked
+/// with `'INTORG'`/`'INTEND'` markers or bounds of the `BV`, `LI` and `UI` types.
+/// A ranged row lo <= a * x <= hi becomes two constraints: the row itself keeps
+/// one side and the other side is added at the end as `<row>_range`.
+/// The requirement of the objective function is the negated constant of it.
+pub fn read_mps<T: Number>(text: &str, format: MpsFormat) -> Result<LinearProgram<T>, ParseError> {
+    let mut lp = LinearProgram::new();
+    lp.direction = Direction::Minimize;
+    let mut rows: HashMap<String, Row> = HashMap::new();
+    let mut columns: HashMap<String, Variable> = HashMap::new();
+    let mut ranges: Vec<(usize, T)> = Vec::new();
+    let mut integer = false;
+    let mut section = Section::Name;
+
+    for (i, line) in text.lines().enumerate() {
+        let line_no = i + 1;
+        let err = |message: String| ParseError::new(line_no, message);
+        if line.trim().is_empty() || line.starts_with('*') {
+            continue;
+        }
+        if section == Section::End {
+            return Err(err("Nothing may follow ENDATA".to_string()));
+        }
+        // Section headers start right at the first column, their data is indented
+        if !line.starts_with(char::is_whitespace) {
+            let mut words = line.split_whitespace();
+            let keyword = words.next().expect("The line is not blank");
+            section = match keyword {
+                "NAME" => Section::Name,
+                "OBJSENSE" => match words.next() {
+                    Some(sense) => {
+                        lp.direction = parse_direction(sense)
+                            .ok_or_else(|| err(format!("Unknown objective sense {}", sense)))?;
+                        Section::Name
+                    }
+                    None => Section::ObjSense,
+                },
+                "ROWS" => Section::Rows,
+                "COLUMNS" => Section::Columns,
+                "RHS" => Section::Rhs,
+                "RANGES" => Section::Ranges,
+                "BOUNDS" => Section::Bounds,
+                "ENDATA" => Section::End,
+                _ => return Err(err(format!("Unknown section {}", keyword))),
+            };
+            continue;
+        }
+
+        let fields = split(line, format);
+        match section {
+            Section::Name | Section::End => {
+                return Err(err("Data outside of any section".to_string()));
+            }
+            Section::ObjSense => {
+                let sense = (fields.first())
+                    .ok_or_else(|| err("Expected an objective sense".to_string()))?;
+                lp.direction = parse_direction(sense)
+                    .ok_or_else(|| err(format!("Unknown objective sense {}", sense)))?;
+            }
+            Section::Rows => {
+                if fields.len() != 2 {
+                    return Err(err("Expected the type and the name of a row".to_string()));
+                }
+                let row = match fields[0] {
+                    "N" if rows.values().any(|&r| r == Row::Objective) => Row::Ignored,
+                    "N" => Row::Objective,
+                    kind => {
+                        let sense = match kind {
+                            "L" => Sense::Le,
+                            "G" => Sense::Ge,
+                            "E" => Sense::Eq,
+                            _ => return Err(err(format!("Unknown row type {}", kind))),
+                        };
+                        let c = lp.add_constraint(fields[1], &[], sense, T::zero());
+                        Row::Constraint(c.0)
+                    }
+                };
+                if rows.insert(fields[1].to_string(), row).is_some() {
+                    return Err(err(format!("Row {} is defined twice", fields[1])));
+                }
+            }
+            Section::Columns => {
+                if fields.get(1) == Some(&"'MARKER'") {
+                    integer = match fields.get(2) {
+                        Some(&"'INTORG'") => true,
+                        Some(&"'INTEND'") => false,
+                        _ => return Err(err("Expected 'INTORG' or 'INTEND'".to_string())),
+                    };
+                    continue;
+                }
+                if fields.len() != 3 && fields.len() != 5 {
+                    return Err(err(
+                        "Expected a column followed by one or two (row, value) pairs".to_string(),
+                    ));
+                }
+                let var = match columns.get(fields[0]) {
+                    Some(&var) => var,
+                    None => {
+                        let var = lp.add_variable(fields[0], T::zero(), None);
+                        lp.set_integer(var, integer);
+                        columns.insert(fields[0].to_string(), var);
+                        var
+                    }
+                };
+                for pair in fields[1..].chunks(2) {
+                    let value: T = parse_number(pair[1]).map_err(err)?;
+                    match find_row(&rows, pair[0]).map_err(err)? {
+                        Row::Objective => lp.objective[var.0] += value,
+                        Row::Ignored => {}
+                        Row::Constraint(c) => {
+                            let terms = &mut lp.constraints[c].terms;
+                            match terms.iter_mut().find(|(v, _)| *v == var) {
+                                Some((_, coef)) => *coef += value,
+                                None => terms.push((var, value)),
+                            }
+                        }
+                    }
+                }
+            }
+            Section::Rhs | Section::Ranges => {
+                // The name of the vector is optional
+                let pairs = if fields.len().is_multiple_of(2) {
+                    &fields[..]
+                } else {
+                    &fields[1..]
+                };
+                if pairs.is_empty() || pairs.len() > 4 {
+                    return Err(err("Expected one or two (row, value) pairs".to_string()));
+                }
+                for pair in pairs.chunks(2) {
+                    let value: T = parse_number(pair[1]).map_err(err)?;
+                    match (find_row(&rows, pair[0]).map_err(err)?, section) {
+                        (Row::Constraint(c), Section::Rhs) => lp.constraints[c].rhs = value,
+                        (Row::Constraint(c), _) => ranges.push((c, value)),
+                        (Row::Objective, Section::Rhs) => lp.constant = -value,
+                        (Row::Ignored, _) => {}
+                        (Row::Objective, _) => {
+                            return Err(err(format!(
+                                "The objective function {} can't have a range",
+                                pair[0]
+                            )));
+                        }
+                    }
+                }
+            }
+            Section::Bounds => read_bound(&mut lp, &columns, &fields).map_err(err)?,
+        }
+    }
+
+    for (c, range) in ranges {
+        let constraint = &mut lp.constraints[c];
+        let (sense, rhs) = match constraint.sense {
+            Sense::Le => (Sense::Ge, constraint.rhs.clone() - range.abs()),
+            Sense::Ge => (Sense::Le, constraint.rhs.clone() + range.abs()),
+            Sense::Eq if range.is_zero() => continue,
+            Sense::Eq => {
+                // The range goes from the requirement towards its sign
+                constraint.sense = if range.is_positive() {
+                    Sense::Ge
+                } else {
+                    Sense::Le
+                };
+                let sense = if range.is_positive() {
+                    Sense::Le
+                } else {
+                    Sense::Ge
+                };
+                (sense, constraint.rhs.clone() + range)
+            }
+        };
+        let name = format!("{}_range", constraint.name);
+        let terms = constraint.terms.clone();
+        lp.add_constraint(&name, &terms, sense, rhs);
+    }
+    Ok(lp)
+}
+
+/// Splits a data line into its non-empty fields, the columns of the fixed format
+/// count characters rather than bytes
+fn split(line: &str, format: MpsFormat) -> Vec<&str> {
+    match format {
+        MpsFormat::Free => line.split_whitespace().collect(),
+        MpsFormat::Fixed => {
+            let offsets: Vec<usize> = (line.char_indices().map(|(i, _)| i))
+                .chain(std::iter::once(line.len()))
+                .collect();
+            let at = |column: usize| offsets[column.min(offsets.len() - 1)];
+            (FIELD_STARTS.iter().zip(&FIELD_ENDS))
+                .map(|(&start, &end)| line[at(start)..at(end)].trim())
+                .filter(|field| !field.is_empty())
+                .collect()
+        }
+    }
+}
+
+fn parse_direction(sense: &str) -> Option<Direction> {
+    match sense {
+        "MAX" | "MAXIMIZE" => Some(Direction::Maximize),
+        "MIN" | "MINIMIZE" => Some(Direction::Minimize),
+        _ => None,
+    }
+}
+
+fn parse_number<T: Number>(field: &str) -> Result<T, String> {
+    match field.parse::<f64>() {
+        Ok(x) if x.is_finite() => Ok(T::from_f64(x)),
+        _ => Err(format!("Invalid number {}", field)),
+    }
+}
+
+fn find_row(rows: &HashMap<String, Row>, name: &str) -> Result<Row, String> {
+    rows.get(name)
+        .copied()
+        .ok_or_else(|| format!("Unknown row {}", name))
+}
+
+/// Applies a line of the BOUNDS section: the type, the optional name of the vector,
+/// the column and the value unless the type doesn't need any
+fn read_bound<T: Number>(
+    lp: &mut LinearProgram<T>,
+    columns: &HashMap<String, Variable>,
+    fields: &[&str],
+) -> Result<(), String> {
+    let (&kind, rest) =
+        (fields.split_first()).ok_or_else(|| "Expected the type of a bound".to_string())?;
+    let with_value = matches!(kind, "UP" | "LO" | "FX" | "LI" | "UI");
+    let (column, value) = match (with_value, rest.len()) {
+        (true, 3) => (rest[1], Some(rest[2])),
+        (true, 2) => (rest[0], Some(rest[1])),
+        // A value of the types without one is ignored
+        (false, 2) | (false, 3) => (rest[1], None),
+        (false, 1) => (rest[0], None),
+        _ => {
+            return Err(format!(
+                "Wrong number of fields for a bound of type {}",
+                kind
+            ))
+        }
+    };
+    let var = *columns
+        .get(column)
+        .ok_or_else(|| format!("Unknown column {}", column))?;
+    let value: Option<T> = value.map(parse_number).transpose()?;
+    let data = &mut lp.variables[var.0];
+    match (kind, value) {
+        ("UP", Some(v)) | ("UI", Some(v)) => {
+            // By the old convention a negative upper bound alone makes the variable unbounded below
+            if v.is_negative() && data.lower.as_ref().is_some_and(|l| l.is_zero()) {
+                data.lower = None;
+            }
+            data.upper = Some(v);
+        }
+        ("LO", Some(v)) | ("LI", Some(v)) => data.lower = Some(v),
+        ("FX", Some(v)) => {
+            data.lower = Some(v.clone());
+            data.upper = Some(v);
+        }
+        ("PL", None) => data.upper = None,
+        ("MI", None) => data.lower = None,
+        ("FR", None) => {
+            data.lower = None;
+            data.upper = None;
+        }
+        ("BV", None) => {
+            data.lower = Some(T::zero());
+            data.upper = Some(T::one());
+        }
+        _ => return Err(format!("Unknown bound type {}", kind)),
+    }
+    if matches!(kind, "LI" | "UI" | "BV") {
+        data.integer = true;
+    }
+    Ok(())
+}
+
+/// Writes the program down in the MPS format. Fails if a name of a row or a column
+/// doesn't fit into its 8 columns of the fixed format, or contains whitespace in the free one.
+pub fn write_mps<T: Number>(
+    lp: &LinearProgram<T>,
+    format: MpsFormat,
+) -> Result<String, WriteError> {
+    for c in &lp.constraints {
+        check_name(&c.name, "row", format)?;
+    }
+    for var in &lp.variables {
+        check_name(&var.name, "column", format)?;
+    }
+    let mut out = String::from("NAME\n");
+    if lp.direction == Direction::Maximize {
+        out.push_str("OBJSENSE\n");
+        out.push_str(&record(format, &["", "MAX"]));
+    }
+
+    // The name of the objective function must differ from the names of the rows
+    let mut objective = String::from("OBJ");
+    while lp.constraints.iter().any(|c| c.name == objective) {
+        objective.push('_');
+    }
+    check_name(&objective, "row", format)?;
+    out.push_str("ROWS\n");
+    out.push_str(&record(format, &["N", &objective]));
+    for c in &lp.constraints {
+        let kind = match c.sense {
+            Sense::Le => "L",
+            Sense::Ge => "G",
+            Sense::Eq => "E",
+        };
+        out.push_str(&record(format, &[kind, &c.name]));
+    }
+
+    let mut entries: Vec<Vec<(&str, String)>> = lp
+        .objective
+        .iter()
+        .map(|c| vec![(objective.as_str(), number(c, format))])
+        .collect();
+    for c in &lp.constraints {
+        for (var, coef) in &c.terms {
+            entries[var.0].push((&c.name, number(coef, format)));
+        }
+    }
+    out.push_str("COLUMNS\n");
+    let mut integer = false;
+    for (var, mut entries) in lp.variables.iter().zip(entries) {
+        if var.integer != integer {
+            integer = var.integer;
+            let marker = if integer { "'INTORG'" } else { "'INTEND'" };
+            out.push_str(&record(format, &["", "MARKER", "'MARKER'", "", marker]));
+        }
+        // A column without any entry would be lost, so the zero of the objective stays
+        if entries.len() > 1 && entries[0].1 == "0" {
+            entries.remove(0);
+        }
+        for pair in entries.chunks(2) {
+            let mut fields = vec!["", &var.name, pair[0].0, &pair[0].1];
+            if let Some((row, value)) = pair.get(1) {
+                fields.extend(&[*row, value.as_str()]);
+            }
+            out.push_str(&record(format, &fields));
+        }
+    }
+    if integer {
+        out.push_str(&record(format, &["", "MARKER", "'MARKER'", "", "'INTEND'"]));
+    }
+
+    out.push_str("RHS\n");
+    if !lp.constant.is_zero() {
+        let value = number(&-lp.constant.clone(), format);
+        out.push_str(&record(format, &["", "RHS", &objective, &value]));
+    }
+    for c in lp.constraints.iter().filter(|c| !c.rhs.is_zero()) {
+        out.push_str(&record(
+            format,
+            &["", "RHS", &c.name, &number(&c.rhs, format)],
+        ));
+    }
+
+    out.push_str("BOUNDS\n");
+    for var in &lp.variables {
+        // The types without a value leave their last field empty
+        let mut bound = |kind: &str, value: Option<&T>| {
+            let value = value.map(|x| number(x, format)).unwrap_or_default();
+            out.push_str(&record(format, &[kind, "BND", &var.name, &value]));
+        };
+        match (&var.lower, &var.upper) {
+            (None, None) => bound("FR", None),
+            (Some(lower), Some(upper)) if lower == upper => bound("FX", Some(upper)),
+            (lower, upper) => {
+                let negative = upper.as_ref().is_some_and(|u| u.is_negative());
+                match lower {
+                    None => bound("MI", None),
+                    // A zero one is written out too, or the negative upper bound would drop it
+                    Some(lower) if !lower.is_zero() || negative => bound("LO", Some(lower)),
+                    Some(_) => {}
+                }
+                if let Some(upper) = upper {
+                    bound("UP", Some(upper));
+                }
+            }
+        }
+    }
+    out.push_str("ENDATA\n");
+    Ok(out)
+}
+
+/// Whether the name of a row or a column (`kind`) reads back as it is
+fn check_name(name: &str, kind: &str, format: MpsFormat) -> Result<(), WriteError> {
+    let message = match format {
+        MpsFormat::Fixed if name.chars().count() > FIELD_ENDS[1] - FIELD_STARTS[1] => {
+            format!("The {} name is longer than 8 characters", kind)
+        }
+        MpsFormat::Free if name.contains(char::is_whitespace) => {
+            format!("The {} name contains whitespace", kind)
+        }
+        _ => return Ok(()),
+    };
+    Err(WriteError {
+        name: name.to_string(),
+        message,
+    })
+}
+
+/// A data line made of the given fields, the first one is the type
+fn record(format: MpsFormat, fields: &[&str]) -> String {
+    let mut line = String::new();
+    match format {
+        MpsFormat::Free => {
+            for field in fields.iter().filter(|f| !f.is_empty()) {
+                line.push(' ');
+                line.push_str(field);
+            }
+        }
+        MpsFormat::Fixed => {
+            for (i, field) in fields.iter().enumerate().filter(|(_, f)| !f.is_empty()) {
+                assert!(
+                    field.chars().count() <= FIELD_ENDS[i] - FIELD_STARTS[i],
+                    "The names are checked and the numbers are short enough"
+                );
+                while line.chars().count() < FIELD_STARTS[i] {
+                    line.push(' ');
+                }
+                line.push_str(field);
+            }
+        }
+    }
+    line.push('\n');
+    line
+}
+
+/// A number as short as it gets. The fixed format allows at most 12 characters,
+/// the free one takes every digit needed to read the same float back.
+fn number<T: Number>(x: &T, format: MpsFormat) -> String {
+    let x = x.to_f64();
+    let plain = format!("{}", x);
+    let scientific = format!("{:e}", x);
+    let shortest = if plain.len() <= scientific.len() {
+        plain
+    } else {
+        scientific
+    };
+    if format == MpsFormat::Free || shortest.len() <= 12 {
+        return shortest;
+    }
+    (0..=6)
+        .rev()
+        .map(|precision| format!("{:.*e}", precision, x))
+        .find(|s| s.len() <= 12)
+        .expect("Any float fits into 12 characters in the scientific notation")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Options, Status};
+    use super::*;
+
+    const FIXED: &str = "\
+NAME          TESTLP
+* The example of the lp_solve docs
+ROWS
+ N  COST
+ L  LIM1
+ G  LIM2
+ E  MYEQN
+COLUMNS
+    X1        COST      1              LIM1      1
+    X1        LIM2      1
+    MARKER    'MARKER'                 'INTORG'
+    X2        COST      2              LIM1      1
+    X2        MYEQN     -1
+    MARKER    'MARKER'                 'INTEND'
+    X3        COST      -1             MYEQN     1
+RHS
+    RHS       LIM1      4              LIM2      1
+    RHS       MYEQN     7
+RANGES
+    RNG       LIM1      2.5
+BOUNDS
+ UP BND       X1        4
+ LO BND       X2        -1
+ UP BND       X2        1
+ENDATA
+";
+
+    fn problem1() -> LinearProgram<f64> {
+        let mut lp = LinearProgram::new();
+        let x = lp.add_variable("x", 0.0, None);
+        let y = lp.add_variable("y", 0.0, None);
+        let z = lp.add_variable("z", 0.0, Some(7.0));
+        lp.add_constraint("c1", &[(x, 10.0), (y, 15.0), (z, 10.0)], Sense::Le, 720.0);
+        lp.add_constraint("c2", &[(x, 13.0), (y, 5.0), (z, 5.0)], Sense::Le, 680.0);
+        lp.add_constraint("c3", &[(x, 20.0), (y, 5.0), (z, 10.0)], Sense::Le, 550.0);
+        lp.maximize(&[(x, 20_000.0), (y, 45_000.0), (z, 85_000.0)]);
+        lp
+    }
+
+    #[test]
+    fn mps_test_read_fixed() {
+        let lp = read_mps::<f64>(FIXED, MpsFormat::Fixed).unwrap();
+        assert_eq!(lp.direction, Direction::Minimize);
+        assert_eq!(lp.objective, vec![1.0, 2.0, -1.0]);
+        let names: Vec<_> = lp.variables.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["X1", "X2", "X3"]);
+        let integer: Vec<_> = lp.variables.iter().map(|v| v.integer).collect();
+        assert_eq!(integer, vec![false, true, false]);
+        assert_eq!(lp.variables[0].upper, Some(4.0));
+        assert_eq!(
+            (lp.variables[1].lower, lp.variables[1].upper),
+            (Some(-1.0), Some(1.0))
+        );
+
+        // LIM1 turns into 1.5 <= X1 + X2 <= 4
+        assert_eq!(lp.constraints.len(), 4);
+        let range = &lp.constraints[3];
+        assert_eq!(range.name, "LIM1_range");
+        assert_eq!((range.sense, range.rhs), (Sense::Ge, 1.5));
+        assert_eq!(range.terms, lp.constraints[0].terms);
+        assert_eq!(
+            lp.constraints[2].terms,
+            vec![(Variable(1), -1.0), (Variable(2), 1.0)]
+        );
+
+        // X3 = 7 + X2, so X1 + 2X2 - X3 = X1 + X2 - 7 >= 1.5 - 7
+        let res = lp.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.objective + 5.5).abs() < 1E-9, "{}", res.objective);
+    }
+
+    #[test]
+    fn mps_test_round_trip() {
+        let mut lp = problem1();
+        lp.set_integer(Variable(1), true);
+        lp.variables[0].lower = Some(1.5);
+        lp.constant = -250.0;
+        let free = lp.add_free_variable("free");
+        let below = lp.add_variable("below", 0.0, None);
+        lp.set_bounds(below, None, Some(-2.0));
+        lp.add_constraint("c4", &[(free, 1.0), (below, 1.0)], Sense::Le, 3.0);
+        for &format in &[MpsFormat::Fixed, MpsFormat::Free] {
+            let text = write_mps(&lp, format).unwrap();
+            let read = read_mps::<f64>(&text, format).unwrap();
+            assert_eq!(write_mps(&read, format).unwrap(), text);
+            assert_eq!(read.direction, Direction::Maximize);
+            assert_eq!(read.constant, -250.0);
+            assert!(read.variables[1].integer);
+            assert_eq!(read.variables[0].lower, Some(1.5));
+            assert_eq!(read.variables[2].upper, Some(7.0));
+            assert_eq!(
+                (read.variables[3].lower, read.variables[3].upper),
+                (None, None)
+            );
+            assert_eq!(
+                (read.variables[4].lower, read.variables[4].upper),
+                (None, Some(-2.0))
+            );
+            let res = read.solve(&Options::default());
+            assert!((res.objective - lp.solve(&Options::default()).objective).abs() < 1E-6);
+        }
+        // The fixed format is readable as the free one when the names have no spaces
+        let text = write_mps(&problem1(), MpsFormat::Fixed).unwrap();
+        let read = read_mps::<f64>(&text, MpsFormat::Free).unwrap();
+        assert_eq!(write_mps(&read, MpsFormat::Fixed).unwrap(), text);
+    }
+
+    #[test]
+    fn mps_test_objective_constant() {
+        // The requirement of the objective function is its negated constant
+        let text = FIXED.replace("MYEQN     7", "MYEQN     7              COST      -3");
+        let lp = read_mps::<f64>(&text, MpsFormat::Fixed).unwrap();
+        assert_eq!(lp.constant, 3.0);
+        let res = lp.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.objective + 2.5).abs() < 1E-9, "{}", res.objective);
+        assert!((lp.objective_value(&res.x) - res.objective).abs() < 1E-9);
+
+        let text = FIXED.replace("RNG       LIM1", "RNG       COST");
+        let error = read_mps::<f64>(&text, MpsFormat::Fixed).unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new(20, "The objective function COST can't have a range")
+        );
+    }
+
+    #[test]
+    fn mps_test_numbers() {
+        let fixed = MpsFormat::Fixed;
+        assert_eq!(number(&0.5, fixed), "0.5");
+        assert_eq!(number(&-720.0, fixed), "-720");
+        assert_eq!(number(&1E-20, fixed), "1e-20");
+        assert_eq!(number(&(1.0 / 3.0), fixed), "3.333333e-1");
+        assert!(number(&(-2.0 / 3.0), fixed).len() <= 12);
+        assert_eq!(number(&1E-20, MpsFormat::Free), "1e-20");
+        assert_eq!(number(&(1.0 / 3.0), MpsFormat::Free), "0.3333333333333333");
+    }
+
+    #[test]
+    fn mps_test_free_precision() {
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 1.0 / 7.0, Some(2.0 / 3.0));
+        let y = lp.add_variable("y", 0.0, None);
+        lp.add_constraint(
+            "c1",
+            &[(x, 1.0 / 3.0), (y, -2.0 / 7.0)],
+            Sense::Le,
+            1.0 / 9.0,
+        );
+        lp.add_constraint("c2", &[(x, 1E-13 / 3.0), (y, 5.0)], Sense::Ge, -1E20 / 3.0);
+        lp.maximize(&[(x, 10.0 / 3.0), (y, 0.1)]);
+        let text = write_mps(&lp, MpsFormat::Free).unwrap();
+        let read = read_mps::<f64>(&text, MpsFormat::Free).unwrap();
+        assert_eq!(read.objective, lp.objective);
+        for (read, c) in read.constraints.iter().zip(&lp.constraints) {
+            assert_eq!((&read.terms, read.rhs), (&c.terms, c.rhs));
+        }
+        assert_eq!(
+            (read.variables[0].lower, read.variables[0].upper),
+            (Some(1.0 / 7.0), Some(2.0 / 3.0))
+        );
+    }
+
+    #[test]
+    fn mps_test_errors() {
+        let error = read_mps::<f64>(&FIXED.replace(" L  LIM1", " X  LIM1"), MpsFormat::Fixed);
+        assert_eq!(error.unwrap_err(), ParseError::new(5, "Unknown row type X"));
+
+        let text = FIXED.replace("MYEQN     7", "MYEQN     7x");
+        let error = read_mps::<f64>(&text, MpsFormat::Fixed).unwrap_err();
+        assert_eq!(
+            (error.line, error.message.as_str()),
+            (18, "Invalid number 7x")
+        );
+
+        let text = FIXED.replace(" LO BND       X2", " XX BND       X2");
+        let error = read_mps::<f64>(&text, MpsFormat::Fixed).unwrap_err();
+        assert_eq!(error.line, 23);
+        assert_eq!(error.to_string(), "line 23: Unknown bound type XX");
+
+        let text = "ROWS\n N obj\nCOLUMNS\n x obj 1 c1 2\nENDATA\n";
+        let error = read_mps::<f64>(text, MpsFormat::Free).unwrap_err();
+        assert_eq!(error, ParseError::new(4, "Unknown row c1"));
+
+        // Data lines whose only content lies past the last field of the fixed format
+        let blank = format!("{:61}X", "");
+        let text = format!("OBJSENSE\n{}\nROWS\n N obj\nENDATA\n", blank);
+        let error = read_mps::<f64>(&text, MpsFormat::Fixed).unwrap_err();
+        assert_eq!(error, ParseError::new(2, "Expected an objective sense"));
+        let text = format!("ROWS\n N obj\nCOLUMNS\nBOUNDS\n{}\nENDATA\n", blank);
+        let error = read_mps::<f64>(&text, MpsFormat::Fixed).unwrap_err();
+        assert_eq!(error, ParseError::new(5, "Expected the type of a bound"));
+    }
+
+    #[test]
+    fn mps_test_non_ascii_names() {
+        let mut lp = problem1();
+        lp.variables[0].name = "Größe".to_string();
+        lp.constraints[0].name = "Gewicht³".to_string();
+        let text = write_mps(&lp, MpsFormat::Fixed).unwrap();
+        let read = read_mps::<f64>(&text, MpsFormat::Fixed).unwrap();
+        assert_eq!(read.variables[0].name, "Größe");
+        assert_eq!(read.constraints[0].name, "Gewicht³");
+        assert_eq!(read.constraints[0].terms, lp.constraints[0].terms);
+        assert_eq!(write_mps(&read, MpsFormat::Fixed).unwrap(), text);
+    }
+
+    #[test]
+    fn mps_test_write_errors() {
+        let mut lp = problem1();
+        lp.add_variable("capacity_x", 0.0, None);
+        let error = write_mps(&lp, MpsFormat::Fixed).unwrap_err();
+        assert_eq!(error.name, "capacity_x");
+        assert_eq!(
+            error.to_string(),
+            "capacity_x: The column name is longer than 8 characters"
+        );
+        assert!(write_mps(&lp, MpsFormat::Free).is_ok());
+
+        // The fixed format keeps the spaces of a name, the free one can't
+        let mut lp = problem1();
+        lp.add_constraint("row 5", &[(Variable(0), 1.0)], Sense::Le, 1.0);
+        let error = write_mps(&lp, MpsFormat::Free).unwrap_err();
+        assert_eq!(error.message, "The row name contains whitespace");
+        assert!(write_mps(&lp, MpsFormat::Fixed).is_ok());
+    }
+}
@@ -0,0 +1,72 @@
+/// The numbers the simplex method can work with: floats for speed
+/// and exact rationals for checking the results on small problems.
+use nalgebra as na;
+use num_traits::{NumAssignRef, NumRef, Signed};
+use std::fmt;
+
+/// Fractions of 64-bit integers, there are no rounding errors with them.
+/// The matrices need `Copy` entries, so the integers can't grow without limit:
+/// fine for small problems, large ones overflow and panic.
+pub type Rational = num_rational::Rational64;
+
+/// A field the simplex table is built of
+pub trait Number: na::Scalar + PartialOrd + fmt::Display + Signed + NumRef + NumAssignRef {
+    /// Numbers closer to zero than this are treated as zeros by the pivoting rules
+    fn eps() -> Self;
+    /// The closest number to the given float
+    fn from_f64(x: f64) -> Self;
+    /// The closest float to the number
+    fn to_f64(&self) -> f64;
+    /// The largest whole number not greater than this one
+    fn floor(&self) -> Self;
+}
+
+impl Number for f32 {
+    fn eps() -> Self {
+        1e-6
+    }
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+    fn to_f64(&self) -> f64 {
+        f64::from(*self)
+    }
+    fn floor(&self) -> Self {
+        f32::floor(*self)
+    }
+}
+
+impl Number for f64 {
+    fn eps() -> Self {
+        1e-9
+    }
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+    fn to_f64(&self) -> f64 {
+        *self
+    }
+    fn floor(&self) -> Self {
+        f64::floor(*self)
+    }
+}
+
+impl Number for Rational {
+    fn eps() -> Self {
+        Rational::from_integer(0)
+    }
+    fn from_f64(x: f64) -> Self {
+        Rational::approximate_float(x).expect("The float does not fit into a fraction")
+    }
+    fn to_f64(&self) -> f64 {
+        *self.numer() as f64 / *self.denom() as f64
+    }
+    fn floor(&self) -> Self {
+        Rational::floor(self)
+    }
+}
+
+/// A shortcut for `T::from_f64`, handy for constants
+pub fn num<T: Number>(x: f64) -> T {
+    T::from_f64(x)
+}
@@ -0,0 +1,400 @@
+/// Watching the solvers at work: an `Observer` is told when a method starts,
+/// after every pivot (or every step of the interior-point method) and when it stops.
+/// `Trace` keeps all of it in memory, `Logger` writes a structured record per event
+/// (`key=value` pairs on a line) to any writer, be it stdout, a file or a buffer.
+use super::{Number, Status};
+use nalgebra as na;
+use std::cell::RefCell;
+use std::io::Write;
+
+/// The phase a pivot belongs to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    /// Looking for a feasible basis: the objective is the negated sum of artificial variables
+    One,
+    /// Optimizing the objective function from a feasible basis
+    Two,
+    /// The dual simplex method bringing the basic variables back within their bounds
+    Dual,
+}
+
+/// A method has set up its problem
+#[derive(Debug, Clone, PartialEq)]
+pub struct Initialization {
+    /// "simplex", "revised simplex", "dual simplex" or "interior point"
+    pub method: &'static str,
+    /// The number of constraints
+    pub rows: usize,
+    /// The number of decision variables
+    pub columns: usize,
+}
+
+/// A pivot of the simplex method. The variables are indexed as `x` followed by `slack`
+/// (and the artificial variables) of the solution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pivot<T: Number> {
+    /// The number of pivots so far, this one included
+    pub iteration: usize,
+    pub phase: Phase,
+    pub entering: usize,
+    /// `None` if the entering variable only moves from one bound to the other
+    pub leaving: Option<usize>,
+    /// The value of the objective function of the phase after the pivot
+    pub objective: T,
+}
+
+/// A step of the interior-point method
+#[derive(Debug, Clone, PartialEq)]
+pub struct BarrierStep {
+    pub iteration: usize,
+    /// The largest violation of the constraints
+    pub primal_residual: f64,
+    /// The largest violation of the dual constraints
+    pub dual_residual: f64,
+    /// The relative gap between the primal and the dual objective values
+    pub gap: f64,
+}
+
+/// A table of the simplex method (`simplex_method`, `two_phase_simplex`, `bounded_simplex`)
+/// as it stands before a pivot, and once more at the end of each phase.
+/// `entering` is a column and `leaving` a row of the table, both `None` at the end of a phase,
+/// while only `leaving` is `None` if the entering variable moves from one bound to the other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tableau<'a, T: Number> {
+    /// The number of pivots so far
+    pub iteration: usize,
+    pub phase: Phase,
+    pub table: &'a na::DMatrix<T>,
+    /// The column of the basic variable of each row below row(0)
+    pub basis: &'a [usize],
+    pub entering: Option<usize>,
+    pub leaving: Option<usize>,
+}
+
+/// A method has stopped
+#[derive(Debug, Clone, PartialEq)]
+pub struct Termination<T: Number> {
+    pub status: Status,
+    /// The value of the objective function at the last point
+    pub objective: T,
+    /// The number of pivots (or steps) it took
+    pub iterations: usize,
+}
+
+/// Gets told about the progress of a solver. Every method does nothing by default.
+/// The solvers only hold a shared reference, so observers keeping records need
+/// interior mutability, as `Trace` and `Logger` do.
+pub trait Observer<T: Number> {
+    fn initialized(&self, _event: &Initialization) {}
+    fn pivoted(&self, _event: &Pivot<T>) {}
+    fn stepped(&self, _event: &BarrierStep) {}
+    fn tabulated(&self, _event: &Tableau<T>) {}
+    fn terminated(&self, _event: &Termination<T>) {}
+}
+
+/// Pays no attention, the default observer
+pub struct Silent;
+
+impl<T: Number> Observer<T> for Silent {}
+
+/// An event of a `Trace`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Record<T: Number> {
+    Initialized(Initialization),
+    Pivoted(Pivot<T>),
+    Stepped(BarrierStep),
+    Terminated(Termination<T>),
+}
+
+/// Keeps every event in memory, in order
+#[derive(Debug)]
+pub struct Trace<T: Number> {
+    records: RefCell<Vec<Record<T>>>,
+}
+
+impl<T: Number> Default for Trace<T> {
+    fn default() -> Self {
+        Trace {
+            records: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl<T: Number> Trace<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All the events so far
+    pub fn records(&self) -> Vec<Record<T>> {
+        self.records.borrow().clone()
+    }
+
+    /// The pivots so far
+    pub fn pivots(&self) -> Vec<Pivot<T>> {
+        (self.records.borrow().iter())
+            .filter_map(|record| match record {
+                Record::Pivoted(pivot) => Some(pivot.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The last termination, if any
+    pub fn termination(&self) -> Option<Termination<T>> {
+        (self.records.borrow().iter().rev()).find_map(|record| match record {
+            Record::Terminated(end) => Some(end.clone()),
+            _ => None,
+        })
+    }
+
+    /// Forgets the events so far
+    pub fn clear(&self) {
+        self.records.borrow_mut().clear();
+    }
+}
+
+impl<T: Number> Observer<T> for Trace<T> {
+    fn initialized(&self, event: &Initialization) {
+        let record = Record::Initialized(event.clone());
+        self.records.borrow_mut().push(record);
+    }
+    fn pivoted(&self, event: &Pivot<T>) {
+        let record = Record::Pivoted(event.clone());
+        self.records.borrow_mut().push(record);
+    }
+    fn stepped(&self, event: &BarrierStep) {
+        let record = Record::Stepped(event.clone());
+        self.records.borrow_mut().push(record);
+    }
+    fn terminated(&self, event: &Termination<T>) {
+        let record = Record::Terminated(event.clone());
+        self.records.borrow_mut().push(record);
+    }
+}
+
+/// Writes a line of `key=value` pairs per event, e.g.
+/// `event=pivot iteration=3 phase=two entering=1 leaving=4 objective=2100`.
+/// A write that fails is skipped, logging never stops the solver.
+pub struct Logger<W: Write> {
+    out: RefCell<W>,
+}
+
+impl<W: Write> Logger<W> {
+    pub fn new(out: W) -> Self {
+        Logger {
+            out: RefCell::new(out),
+        }
+    }
+
+    /// Gives the writer back
+    pub fn into_inner(self) -> W {
+        self.out.into_inner()
+    }
+
+    fn write(&self, line: String) {
+        let _ = writeln!(self.out.borrow_mut(), "{}", line);
+    }
+}
+
+impl<T: Number, W: Write> Observer<T> for Logger<W> {
+    fn initialized(&self, event: &Initialization) {
+        self.write(format!(
+            "event=initialized method=\"{}\" rows={} columns={}",
+            event.method, event.rows, event.columns
+        ));
+    }
+    fn pivoted(&self, event: &Pivot<T>) {
+        let phase = match event.phase {
+            Phase::One => "one",
+            Phase::Two => "two",
+            Phase::Dual => "dual",
+        };
+        let leaving = match event.leaving {
+            Some(leaving) => leaving.to_string(),
+            None => "none".to_string(),
+        };
+        self.write(format!(
+            "event=pivot iteration={} phase={} entering={} leaving={} objective={}",
+            event.iteration, phase, event.entering, leaving, event.objective
+        ));
+    }
+    fn stepped(&self, event: &BarrierStep) {
+        self.write(format!(
+            "event=step iteration={} primal_residual={:e} dual_residual={:e} gap={:e}",
+            event.iteration, event.primal_residual, event.dual_residual, event.gap
+        ));
+    }
+    fn terminated(&self, event: &Termination<T>) {
+        self.write(format!(
+            "event=terminated status={:?} objective={} iterations={}",
+            event.status, event.objective, event.iterations
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        bounded_simplex, interior_point, revised_simplex, simplex_method, Options, Sense,
+        SparseMatrix, WarmStart,
+    };
+    use super::*;
+    use nalgebra as na;
+
+    /*
+    Objective function = 7x + 5y
+    Constraints:
+    2x + 3y <= 90
+    3x + 2y <= 120
+    x enters and the slack of the second row leaves (280), then y replaces
+    the slack of the first row (282)
+    */
+    fn problem() -> (na::DMatrix<f64>, Vec<f64>, Vec<f64>) {
+        let constraints = na::DMatrix::from_row_slice(2, 2, &[2.0, 3.0, 3.0, 2.0]);
+        (constraints, vec![90.0, 120.0], vec![7.0, 5.0])
+    }
+
+    fn expected_pivots() -> Vec<(usize, Option<usize>, f64)> {
+        vec![(0, Some(3), 280.0), (1, Some(2), 282.0)]
+    }
+
+    fn check_pivots(trace: &Trace<f64>) {
+        let pivots = trace.pivots();
+        assert_eq!(pivots.len(), 2);
+        for (k, (pivot, (entering, leaving, objective))) in
+            pivots.iter().zip(expected_pivots()).enumerate()
+        {
+            assert_eq!(pivot.iteration, k + 1);
+            assert_eq!(pivot.phase, Phase::Two);
+            assert_eq!((pivot.entering, pivot.leaving), (entering, leaving));
+            assert!((pivot.objective - objective).abs() < 1E-9, "{:?}", pivot);
+        }
+        let end = trace.termination().unwrap();
+        assert_eq!(end.status, Status::Optimal);
+        assert_eq!(end.iterations, 2);
+        assert!((end.objective - 282.0).abs() < 1E-9);
+    }
+
+    #[test]
+    fn observer_test_trace() {
+        let (constraints, req, obj) = problem();
+        let trace = Trace::new();
+        simplex_method(constraints.clone(), req.clone(), obj.clone(), &trace);
+        assert_eq!(
+            trace.records()[0],
+            Record::Initialized(Initialization {
+                method: "simplex",
+                rows: 2,
+                columns: 2,
+            })
+        );
+        check_pivots(&trace);
+
+        trace.clear();
+        let options = Options {
+            observer: &trace,
+            ..Options::default()
+        };
+        revised_simplex(
+            SparseMatrix::from_dense(&constraints),
+            vec![Sense::Le; 2],
+            req.clone(),
+            obj.clone(),
+            &[(Some(0.0), None); 2],
+            &options,
+        );
+        match &trace.records()[0] {
+            Record::Initialized(start) => assert_eq!(start.method, "revised simplex"),
+            record => panic!("{:?}", record),
+        }
+        check_pivots(&trace);
+
+        // Phase I reports the negated sum of the artificial variables
+        trace.clear();
+        bounded_simplex(
+            constraints.clone(),
+            vec![Sense::Le, Sense::Ge],
+            vec![90.0, 30.0],
+            obj.clone(),
+            &[(Some(0.0), None); 2],
+            &options,
+        );
+        let pivots = trace.pivots();
+        assert_eq!(pivots[0].phase, Phase::One);
+        assert!(pivots[0].objective.abs() < 1E-9, "{:?}", pivots);
+        assert!(pivots.iter().skip(1).all(|pivot| pivot.phase == Phase::Two));
+    }
+
+    #[test]
+    fn observer_test_dual_and_interior() {
+        let (constraints, req, obj) = problem();
+        let trace = Trace::new();
+        let options = Options {
+            observer: &trace,
+            ..Options::default()
+        };
+        let bounds = vec![(Some(0.0), None); 2];
+        let constr = SparseMatrix::from_dense(&constraints);
+        let mut warm = WarmStart::new(
+            constr,
+            vec![Sense::Le; 2],
+            req.clone(),
+            obj.clone(),
+            &bounds,
+        );
+        warm.solve(&options);
+        trace.clear();
+        // x <= 30 cuts the optimum (36, 6) off
+        warm.add_row(&[(0, 1.0)], Sense::Le, 30.0);
+        warm.solve(&options);
+        match &trace.records()[0] {
+            Record::Initialized(start) => assert_eq!(start.method, "dual simplex"),
+            record => panic!("{:?}", record),
+        }
+        let pivots = trace.pivots();
+        assert_eq!(pivots[0].phase, Phase::Dual);
+        assert!((pivots[0].objective - 260.0).abs() < 1E-9, "{:?}", pivots);
+
+        trace.clear();
+        interior_point(
+            constraints,
+            vec![Sense::Le; 2],
+            req,
+            obj,
+            &bounds,
+            false,
+            &options,
+        );
+        let records = trace.records();
+        let steps = records
+            .iter()
+            .filter(|record| matches!(record, Record::Stepped(_)))
+            .count();
+        let end = trace.termination().unwrap();
+        assert_eq!(end.status, Status::Optimal);
+        assert_eq!(steps, end.iterations + 1);
+        match &records[steps] {
+            Record::Stepped(step) => assert!(step.gap < 1E-8, "{:?}", step),
+            record => panic!("{:?}", record),
+        }
+    }
+
+    #[test]
+    fn observer_test_logger() {
+        let (constraints, req, obj) = problem();
+        let logger = Logger::new(Vec::new());
+        simplex_method(constraints, req, obj, &logger);
+        let log = String::from_utf8(logger.into_inner()).unwrap();
+        let lines: Vec<_> = log.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "event=initialized method=\"simplex\" rows=2 columns=2",
+                "event=pivot iteration=1 phase=two entering=0 leaving=3 objective=280",
+                "event=pivot iteration=2 phase=two entering=1 leaving=2 objective=282",
+                "event=terminated status=Optimal objective=282 iterations=2",
+            ]
+        );
+    }
+}
@@ -0,0 +1,165 @@
+/// Pivoting rules deciding which variables enter and leave the basis.
+/// [https://en.wikipedia.org/wiki/Simplex_algorithm#Pivot_operations]
+/// [https://en.wikipedia.org/wiki/Bland%27s_rule]
+use super::{Layout, Number};
+use nalgebra as na;
+
+/// A strategy of choosing the next pivot of the simplex table.
+/// The table has the objective function in row(0) and the requirements in the last column.
+pub trait PivotRule<T: Number> {
+    /// Chooses the entering column out of `candidates`,
+    /// the columns with negative coefficients in row(0) (never empty).
+    fn entering(&self, table: &na::DMatrix<T>, candidates: &[usize]) -> usize;
+
+    /// Chooses the leaving row out of `ties`, the rows with the same minimal ratio
+    /// (never empty). `basis[i - 1]` is the basic column of row i.
+    fn leaving(&self, _table: &na::DMatrix<T>, _basis: &[usize], ties: &[usize]) -> usize {
+        ties[0]
+    }
+}
+
+/// The most negative coefficient in row(0) enters the basis.
+/// It is cheap, but can cycle forever on degenerate problems.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Dantzig;
+
+impl<T: Number> PivotRule<T> for Dantzig {
+    fn entering(&self, table: &na::DMatrix<T>, candidates: &[usize]) -> usize {
+        argmax(candidates, |j| -table[(0, j)].clone())
+    }
+}
+
+/// The variables with the smallest indices enter and leave the basis.
+/// It never cycles, yet often takes a lot more iterations than the others.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Bland;
+
+impl<T: Number> PivotRule<T> for Bland {
+    fn entering(&self, _table: &na::DMatrix<T>, candidates: &[usize]) -> usize {
+        candidates[0]
+    }
+
+    fn leaving(&self, _table: &na::DMatrix<T>, basis: &[usize], ties: &[usize]) -> usize {
+        *ties
+            .iter()
+            .min_by_key(|&&i| basis[i - 1])
+            .expect("There is at least one row to leave")
+    }
+}
+
+/// The entering variable makes the steepest angle with the objective function,
+/// i.e. its coefficient in row(0) is normalized by the length of its edge direction.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SteepestEdge;
+
+impl<T: Number> PivotRule<T> for SteepestEdge {
+    fn entering(&self, table: &na::DMatrix<T>, candidates: &[usize]) -> usize {
+        // The square root of the exact norm is rarely exact, floats are good enough to compare
+        argmax(candidates, |j| {
+            let norm = (1..table.nrows()).fold(1.0, |acc, i| acc + table[(i, j)].to_f64().powi(2));
+            -table[(0, j)].to_f64() / norm.sqrt()
+        })
+    }
+}
+
+/// The entering variable gives the largest growth of the objective function
+/// in a single pivot, which needs a ratio test per candidate.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LargestImprovement;
+
+impl<T: Number> PivotRule<T> for LargestImprovement {
+    fn entering(&self, table: &na::DMatrix<T>, candidates: &[usize]) -> usize {
+        // The flag marks the infinite growth, when nothing stops the variable
+        argmax(candidates, |j| match min_ratio(table, j) {
+            Some((ratio, _)) => (false, -table[(0, j)].clone() * ratio),
+            None => (true, T::zero()),
+        })
+    }
+}
+
+/// The ratio test: among the rows with positive coefficients in the given column
+/// returns the minimal ratio of the requirement to the coefficient
+/// and all the rows that reach it. Returns `None` if the column is unlimited.
+/// The upper bounds of the variables are not taken into account.
+pub(super) fn min_ratio<T: Number>(table: &na::DMatrix<T>, col: usize) -> Option<(T, Vec<usize>)> {
+    let last_col = table.ncols() - 1;
+    let mut best: Option<(T, Vec<usize>)> = None;
+    for i in 1..table.nrows() {
+        // Only positive coefficients keep the requirements non-negative
+        if table[(i, col)] <= T::eps() {
+            continue;
+        }
+        let ratio = table[(i, last_col)].clone() / &table[(i, col)];
+        keep_min(&mut best, ratio, i);
+    }
+    best
+}
+
+/// The outcome of the ratio test with bounded variables
+pub(super) enum Ratio {
+    /// The rows whose basic variables reach their bounds first,
+    /// along with whether it is the upper bound
+    Rows(Vec<(usize, bool)>),
+    /// The entering variable reaches its own upper bound first
+    Flip,
+    Unbounded,
+}
+
+/// The ratio test where the basic variables stop at both of their bounds,
+/// free ones never stop, and the entering variable may stop at its own upper bound.
+pub(super) fn bounded_ratio<T: Number>(
+    table: &na::DMatrix<T>,
+    col: usize,
+    basis: &[usize],
+    layout: &Layout<T>,
+) -> Ratio {
+    let last_col = table.ncols() - 1;
+    let mut best: Option<(T, Vec<(usize, bool)>)> = None;
+    for i in 1..table.nrows() {
+        let b = basis[i - 1];
+        let coef = &table[(i, col)];
+        if layout.is_free(b) {
+            continue;
+        }
+        // Positive coefficients decrease the basic variable, negative ones increase it
+        if *coef > T::eps() {
+            keep_min(&mut best, table[(i, last_col)].clone() / coef, (i, false));
+        } else if *coef < -T::eps() {
+            if let Some(upper) = layout.upper(b) {
+                let room = upper.clone() - &table[(i, last_col)];
+                keep_min(&mut best, room / -coef.clone(), (i, true));
+            }
+        }
+    }
+    match (best, layout.upper(col)) {
+        (Some((ratio, _)), Some(upper)) if *upper <= ratio => Ratio::Flip,
+        (None, Some(_)) => Ratio::Flip,
+        (Some((_, ties)), _) => Ratio::Rows(ties),
+        (None, None) => Ratio::Unbounded,
+    }
+}
+
+/// Keeps the minimal ratio and all the items reaching it
+fn keep_min<T: Number, I>(best: &mut Option<(T, Vec<I>)>, ratio: T, item: I) {
+    match best {
+        Some((ref min, ref mut ties))
+            if (ratio.clone() - min).abs() <= T::eps() * (T::one() + min.abs()) =>
+        {
+            ties.push(item)
+        }
+        Some((ref min, _)) if *min < ratio => {}
+        _ => *best = Some((ratio, vec![item])),
+    }
+}
+
+/// The first of the indices with the largest score
+fn argmax<S: PartialOrd>(indices: &[usize], score: impl Fn(usize) -> S) -> usize {
+    let mut best = (indices[0], score(indices[0]));
+    for &j in &indices[1..] {
+        let s = score(j);
+        if s > best.1 {
+            best = (j, s);
+        }
+    }
+    best.0
+}
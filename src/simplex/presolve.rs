@@ -0,0 +1,580 @@
+/// Presolve: the reductions of a `LinearProgram` before the simplex method sees it.
+/// Generated programs are full of rows and columns that tell nothing new:
+/// empty rows, duplicate (proportional) rows, fixed variables and rows of one variable,
+/// which are just bounds. They are taken out until nothing changes, which also finds
+/// some of the infeasible programs without solving them. Postsolve brings the solution
+/// of the reduced program back to the variables and constraints of the original one,
+/// prices included.
+use super::model::{Constraint, ConstraintData, LinearProgram, Variable, VariableData};
+use super::{Layout, Number, Sense, Solution, Status};
+
+/// Why presolve found a program infeasible
+#[derive(Debug, Clone, PartialEq)]
+pub enum Infeasibility {
+    /// The constraint can't hold: it is empty (after the fixed variables are taken out)
+    /// and its requirement is out of reach, or it contradicts a duplicate of it
+    Constraint(Constraint),
+    /// The bounds of this variable are crossed, or the rows of this variable alone
+    /// push them across each other
+    Bounds(Variable),
+}
+
+/// A row of the program being reduced, with the variables as indices
+#[derive(Debug, Clone)]
+struct Row<T: Number> {
+    terms: Vec<(usize, T)>,
+    sense: Sense,
+    rhs: T,
+}
+
+/// The reduced program along with what postsolve needs to undo the reductions
+#[derive(Debug, Clone)]
+pub struct Presolved<T: Number> {
+    /// The reduced program, to be solved in place of the original one
+    pub program: LinearProgram<T>,
+    /// The original program
+    original: LinearProgram<T>,
+    /// The original index of each variable of the reduced program
+    variables: Vec<usize>,
+    /// The original index of each constraint of the reduced program
+    constraints: Vec<usize>,
+    /// The values of the variables taken out as fixed
+    fixed: Vec<Option<T>>,
+    /// The bounds of the variables after the singleton rows tightened them
+    lower: Vec<Option<T>>,
+    upper: Vec<Option<T>>,
+    /// The singleton row each of those bounds comes from, if not the original bound
+    lower_from: Vec<Option<usize>>,
+    upper_from: Vec<Option<usize>>,
+}
+
+/// The tolerance of comparisons around `x`
+fn tolerance<T: Number>(x: &T) -> T {
+    T::eps() * (T::one() + x.abs())
+}
+
+/// Whether `lhs <sense> rhs` holds within the tolerance
+fn holds<T: Number>(lhs: &T, sense: Sense, rhs: &T) -> bool {
+    let tol = tolerance(rhs);
+    match sense {
+        Sense::Le => *lhs <= rhs.clone() + tol,
+        Sense::Ge => *lhs >= rhs.clone() - tol,
+        Sense::Eq => (lhs.clone() - rhs).abs() <= tol,
+    }
+}
+
+/// The sense of a constraint multiplied by a negative number
+fn flip(sense: Sense) -> Sense {
+    match sense {
+        Sense::Le => Sense::Ge,
+        Sense::Ge => Sense::Le,
+        Sense::Eq => Sense::Eq,
+    }
+}
+
+/// The factor turning row `a` into row `b`, if they are proportional
+fn proportion<T: Number>(a: &[(usize, T)], b: &[(usize, T)]) -> Option<T> {
+    if a.len() != b.len() || a.is_empty() {
+        return None;
+    }
+    let factor = b[0].1.clone() / &a[0].1;
+    let same = a
+        .iter()
+        .zip(b)
+        .all(|((j, x), (k, y))| j == k && (x.clone() * &factor - y).abs() <= tolerance(y));
+    if same {
+        Some(factor)
+    } else {
+        None
+    }
+}
+
+/// Reduces the program, or tells why it is infeasible
+pub fn presolve<T: Number>(lp: &LinearProgram<T>) -> Result<Presolved<T>, Infeasibility> {
+    let n = lp.variables.len();
+    let mut rows: Vec<Option<Row<T>>> = (lp.constraints.iter())
+        .map(|c| {
+            let mut terms: Vec<_> = (c.terms.iter())
+                .filter(|(_, coef)| !coef.is_zero())
+                .map(|(var, coef)| (var.0, coef.clone()))
+                .collect();
+            terms.sort_by_key(|(j, _)| *j);
+            Some(Row {
+                terms,
+                sense: c.sense,
+                rhs: c.rhs.clone(),
+            })
+        })
+        .collect();
+    let mut lower: Vec<_> = lp.variables.iter().map(|v| v.lower.clone()).collect();
+    let mut upper: Vec<_> = lp.variables.iter().map(|v| v.upper.clone()).collect();
+    for j in 0..n {
+        if let (Some(l), Some(u)) = (&lower[j], &upper[j]) {
+            if l > u {
+                return Err(Infeasibility::Bounds(Variable(j)));
+            }
+        }
+    }
+    let mut lower_from = vec![None; n];
+    let mut upper_from = vec![None; n];
+    let mut fixed: Vec<Option<T>> = vec![None; n];
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        // Fixed variables move over to the requirements
+        for j in 0..n {
+            if fixed[j].is_some() {
+                continue;
+            }
+            let value = match (&lower[j], &upper[j]) {
+                (Some(l), Some(u)) if l == u => l.clone(),
+                _ => continue,
+            };
+            for row in rows.iter_mut().flatten() {
+                if let Some(t) = row.terms.iter().position(|(k, _)| *k == j) {
+                    let (_, coef) = row.terms.remove(t);
+                    row.rhs -= coef * &value;
+                }
+            }
+            fixed[j] = Some(value);
+            changed = true;
+        }
+
+        // Empty rows either hold or not, rows of one variable are its bounds
+        for (i, slot) in rows.iter_mut().enumerate() {
+            let row = match slot.take() {
+                Some(row) if row.terms.len() <= 1 => row,
+                other => {
+                    *slot = other;
+                    continue;
+                }
+            };
+            changed = true;
+            let (j, coef) = match row.terms.first() {
+                Some(term) => term.clone(),
+                None if holds(&T::zero(), row.sense, &row.rhs) => continue,
+                None => return Err(Infeasibility::Constraint(Constraint(i))),
+            };
+            let bound = row.rhs / &coef;
+            let sense = if coef.is_negative() {
+                flip(row.sense)
+            } else {
+                row.sense
+            };
+            if sense != Sense::Ge && upper[j].as_ref().is_none_or(|u| bound < *u) {
+                upper[j] = Some(bound.clone());
+                upper_from[j] = Some(i);
+            }
+            if sense != Sense::Le && lower[j].as_ref().is_none_or(|l| bound > *l) {
+                lower[j] = Some(bound);
+                lower_from[j] = Some(i);
+            }
+            if let (Some(l), Some(u)) = (&lower[j], &upper[j]) {
+                if l.clone() - u > tolerance(u) {
+                    return Err(Infeasibility::Bounds(Variable(j)));
+                }
+                // Bounds crossed by a rounding error meet
+                if l > u {
+                    upper[j] = Some(l.clone());
+                }
+            }
+        }
+
+        // Of two proportional rows, the one implied by the other goes
+        for i in 0..rows.len() {
+            for k in i + 1..rows.len() {
+                let (a, b) = match (&rows[i], &rows[k]) {
+                    (Some(a), Some(b)) => (a, b),
+                    _ => continue,
+                };
+                let factor = match proportion(&a.terms, &b.terms) {
+                    Some(factor) => factor,
+                    None => continue,
+                };
+                // Row k in the scale of row i
+                let rhs = b.rhs.clone() / &factor;
+                let sense = if factor.is_negative() {
+                    flip(b.sense)
+                } else {
+                    b.sense
+                };
+                let implied = match (a.sense, sense) {
+                    (Sense::Eq, _) if holds(&a.rhs, sense, &rhs) => Some(k),
+                    (_, Sense::Eq) if holds(&rhs, a.sense, &a.rhs) => Some(i),
+                    (Sense::Eq, _) | (_, Sense::Eq) => None,
+                    (Sense::Le, Sense::Le) => Some(if a.rhs <= rhs { k } else { i }),
+                    (Sense::Ge, Sense::Ge) => Some(if a.rhs >= rhs { k } else { i }),
+                    (Sense::Le, Sense::Ge) if holds(&rhs, Sense::Le, &a.rhs) => continue,
+                    (Sense::Ge, Sense::Le) if holds(&a.rhs, Sense::Le, &rhs) => continue,
+                    _ => None,
+                };
+                match implied {
+                    Some(r) => {
+                        rows[r] = None;
+                        changed = true;
+                    }
+                    None => return Err(Infeasibility::Constraint(Constraint(k))),
+                }
+            }
+        }
+    }
+
+    // The reduced program
+    let mut program = LinearProgram::new();
+    program.direction = lp.direction;
+    let mut index = vec![None; n];
+    let mut variables = Vec::new();
+    for (j, data) in lp.variables.iter().enumerate() {
+        if fixed[j].is_some() {
+            continue;
+        }
+        index[j] = Some(variables.len());
+        variables.push(j);
+        program.variables.push(VariableData {
+            name: data.name.clone(),
+            lower: lower[j].clone(),
+            upper: upper[j].clone(),
+            integer: data.integer,
+        });
+        program.objective.push(lp.objective[j].clone());
+    }
+    let mut constraints = Vec::new();
+    for (i, row) in rows.into_iter().enumerate() {
+        if let Some(row) = row {
+            constraints.push(i);
+            program.constraints.push(ConstraintData {
+                name: lp.constraints[i].name.clone(),
+                terms: (row.terms.into_iter())
+                    .map(|(j, coef)| (Variable(index[j].expect("A kept variable")), coef))
+                    .collect(),
+                sense: row.sense,
+                rhs: row.rhs,
+            });
+        }
+    }
+    Ok(Presolved {
+        program,
+        original: lp.clone(),
+        variables,
+        constraints,
+        fixed,
+        lower,
+        upper,
+        lower_from,
+        upper_from,
+    })
+}
+
+impl<T: Number> Presolved<T> {
+    /// The number of variables and constraints presolve took out
+    pub fn removed(&self) -> (usize, usize) {
+        (
+            self.original.variables.len() - self.variables.len(),
+            self.original.constraints.len() - self.constraints.len(),
+        )
+    }
+
+    /// Brings a solution of the reduced program (as `LinearProgram::solve` gives it)
+    /// back to the original program. `x`, `slack`, `duals`, `reduced_costs` and `ray`
+    /// follow the original variables and constraints, the prices of the rows turned bounds
+    /// are recovered from the reduced costs. `basis` stays that of the reduced program,
+    /// `table` is left empty as its columns don't match the original program.
+    pub fn postsolve(&self, solution: &Solution<T>) -> Solution<T> {
+        let lp = &self.original;
+        let (n, m) = (lp.variables.len(), lp.constraints.len());
+        let mut x: Vec<T> = (self.fixed.iter())
+            .map(|value| value.clone().unwrap_or_else(T::zero))
+            .collect();
+        for (k, &j) in self.variables.iter().enumerate() {
+            x[j] = solution.x[k].clone();
+        }
+        // The activities of the constraints, the slack of a `>=` constraint is its surplus
+        let activity = |x: &[T], i: usize| {
+            (lp.constraints[i].terms.iter())
+                .fold(T::zero(), |acc, (var, coef)| acc + coef.clone() * &x[var.0])
+        };
+        let slack = (0..m)
+            .map(|i| {
+                let c = &lp.constraints[i];
+                match c.sense {
+                    Sense::Ge => activity(&x, i) - &c.rhs,
+                    _ => c.rhs.clone() - activity(&x, i),
+                }
+            })
+            .collect();
+        let ray = solution.ray.as_ref().map(|reduced| {
+            let mut ray = vec![T::zero(); n + m];
+            for (k, &j) in self.variables.iter().enumerate() {
+                ray[j] = reduced[k].clone();
+            }
+            for i in 0..m {
+                let change = activity(&ray, i);
+                ray[n + i] = match lp.constraints[i].sense {
+                    Sense::Ge => change,
+                    _ => -change,
+                };
+            }
+            ray
+        });
+
+        let (duals, reduced_costs) = if solution.status == Status::Optimal {
+            let mut duals = vec![T::zero(); m];
+            for (k, &i) in self.constraints.iter().enumerate() {
+                duals[i] = solution.duals[k].clone();
+            }
+            let mut reduced_costs = lp.objective.clone();
+            for (c, y) in lp.constraints.iter().zip(&duals) {
+                for (var, coef) in &c.terms {
+                    reduced_costs[var.0] -= y.clone() * coef;
+                }
+            }
+            // The price of a variable held by a singleton row belongs to the row
+            for j in 0..n {
+                let d = reduced_costs[j].clone();
+                if d.abs() <= T::eps() {
+                    continue;
+                }
+                let at = |bound: &Option<T>| {
+                    bound
+                        .as_ref()
+                        .is_some_and(|b| (x[j].clone() - b).abs() <= tolerance(b))
+                };
+                let (at_lower, at_upper) = (at(&self.lower[j]), at(&self.upper[j]));
+                // Growing the variable pays, so it sits at its upper bound
+                let pays = (d.clone() * lp.sign()).is_positive();
+                let from = match (at_lower, at_upper) {
+                    (true, true) if pays => self.upper_from[j],
+                    (true, true) => self.lower_from[j],
+                    (_, true) => self.upper_from[j],
+                    (true, _) => self.lower_from[j],
+                    _ => None,
+                };
+                if let Some(i) = from {
+                    let coef = (lp.constraints[i].terms.iter())
+                        .filter(|(var, _)| var.0 == j)
+                        .fold(T::zero(), |acc, (_, coef)| acc + coef);
+                    duals[i] = d / coef;
+                    reduced_costs[j] = T::zero();
+                }
+            }
+            (duals, reduced_costs)
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        Solution {
+            status: solution.status,
+            objective: lp.objective_value(&x),
+            x,
+            slack,
+            basis: solution.basis.clone(),
+            ray,
+            duals,
+            reduced_costs,
+            farkas: match solution.status {
+                Status::Infeasible => self.original.farkas(),
+                _ => None,
+            },
+            table: nalgebra::DMatrix::zeros(0, 0),
+            layout: solution.layout.clone(),
+        }
+    }
+}
+
+/// The solution of a program presolve found infeasible: the variables sit at a bound
+pub(super) fn infeasible<T: Number>(lp: &LinearProgram<T>) -> Solution<T> {
+    let (n, m) = (lp.variables.len(), lp.constraints.len());
+    let bounds: Vec<_> = (lp.variables.iter())
+        .map(|v| (v.lower.clone(), v.upper.clone()))
+        .collect();
+    let x: Vec<T> = (bounds.iter())
+        .map(|(lower, upper)| {
+            lower
+                .clone()
+                .or_else(|| upper.clone())
+                .unwrap_or_else(T::zero)
+        })
+        .collect();
+    let slack = (lp.constraints.iter())
+        .map(|c| {
+            let activity =
+                (c.terms.iter()).fold(T::zero(), |acc, (var, coef)| acc + coef.clone() * &x[var.0]);
+            match c.sense {
+                Sense::Ge => activity - &c.rhs,
+                _ => c.rhs.clone() - activity,
+            }
+        })
+        .collect();
+    Solution {
+        status: Status::Infeasible,
+        objective: lp.objective_value(&x),
+        x,
+        slack,
+        basis: Vec::new(),
+        ray: None,
+        duals: Vec::new(),
+        reduced_costs: Vec::new(),
+        farkas: lp.farkas(),
+        table: nalgebra::DMatrix::zeros(0, 0),
+        layout: Layout::new(&bounds, n + m + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Options;
+    use super::*;
+
+    /// A program with every kind of reduction:
+    /// x + y + 2z + w -> max
+    /// empty:  0 <= 1
+    /// fix:    w = 2 (bounds)
+    /// single: 2x <= 8
+    /// c1:     x + y + w <= 10
+    /// c2:     2x + 2y + 2w <= 24 (implied by c1)
+    /// c3:     y + z <= 7
+    /// single: -z >= -3 + w (z <= 5 after w is fixed, but z <= 4 holds anyway)
+    fn program() -> (LinearProgram<f64>, [Variable; 4]) {
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 0.0, None);
+        let y = lp.add_variable("y", 0.0, None);
+        let z = lp.add_variable("z", 0.0, Some(4.0));
+        let w = lp.add_variable("w", 2.0, Some(2.0));
+        lp.add_constraint("empty", &[(x, 0.0)], Sense::Le, 1.0);
+        lp.add_constraint("single", &[(x, 2.0)], Sense::Le, 8.0);
+        lp.add_constraint("c1", &[(x, 1.0), (y, 1.0), (w, 1.0)], Sense::Le, 10.0);
+        lp.add_constraint("c2", &[(x, 2.0), (y, 2.0), (w, 2.0)], Sense::Le, 24.0);
+        lp.add_constraint("c3", &[(y, 1.0), (z, 1.0)], Sense::Le, 7.0);
+        lp.add_constraint("last", &[(z, -1.0), (w, -1.0)], Sense::Ge, -7.0);
+        lp.maximize(&[(x, 1.0), (y, 1.0), (z, 2.0), (w, 1.0)]);
+        (lp, [x, y, z, w])
+    }
+
+    #[test]
+    fn presolve_test_reductions() {
+        let (lp, [x, y, z, _]) = program();
+        let presolved = presolve(&lp).unwrap();
+        // w is fixed, the empty, singleton and duplicate rows go
+        assert_eq!(presolved.removed(), (1, 4));
+        let reduced = &presolved.program;
+        assert_eq!(reduced.variables.len(), 3);
+        assert_eq!(reduced.variable("x"), Some(Variable(0)));
+        assert_eq!(reduced.variables[0].upper, Some(4.0));
+        let names: Vec<_> = reduced
+            .constraints
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["c1", "c3"]);
+        // w = 2 moved to the requirement of c1
+        assert_eq!(reduced.constraints[0].rhs, 8.0);
+
+        let solution = presolved.postsolve(&reduced.solve(&Options::default()));
+        let expected = lp.solve(&Options::default());
+        assert_eq!(solution.status, Status::Optimal);
+        assert!((solution.objective - expected.objective).abs() < 1E-9);
+        assert!(
+            (solution.objective - 17.0).abs() < 1E-9,
+            "{}",
+            solution.objective
+        );
+        assert_eq!(solution.x.len(), 4);
+        assert_eq!(solution.slack.len(), 6);
+        for (a, b) in solution.slack.iter().zip(&expected.slack) {
+            assert!(
+                (a - b).abs() < 1E-9,
+                "{:?} {:?}",
+                solution.slack,
+                expected.slack
+            );
+        }
+        assert!((solution.value(x) - 4.0).abs() < 1E-9);
+        assert!((solution.value(y) - 3.0).abs() < 1E-9);
+        assert!((solution.value(z) - 4.0).abs() < 1E-9);
+        // The singleton row holding x gets its price back
+        assert!(
+            (solution.duals[1] - 0.5).abs() < 1E-9,
+            "{:?}",
+            solution.duals
+        );
+        // The prices are those of the whole program
+        for (a, b) in solution.duals.iter().zip(&expected.duals) {
+            assert!(
+                (a - b).abs() < 1E-9,
+                "{:?} {:?}",
+                solution.duals,
+                expected.duals
+            );
+        }
+        for (a, b) in solution.reduced_costs.iter().zip(&expected.reduced_costs) {
+            assert!((a - b).abs() < 1E-9, "{:?}", solution.reduced_costs);
+        }
+    }
+
+    #[test]
+    fn presolve_test_through_options() {
+        let (mut lp, [_, y, ..]) = program();
+        lp.minimize(&[(y, -1.0)]);
+        let options = Options {
+            presolve: true,
+            ..Options::default()
+        };
+        let res = lp.solve(&options);
+        let expected = lp.solve(&Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.objective - expected.objective).abs() < 1E-9);
+        for (a, b) in res.duals.iter().zip(&expected.duals) {
+            assert!((a - b).abs() < 1E-9, "{:?} {:?}", res.duals, expected.duals);
+        }
+        assert!(lp.ranging(&expected).is_some());
+
+        // The fixed row takes z out of the reduced program, whose table has no ranges
+        // of the original one
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 0.0, None);
+        let y = lp.add_variable("y", 0.0, None);
+        let z = lp.add_variable("z", 0.0, None);
+        lp.add_constraint("c1", &[(x, 1.0), (y, 1.0), (z, 1.0)], Sense::Le, 6.0);
+        lp.add_constraint("c2", &[(z, 1.0)], Sense::Eq, 2.0);
+        lp.maximize(&[(x, 2.0), (y, 1.0), (z, 1.0)]);
+        let res = lp.solve(&options);
+        assert_eq!(res.status, Status::Optimal);
+        assert!((res.objective - 10.0).abs() < 1E-9, "{}", res.objective);
+        assert_eq!(lp.ranging(&res), None);
+    }
+
+    #[test]
+    fn presolve_test_infeasible() {
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 0.0, None);
+        let y = lp.add_variable("y", 0.0, None);
+        lp.add_constraint("c1", &[(x, 1.0), (y, 1.0)], Sense::Le, 4.0);
+        lp.add_constraint("c2", &[(x, -2.0), (y, -2.0)], Sense::Le, -10.0);
+        assert_eq!(
+            presolve(&lp).unwrap_err(),
+            Infeasibility::Constraint(Constraint(1))
+        );
+
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 0.0, Some(3.0));
+        let y = lp.add_variable("y", 2.0, Some(2.0));
+        lp.add_constraint("c1", &[(x, 1.0), (y, -1.0)], Sense::Ge, 2.0);
+        assert_eq!(presolve(&lp).unwrap_err(), Infeasibility::Bounds(x));
+        let options = Options {
+            presolve: true,
+            ..Options::default()
+        };
+        assert_eq!(lp.solve(&options).status, Status::Infeasible);
+
+        let mut lp = LinearProgram::<f64>::new();
+        let x = lp.add_variable("x", 1.0, Some(1.0));
+        lp.add_constraint("c1", &[(x, 1.0)], Sense::Eq, 1.0);
+        lp.add_constraint("c2", &[(x, 3.0)], Sense::Ge, 4.0);
+        assert_eq!(
+            presolve(&lp).unwrap_err(),
+            Infeasibility::Constraint(Constraint(1))
+        );
+    }
+}
@@ -0,0 +1,460 @@
+/// Convex quadratic programming with the primal active-set method:
+/// minimize 1/2 x^T Q x + c^T x subject to A x <sense> b, with Q positive semidefinite.
+/// Phase I of the simplex method finds a feasible vertex, whose tight constraints
+/// make up the first working set. Each step minimizes the objective on the face
+/// of the working set: a blocking constraint on the way joins the set, and at the
+/// minimum of the face a constraint with a multiplier of the wrong sign leaves it,
+/// one constraint at a time like the basis of the simplex method.
+/// [https://en.wikipedia.org/wiki/Active-set_method]
+/// Nocedal, Wright: Numerical Optimization, chapter 16.5
+use super::{bounded_simplex, dot, Number, Options, Sense, Status};
+use nalgebra as na;
+
+/// The result of the active-set method
+#[derive(Debug, Clone)]
+pub struct QpSolution<T: Number> {
+    /// `Unbounded` when the objective function decreases without limit along `ray`
+    pub status: Status,
+    /// The value of the objective function at `x`
+    pub objective: T,
+    /// The minimizer, or the last point reached if there is none
+    pub x: Vec<T>,
+    /// The constraints of the final working set, in increasing order:
+    /// every equality and the inequalities holding tight with a multiplier
+    pub active: Vec<usize>,
+    /// The Lagrange multiplier of each constraint, zero for those not in `active`:
+    /// Q x + c = A^T y, and y_i is the growth of the objective function per unit growth
+    /// of b_i like the duals of the simplex method. Only meaningful for optimal solutions.
+    pub multipliers: Vec<T>,
+    /// The direction along which the objective function decreases forever.
+    /// Only present for unbounded problems.
+    pub ray: Option<Vec<T>>,
+    /// The number of changes of the working set
+    pub iterations: usize,
+}
+
+/// Minimizes 1/2 x^T Q x + c^T x over the free variables x subject to the constraints
+/// `constr` x <sense> `req`, stated as for `two_phase_simplex`. `Q` has to be symmetric
+/// and positive semidefinite. The options go to Phase I, and `max_iterations`
+/// also limits the changes of the working set.
+pub fn active_set<T: Number>(
+    q: &na::DMatrix<T>,
+    c: &[T],
+    constr: &na::DMatrix<T>,
+    senses: &[Sense],
+    req: &[T],
+    options: &Options<T>,
+) -> QpSolution<T> {
+    let n = c.len();
+    assert_eq!(q.shape(), (n, n), "Q is square with a row per variable");
+    assert_eq!(
+        constr.ncols(),
+        n,
+        "One column of the constraints per variable"
+    );
+    assert_eq!(constr.nrows(), senses.len(), "One sense per constraint");
+    assert_eq!(constr.nrows(), req.len(), "One requirement per constraint");
+    let m = constr.nrows();
+    let phase_one = bounded_simplex(
+        constr.clone(),
+        senses.to_vec(),
+        req.to_vec(),
+        vec![T::zero(); n],
+        &vec![(None, None); n],
+        options,
+    );
+    let mut x = phase_one.x;
+    if phase_one.status != Status::Optimal {
+        return QpSolution {
+            status: phase_one.status,
+            objective: objective(q, c, &x),
+            x,
+            active: Vec::new(),
+            multipliers: vec![T::zero(); m],
+            ray: None,
+            iterations: 0,
+        };
+    }
+    // Every constraint as a_i x <= b_i, `>=` ones negated
+    let flip = |i: usize| match senses[i] {
+        Sense::Ge => -T::one(),
+        _ => T::one(),
+    };
+    let a = na::DMatrix::from_fn(m, n, |i, j| constr[(i, j)].clone() * flip(i));
+    let b: Vec<T> = (0..m).map(|i| req[i].clone() * flip(i)).collect();
+    let row = |i: usize| -> Vec<T> { a.row(i).iter().cloned().collect() };
+
+    // The equalities, then the tight inequalities of the vertex, as long as they're independent
+    let mut working: Vec<usize> = Vec::new();
+    let tight = |i: usize, x: &[T]| (dot(&row(i), x) - &b[i]).abs() <= T::eps();
+    let order = (0..m).filter(|&i| senses[i] == Sense::Eq);
+    for i in order.chain((0..m).filter(|&i| senses[i] != Sense::Eq && tight(i, &x))) {
+        let mut rows = working.clone();
+        rows.push(i);
+        let stacked = na::DMatrix::from_fn(rows.len(), n, |r, j| a[(rows[r], j)].clone());
+        if rank(stacked) == rows.len() {
+            working.push(i);
+        }
+    }
+
+    let mut iterations = 0;
+    let mut multipliers = vec![T::zero(); m];
+    let status = loop {
+        if iterations >= options.max_iterations {
+            break Status::IterationLimit;
+        }
+        // The KKT system of the face: [Q A_W^T; A_W 0] [p; mu] = [-(Q x + c); 0]
+        let w = working.len();
+        let gradient: Vec<T> = (0..n)
+            .map(|i| dot(&q.row(i).iter().cloned().collect::<Vec<_>>(), &x) + &c[i])
+            .collect();
+        let kkt = na::DMatrix::from_fn(n + w, n + w, |r, s| match (r < n, s < n) {
+            (true, true) => q[(r, s)].clone(),
+            (true, false) => a[(working[s - n], r)].clone(),
+            (false, true) => a[(working[r - n], s)].clone(),
+            (false, false) => T::zero(),
+        });
+        let rhs: Vec<T> = (0..n + w)
+            .map(|r| {
+                if r < n {
+                    -gradient[r].clone()
+                } else {
+                    T::zero()
+                }
+            })
+            .collect();
+        let (p, mu, bounded) = match solve(kkt, &rhs) {
+            Ok(z) => (z[..n].to_vec(), z[n..].to_vec(), true),
+            // A direction of zero curvature along the face, turned downhill
+            Err(z) => {
+                let mut p = z[..n].to_vec();
+                if dot(&gradient, &p) > T::zero() {
+                    p.iter_mut().for_each(|p| *p = -p.clone());
+                }
+                (p, Vec::new(), false)
+            }
+        };
+
+        if bounded && p.iter().all(|p| p.abs() <= T::eps()) {
+            // The minimum of the face: optimal unless an inequality pulls the wrong way
+            let leaving = (0..w)
+                .filter(|&k| senses[working[k]] != Sense::Eq && mu[k] < -T::eps())
+                .min_by(|&k, &l| mu[k].partial_cmp(&mu[l]).unwrap());
+            match leaving {
+                Some(k) => {
+                    working.remove(k);
+                    iterations += 1;
+                }
+                None => {
+                    for (k, &i) in working.iter().enumerate() {
+                        multipliers[i] = -mu[k].clone() * flip(i);
+                    }
+                    break Status::Optimal;
+                }
+            }
+            continue;
+        }
+
+        // The ratio test: the first constraint out of the working set the step runs into
+        let mut step: Option<(T, usize)> = None;
+        for i in (0..m).filter(|i| !working.contains(i)) {
+            let rate = dot(&row(i), &p);
+            if rate <= T::eps() {
+                continue;
+            }
+            let ratio = (b[i].clone() - dot(&row(i), &x)) / rate;
+            if step.as_ref().is_none_or(|(least, _)| ratio < *least) {
+                step = Some((ratio, i));
+            }
+        }
+        let (length, blocking) = match step {
+            Some((ratio, i)) if !bounded || ratio < T::one() => (ratio, Some(i)),
+            None if !bounded => {
+                return QpSolution {
+                    status: Status::Unbounded,
+                    objective: objective(q, c, &x),
+                    x,
+                    active: sorted(working),
+                    multipliers,
+                    ray: Some(p),
+                    iterations,
+                }
+            }
+            _ => (T::one(), None),
+        };
+        let length = if length < T::zero() {
+            T::zero()
+        } else {
+            length
+        };
+        for (x, p) in x.iter_mut().zip(&p) {
+            *x += length.clone() * p;
+        }
+        if let Some(i) = blocking {
+            working.push(i);
+            iterations += 1;
+        }
+    };
+    QpSolution {
+        status,
+        objective: objective(q, c, &x),
+        x,
+        active: sorted(working),
+        multipliers,
+        ray: None,
+        iterations,
+    }
+}
+
+/// 1/2 x^T Q x + c^T x
+fn objective<T: Number>(q: &na::DMatrix<T>, c: &[T], x: &[T]) -> T {
+    let mut value = dot(c, x);
+    for (i, xi) in x.iter().enumerate() {
+        for (j, xj) in x.iter().enumerate() {
+            value += q[(i, j)].clone() * xi * xj / (T::one() + T::one());
+        }
+    }
+    value
+}
+
+fn sorted(mut indices: Vec<usize>) -> Vec<usize> {
+    indices.sort_unstable();
+    indices
+}
+
+/// Brings the matrix into the reduced row echelon form, along with any extra columns
+/// to the right of the first `n`. Returns the pivot column of each leading row.
+fn eliminate<T: Number>(m: &mut na::DMatrix<T>, n: usize) -> Vec<usize> {
+    let mut pivots = Vec::new();
+    for col in 0..n {
+        let r0 = pivots.len();
+        if r0 == m.nrows() {
+            break;
+        }
+        let best = (r0..m.nrows())
+            .max_by(|&r, &s| m[(r, col)].abs().partial_cmp(&m[(s, col)].abs()).unwrap())
+            .unwrap();
+        if m[(best, col)].abs() <= T::eps() {
+            continue;
+        }
+        m.swap_rows(r0, best);
+        let pivot = m[(r0, col)].clone();
+        for s in 0..m.ncols() {
+            m[(r0, s)] /= &pivot;
+        }
+        for r in (0..m.nrows()).filter(|&r| r != r0) {
+            let factor = m[(r, col)].clone();
+            if factor.is_zero() {
+                continue;
+            }
+            for s in 0..m.ncols() {
+                let delta = m[(r0, s)].clone() * &factor;
+                m[(r, s)] -= delta;
+            }
+        }
+        pivots.push(col);
+    }
+    pivots
+}
+
+fn rank<T: Number>(mut m: na::DMatrix<T>) -> usize {
+    let n = m.ncols();
+    eliminate(&mut m, n).len()
+}
+
+/// Solves the symmetric system K z = rhs, any free unknowns set to zero. If there's no
+/// solution, returns a z with K z = 0 and z^T rhs != 0 instead, found by keeping
+/// track of the row operations.
+fn solve<T: Number>(k: na::DMatrix<T>, rhs: &[T]) -> Result<Vec<T>, Vec<T>> {
+    let n = k.nrows();
+    let mut m = na::DMatrix::from_fn(n, 2 * n + 1, |r, s| match s {
+        s if s < n => k[(r, s)].clone(),
+        s if s == n => rhs[r].clone(),
+        s if s - n - 1 == r => T::one(),
+        _ => T::zero(),
+    });
+    let pivots = eliminate(&mut m, n);
+    // A row of zeros equal to a nonzero: the combination of the rows it came from
+    if let Some(r) = (pivots.len()..n).find(|&r| m[(r, n)].abs() > T::eps()) {
+        return Err((0..n).map(|s| m[(r, n + 1 + s)].clone()).collect());
+    }
+    let mut z = vec![T::zero(); n];
+    for (r, &col) in pivots.iter().enumerate() {
+        z[col] = m[(r, n)].clone();
+    }
+    Ok(z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplex::Rational;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    /// The KKT conditions: feasibility, stationarity, the signs of the multipliers
+    /// and complementary slackness
+    fn check(
+        q: &na::DMatrix<f64>,
+        c: &[f64],
+        constr: &na::DMatrix<f64>,
+        senses: &[Sense],
+        req: &[f64],
+        res: &QpSolution<f64>,
+    ) {
+        assert_eq!(res.status, Status::Optimal);
+        let n = c.len();
+        for i in 0..constr.nrows() {
+            let lhs: f64 = (0..n).map(|j| constr[(i, j)] * res.x[j]).sum();
+            let y = res.multipliers[i];
+            match senses[i] {
+                Sense::Le => assert!(lhs <= req[i] + 1E-7 && y <= 1E-7, "{:?}", res),
+                Sense::Ge => assert!(lhs >= req[i] - 1E-7 && y >= -1E-7, "{:?}", res),
+                Sense::Eq => assert!((lhs - req[i]).abs() < 1E-7, "{:?}", res),
+            }
+            assert!((y * (lhs - req[i])).abs() < 1E-7, "{:?}", res);
+        }
+        for j in 0..n {
+            let gradient: f64 = (0..n).map(|k| q[(j, k)] * res.x[k]).sum::<f64>() + c[j];
+            let pulled: f64 = (0..constr.nrows())
+                .map(|i| constr[(i, j)] * res.multipliers[i])
+                .sum();
+            assert!((gradient - pulled).abs() < 1E-7, "{:?}", res);
+        }
+    }
+
+    #[test]
+    fn quadratic_test_textbook() {
+        // Nocedal, Wright, example 16.4: (x1 - 1)^2 + (x2 - 2.5)^2 -> min
+        let r = |n: i64, d: i64| Rational::new(n, d);
+        let q = na::DMatrix::from_row_slice(2, 2, &[2, 0, 0, 2].map(|x| r(x, 1)));
+        let c = [r(-2, 1), r(-5, 1)];
+        let constr =
+            na::DMatrix::from_row_slice(5, 2, &[1, -2, -1, -2, -1, 2, 1, 0, 0, 1].map(|x| r(x, 1)));
+        let req = [-2, -6, -2, 0, 0].map(|x| r(x, 1));
+        let res = active_set(&q, &c, &constr, &[Sense::Ge; 5], &req, &Options::default());
+        assert_eq!(res.status, Status::Optimal);
+        assert_eq!(res.x, vec![r(7, 5), r(17, 10)]);
+        assert_eq!(res.objective, r(-129, 20));
+        assert_eq!(res.active, vec![0]);
+        assert_eq!(
+            res.multipliers,
+            vec![r(4, 5), r(0, 1), r(0, 1), r(0, 1), r(0, 1)]
+        );
+    }
+
+    #[test]
+    fn quadratic_test_least_squares_and_portfolio() {
+        // ||A x - b||^2 -> min with 0 <= x <= 1 and x1 + x2 + x3 <= 1.5
+        let a = na::DMatrix::from_row_slice(
+            4,
+            3,
+            &[1.0, 2.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 1.0, 2.0, 1.0, 1.0],
+        );
+        let b = na::DVector::from_row_slice(&[3.0, 2.0, -1.0, 4.0]);
+        let q = a.transpose() * &a * 2.0;
+        let c: Vec<f64> = (a.transpose() * &b * -2.0).iter().cloned().collect();
+        let mut constr = na::DMatrix::zeros(7, 3);
+        for j in 0..3 {
+            constr[(j, j)] = 1.0;
+            constr[(j + 3, j)] = 1.0;
+            constr[(6, j)] = 1.0;
+        }
+        let senses = [[Sense::Ge; 3], [Sense::Le; 3]].concat();
+        let senses = [senses, vec![Sense::Le]].concat();
+        let req = [0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.5];
+        let res = active_set(&q, &c, &constr, &senses, &req, &Options::default());
+        check(&q, &c, &constr, &senses, &req, &res);
+        assert!(!res.active.is_empty());
+
+        // Without the constraints it's the least squares solution
+        let res = active_set(
+            &q,
+            &c,
+            &na::DMatrix::zeros(0, 3),
+            &[],
+            &[],
+            &Options::default(),
+        );
+        let expected = crate::lstsq::least_squares_gen(a, b).unwrap();
+        assert_eq!(res.status, Status::Optimal);
+        assert!((0..3).all(|j| (res.x[j] - expected[j]).abs() < 1E-7));
+
+        // The least risky portfolio of three assets earning at least 10% on the whole
+        let covariance = na::DMatrix::from_row_slice(
+            3,
+            3,
+            &[0.04, 0.006, 0.0, 0.006, 0.09, 0.01, 0.0, 0.01, 0.16],
+        );
+        let c = [0.0; 3];
+        let mut constr = na::DMatrix::from_row_slice(2, 3, &[1.0, 1.0, 1.0, 0.06, 0.1, 0.15]);
+        constr = constr.insert_rows(2, 3, 0.0);
+        for j in 0..3 {
+            constr[(j + 2, j)] = 1.0;
+        }
+        let senses = [Sense::Eq, Sense::Ge, Sense::Ge, Sense::Ge, Sense::Ge];
+        let req = [1.0, 0.1, 0.0, 0.0, 0.0];
+        let res = active_set(&covariance, &c, &constr, &senses, &req, &Options::default());
+        check(&covariance, &c, &constr, &senses, &req, &res);
+        assert!((res.x.iter().sum::<f64>() - 1.0).abs() < 1E-9);
+        // Asking for more return costs risk
+        assert!(res.active.contains(&1) && res.multipliers[1] > 0.0);
+    }
+
+    #[test]
+    fn quadratic_test_random() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut random = || rng.gen_range(-10, 11) as f64;
+        for &(n, m, rank) in &[(3, 4, 3), (4, 6, 2), (5, 8, 5), (4, 5, 0)] {
+            // Q = L L^T with L of the given rank, so Q may be singular
+            let l = na::DMatrix::from_fn(n, rank, |_, _| random());
+            let q = &l * l.transpose();
+            let c: Vec<f64> = (0..n).map(|_| random()).collect();
+            // A box keeps the problem bounded, the other constraints hold at zero
+            let mut constr = na::DMatrix::from_fn(m, n, |_, _| random());
+            let mut req: Vec<f64> = (0..m).map(|_| random().abs() + 1.0).collect();
+            let mut senses = vec![Sense::Le; m];
+            constr = constr.insert_rows(m, 2 * n, 0.0);
+            for j in 0..n {
+                constr[(m + j, j)] = 1.0;
+                constr[(m + n + j, j)] = 1.0;
+            }
+            req.extend(vec![5.0; n]);
+            req.extend(vec![-5.0; n]);
+            senses.extend(vec![Sense::Le; n]);
+            senses.extend(vec![Sense::Ge; n]);
+            let res = active_set(&q, &c, &constr, &senses, &req, &Options::default());
+            check(&q, &c, &constr, &senses, &req, &res);
+            if rank == 0 {
+                // A linear program, the simplex method maximizes -c^T x
+                let negated: Vec<f64> = c.iter().map(|c| -c).collect();
+                let lp = bounded_simplex(
+                    constr,
+                    senses,
+                    req,
+                    negated,
+                    &vec![(None, None); n],
+                    &Options::default(),
+                );
+                assert_eq!(lp.status, Status::Optimal);
+                assert!((lp.objective + res.objective).abs() < 1E-7);
+            }
+        }
+    }
+
+    #[test]
+    fn quadratic_test_unbounded_and_infeasible() {
+        // x1^2 - x2 -> min with x1 + x2 >= 1: x2 runs off
+        let q = na::DMatrix::from_row_slice(2, 2, &[2.0, 0.0, 0.0, 0.0]);
+        let constr = na::DMatrix::from_row_slice(1, 2, &[1.0, 1.0]);
+        let opts = Options::default();
+        let res = active_set(&q, &[0.0, -1.0], &constr, &[Sense::Ge], &[1.0], &opts);
+        assert_eq!(res.status, Status::Unbounded);
+        let ray: Vec<f64> = res.ray.unwrap();
+        assert!(ray[0].abs() < 1E-9 && ray[1] > 0.0);
+
+        let constr = na::DMatrix::from_row_slice(2, 2, &[1.0, 1.0, 1.0, 1.0]);
+        let senses = [Sense::Ge, Sense::Le];
+        let res = active_set(&q, &[0.0, 1.0], &constr, &senses, &[2.0, 1.0], &opts);
+        assert_eq!(res.status, Status::Infeasible);
+    }
+}
@@ -0,0 +1,896 @@
+/// The revised simplex method: instead of the whole table it keeps the sparse constraint
+/// matrix and an LU factorization of the basis, from which it computes the shadow prices
+/// (btran) and the entering column (ftran) of each pivot. The factorization is updated
+/// with an eta column per pivot and computed anew every `Options::refactor_every` pivots.
+/// Every row gets a logical variable s_i, so that A x + s = b with
+/// s_i >= 0 (`<=`), s_i <= 0 (`>=`) or s_i = 0 (`=`), and the variables live within
+/// their bounds, sitting at one of them when non-basic.
+/// [https://en.wikipedia.org/wiki/Revised_simplex_method]
+use super::observer::{Initialization, Phase, Pivot, Termination};
+use super::sensitivity::Interval;
+use super::sparse::{SparseLu, SparseMatrix};
+use super::{dot, Layout, Number, Options, Sense, Solution, Status};
+use nalgebra as na;
+
+/// How many degenerate pivots in a row switch the pricing over to Bland's rule,
+/// which never cycles
+const DEGENERATE_LIMIT: usize = 50;
+
+/// What a run of pivots ended with
+pub(super) enum Outcome<T> {
+    Optimal,
+    /// The column that could grow forever and the direction it moves in
+    Unbounded(usize, T),
+    /// No point satisfies the constraints
+    Infeasible,
+    IterationLimit,
+}
+
+/// The state of the method: the columns are the decision variables, followed by
+/// the logical variables and the artificial ones of Phase I (one per row).
+#[derive(Debug, Clone)]
+pub(super) struct Revised<T: Number> {
+    pub(super) constr: SparseMatrix<T>,
+    pub(super) senses: Vec<Sense>,
+    pub(super) req: Vec<T>,
+    /// The coefficient of each artificial variable in its row, zero if it isn't needed
+    pub(super) artificial: Vec<T>,
+    pub(super) lower: Vec<Option<T>>,
+    pub(super) upper: Vec<Option<T>>,
+    /// The values of all the columns, non-basic ones sit at a bound (or at zero if free)
+    pub(super) x: Vec<T>,
+    /// The column of each basis position
+    pub(super) basis: Vec<usize>,
+    /// The basis position of each column
+    pub(super) position: Vec<Option<usize>>,
+    pub(super) lu: SparseLu<T>,
+    /// The multipliers of the constraints proving the last `Outcome::Infeasible`
+    pub(super) farkas: Option<Vec<T>>,
+}
+
+impl<T: Number> Revised<T> {
+    /// Puts the non-basic variables at a bound, the logical variables take up the rest
+    /// of the requirements if they can, the artificial ones otherwise
+    pub(super) fn new(
+        constr: SparseMatrix<T>,
+        senses: Vec<Sense>,
+        req: Vec<T>,
+        bounds: &[Interval<T>],
+    ) -> Self {
+        let (m, n) = (constr.nrows(), constr.ncols());
+        assert_eq!(m, senses.len(), "One sense per constraint");
+        assert_eq!(m, req.len(), "One requirement per constraint");
+        assert_eq!(n, bounds.len(), "One pair of bounds per variable");
+
+        let (mut lower, mut upper): (Vec<_>, Vec<_>) = bounds.iter().cloned().unzip();
+        for sense in &senses {
+            let (low, high) = logical_bounds(*sense);
+            lower.push(low);
+            upper.push(high);
+        }
+        // The artificial variables stay at zero unless Phase I needs them
+        lower.extend(vec![Some(T::zero()); m]);
+        upper.extend(vec![Some(T::zero()); m]);
+
+        let mut x: Vec<T> = (lower.iter().zip(&upper))
+            .map(|(lower, upper)| at_bound(lower, upper))
+            .collect();
+        let mut rest = req.clone();
+        for (j, value) in x.iter().enumerate().take(n) {
+            for (i, v) in constr.column(j) {
+                rest[i] -= v.clone() * value;
+            }
+        }
+        let mut artificial = vec![T::zero(); m];
+        let mut basis = Vec::with_capacity(m);
+        for (i, r) in rest.into_iter().enumerate() {
+            let fits = lower[n + i].as_ref().is_none_or(|l| r >= *l)
+                && upper[n + i].as_ref().is_none_or(|u| r <= *u);
+            if fits {
+                x[n + i] = r;
+                basis.push(n + i);
+            } else {
+                let a = n + m + i;
+                artificial[i] = if r.is_negative() { -T::one() } else { T::one() };
+                x[a] = r.abs();
+                upper[a] = None;
+                basis.push(a);
+            }
+        }
+        let mut revised = Revised {
+            constr,
+            senses,
+            req,
+            artificial,
+            lower,
+            upper,
+            x,
+            basis,
+            position: Vec::new(),
+            lu: SparseLu::factorize(&[]).expect("An empty matrix is regular"),
+            farkas: None,
+        };
+        revised.refactor();
+        revised
+    }
+
+    /// The number of decision variables
+    pub(super) fn n(&self) -> usize {
+        self.constr.ncols()
+    }
+
+    /// The number of constraints
+    pub(super) fn m(&self) -> usize {
+        self.constr.nrows()
+    }
+
+    fn ncols(&self) -> usize {
+        self.x.len()
+    }
+
+    /// The non-zeros of the column
+    pub(super) fn column(&self, j: usize) -> Vec<(usize, T)> {
+        let (n, m) = (self.n(), self.m());
+        if j < n {
+            self.constr.column(j).map(|(i, v)| (i, v.clone())).collect()
+        } else if j < n + m {
+            vec![(j - n, T::one())]
+        } else {
+            vec![(j - n - m, self.artificial[j - n - m].clone())]
+        }
+    }
+
+    /// The column as a dense vector
+    pub(super) fn dense_column(&self, j: usize) -> Vec<T> {
+        let mut dense = vec![T::zero(); self.m()];
+        for (i, v) in self.column(j) {
+            dense[i] = v;
+        }
+        dense
+    }
+
+    pub(super) fn dot_column(&self, j: usize, y: &[T]) -> T {
+        if j < self.n() {
+            self.constr.dot_column(j, y)
+        } else {
+            (self.column(j).into_iter()).fold(T::zero(), |acc, (i, v)| acc + v * &y[i])
+        }
+    }
+
+    pub(super) fn is_fixed(&self, j: usize) -> bool {
+        match (&self.lower[j], &self.upper[j]) {
+            (Some(lower), Some(upper)) => lower == upper,
+            _ => false,
+        }
+    }
+
+    /// Some variable has its lower bound above its upper one
+    pub(super) fn crossed(&self) -> bool {
+        (self.lower.iter().zip(&self.upper)).any(|bounds| match bounds {
+            (Some(lower), Some(upper)) => upper < lower,
+            _ => false,
+        })
+    }
+
+    /// Factorizes the basis and recomputes the basic variables from the non-basic ones,
+    /// which also clears the errors the updates have piled up. A basis the round-off
+    /// has made singular is repaired with logical columns first.
+    pub(super) fn refactor(&mut self) {
+        self.lu = match self.factorize() {
+            Some(lu) => lu,
+            None => {
+                self.repair();
+                match self.factorize() {
+                    Some(lu) => lu,
+                    None => {
+                        // The logical columns alone always make a basis
+                        let n = self.n();
+                        for p in 0..self.m() {
+                            self.replace_basic(p, n + p);
+                        }
+                        self.factorize().expect("The logical columns are regular")
+                    }
+                }
+            }
+        };
+        self.position = vec![None; self.ncols()];
+        for (p, &j) in self.basis.iter().enumerate() {
+            self.position[j] = Some(p);
+        }
+        let mut rest = self.req.clone();
+        for j in (0..self.ncols()).filter(|&j| self.position[j].is_none()) {
+            if !self.x[j].is_zero() {
+                for (i, v) in self.column(j) {
+                    rest[i] -= v * &self.x[j];
+                }
+            }
+        }
+        for (p, value) in self.lu.ftran(rest).into_iter().enumerate() {
+            self.x[self.basis[p]] = value;
+        }
+    }
+
+    fn factorize(&self) -> Option<SparseLu<T>> {
+        let columns: Vec<_> = self.basis.iter().map(|&j| self.column(j)).collect();
+        SparseLu::factorize(&columns)
+    }
+
+    /// Swaps the basic columns that depend on the others for the logical columns
+    /// of the rows they leave uncovered, by a Gaussian elimination of the basis
+    fn repair(&mut self) {
+        let m = self.m();
+        let mut covered = vec![false; m];
+        let mut eliminated: Vec<(usize, Vec<T>)> = Vec::new();
+        let mut dependent = Vec::new();
+        for p in 0..m {
+            let mut column = self.dense_column(self.basis[p]);
+            for (r, pivot) in &eliminated {
+                let factor = column[*r].clone() / &pivot[*r];
+                if !factor.is_zero() {
+                    for (c, v) in column.iter_mut().zip(pivot) {
+                        *c -= factor.clone() * v;
+                    }
+                }
+            }
+            let row = (0..m)
+                .filter(|&i| !covered[i])
+                .max_by(|&a, &b| column[a].abs().partial_cmp(&column[b].abs()).unwrap());
+            match row {
+                Some(r) if column[r].abs() > T::eps() => {
+                    covered[r] = true;
+                    eliminated.push((r, column));
+                }
+                _ => dependent.push(p),
+            }
+        }
+        let uncovered = (0..m).filter(|&i| !covered[i]);
+        let n = self.n();
+        for (p, r) in dependent.into_iter().zip(uncovered) {
+            self.replace_basic(p, n + r);
+        }
+    }
+
+    /// Puts the column `j` at basis position `p`, the column leaving it goes
+    /// to its nearest bound (or zero if it's free)
+    fn replace_basic(&mut self, p: usize, j: usize) {
+        let leaving = self.basis[p];
+        let value = &self.x[leaving];
+        self.x[leaving] = match (&self.lower[leaving], &self.upper[leaving]) {
+            (Some(lower), Some(upper)) => {
+                if value.clone() - lower <= upper.clone() - value {
+                    lower.clone()
+                } else {
+                    upper.clone()
+                }
+            }
+            (lower, upper) => at_bound(lower, upper),
+        };
+        self.basis[p] = j;
+    }
+
+    /// The shadow prices of the rows under the given costs of the columns
+    pub(super) fn prices(&self, cost: &[T]) -> Vec<T> {
+        self.lu
+            .btran(self.basis.iter().map(|&j| cost[j].clone()).collect())
+    }
+
+    /// Exchanges the leaving basic variable at position `p`, which ends up at `bound`,
+    /// for the entering column `q`. `alpha` is the entering column in terms of the basis,
+    /// `dir` the direction it moves in and `step` how far.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn pivot(
+        &mut self,
+        q: usize,
+        p: usize,
+        alpha: &[T],
+        dir: &T,
+        step: &T,
+        bound: T,
+        options: &Options<T>,
+    ) {
+        for (i, a) in alpha.iter().enumerate() {
+            let j = self.basis[i];
+            self.x[j] -= dir.clone() * a * step;
+        }
+        let j = self.basis[p];
+        self.x[q] += dir.clone() * step;
+        self.x[j] = bound;
+        self.position[j] = None;
+        self.position[q] = Some(p);
+        self.basis[p] = q;
+        self.lu.update(p, alpha);
+        if self.lu.updates() >= options.refactor_every {
+            self.refactor();
+        }
+    }
+
+    /// Pivots until no column improves the objective function
+    pub(super) fn run(
+        &mut self,
+        cost: &[T],
+        phase: Phase,
+        options: &Options<T>,
+        iterations: &mut usize,
+    ) -> Outcome<T> {
+        let mut degenerate = 0;
+        loop {
+            let bland = degenerate >= DEGENERATE_LIMIT;
+            let y = self.prices(cost);
+            // The entering column and the direction it moves in
+            let mut entering: Option<(usize, T, T)> = None;
+            for (j, c) in cost.iter().enumerate() {
+                if self.position[j].is_some() || self.is_fixed(j) {
+                    continue;
+                }
+                let d = c.clone() - self.dot_column(j, &y);
+                let can_grow = self.upper[j].as_ref().is_none_or(|u| self.x[j] < *u);
+                let can_fall = self.lower[j].as_ref().is_none_or(|l| self.x[j] > *l);
+                let dir = if d > T::eps() && can_grow {
+                    T::one()
+                } else if d < -T::eps() && can_fall {
+                    -T::one()
+                } else {
+                    continue;
+                };
+                let score = d.abs();
+                if entering.as_ref().is_none_or(|(_, _, best)| score > *best) {
+                    entering = Some((j, dir, score));
+                    if bland {
+                        break;
+                    }
+                }
+            }
+            let (q, dir) = match entering {
+                Some((q, dir, _)) => (q, dir),
+                None => return Outcome::Optimal,
+            };
+            if *iterations >= options.max_iterations {
+                return Outcome::IterationLimit;
+            }
+            *iterations += 1;
+
+            let alpha = self.lu.ftran(self.dense_column(q));
+            // The basic variables stop at their bounds, the ties go to the largest pivot
+            // (or the smallest column under Bland's rule)
+            let mut leaving: Option<(T, usize, bool)> = None;
+            for (p, a) in alpha.iter().enumerate() {
+                let rate = -dir.clone() * a;
+                let j = self.basis[p];
+                let limit = if rate < -T::eps() {
+                    (self.lower[j].clone()).map(|l| (self.x[j].clone() - l) / -rate.clone())
+                } else if rate > T::eps() {
+                    (self.upper[j].clone()).map(|u| (u - &self.x[j]) / rate.clone())
+                } else {
+                    None
+                };
+                let ratio = match limit {
+                    Some(ratio) if ratio.is_negative() => T::zero(),
+                    Some(ratio) => ratio,
+                    None => continue,
+                };
+                let better = match &leaving {
+                    None => true,
+                    Some((best, b, _)) if (ratio.clone() - best).abs() <= T::eps() => {
+                        if bland {
+                            j < self.basis[*b]
+                        } else {
+                            a.abs() > alpha[*b].abs()
+                        }
+                    }
+                    Some((best, _, _)) => ratio < *best,
+                };
+                if better {
+                    leaving = Some((ratio, p, rate > T::zero()));
+                }
+            }
+            let flip = match (&self.lower[q], &self.upper[q]) {
+                (Some(l), Some(u)) => Some(u.clone() - l),
+                _ => None,
+            };
+            let step = match (&leaving, &flip) {
+                (Some((ratio, _, _)), Some(flip)) if flip <= ratio => flip.clone(),
+                (Some((ratio, _, _)), _) => ratio.clone(),
+                (None, Some(flip)) => flip.clone(),
+                (None, None) => return Outcome::Unbounded(q, dir),
+            };
+            if step <= T::eps() {
+                degenerate += 1;
+            } else {
+                degenerate = 0;
+            }
+
+            let left = match leaving {
+                Some((ratio, p, to_upper)) if flip.as_ref().is_none_or(|f| ratio < *f) => {
+                    let j = self.basis[p];
+                    let bound = if to_upper {
+                        self.upper[j].clone()
+                    } else {
+                        self.lower[j].clone()
+                    };
+                    let bound = bound.expect("The variable stopped at this bound");
+                    self.pivot(q, p, &alpha, &dir, &step, bound, options);
+                    Some(j)
+                }
+                _ => {
+                    // The entering variable jumps over to its other bound
+                    for (i, a) in alpha.iter().enumerate() {
+                        let j = self.basis[i];
+                        self.x[j] -= dir.clone() * a * &step;
+                    }
+                    self.x[q] = if dir.is_positive() {
+                        self.upper[q].clone()
+                    } else {
+                        self.lower[q].clone()
+                    }
+                    .expect("Only bounded variables flip");
+                    None
+                }
+            };
+            self.report(phase, *iterations, q, left, cost, options);
+        }
+    }
+
+    /// Tells the observer about the pivot just made
+    pub(super) fn report(
+        &self,
+        phase: Phase,
+        iteration: usize,
+        entering: usize,
+        leaving: Option<usize>,
+        cost: &[T],
+        options: &Options<T>,
+    ) {
+        options.observer.pivoted(&Pivot {
+            iteration,
+            phase,
+            entering,
+            leaving,
+            objective: dot(cost, &self.x),
+        });
+    }
+
+    /// The objective function over all the columns, zeros beyond the decision variables
+    pub(super) fn cost(&self, obj: &[T]) -> Vec<T> {
+        let mut cost = obj.to_vec();
+        cost.resize(self.ncols(), T::zero());
+        cost
+    }
+
+    /// Phase I (if the artificial variables are in use) followed by Phase II
+    pub(super) fn optimize(
+        &mut self,
+        obj: &[T],
+        options: &Options<T>,
+        iterations: &mut usize,
+    ) -> Outcome<T> {
+        let (n, m) = (self.n(), self.m());
+        self.farkas = None;
+        if self.crossed() {
+            return Outcome::Infeasible;
+        }
+        if self.basis.iter().any(|&j| j >= n + m) {
+            // Phase I: drive the artificial variables down to zero
+            let mut cost = vec![T::zero(); self.ncols()];
+            for (i, a) in self.artificial.iter().enumerate() {
+                if !a.is_zero() {
+                    cost[n + m + i] = -T::one();
+                }
+            }
+            if let Outcome::IterationLimit = self.run(&cost, Phase::One, options, iterations) {
+                return Outcome::IterationLimit;
+            }
+            let max_req =
+                self.req.iter().fold(
+                    T::zero(),
+                    |acc, b| {
+                        if b.abs() > acc {
+                            b.abs()
+                        } else {
+                            acc
+                        }
+                    },
+                );
+            let left = self.x[n + m..].iter().fold(T::zero(), |acc, a| acc + a);
+            if left > T::eps() * (T::one() + max_req) {
+                // The prices of Phase I tell how far relaxing each constraint
+                // gets the artificial variables down, that proves them needed
+                self.farkas = Some(self.prices(&cost));
+                return Outcome::Infeasible;
+            }
+            // The artificial variables left in the basis are zeros and stay so
+            for a in n + m..n + 2 * m {
+                self.upper[a] = Some(T::zero());
+            }
+        }
+        self.run(&self.cost(obj), Phase::Two, options, iterations)
+    }
+
+    /// The solution as `x` and `slack` along with the prices under the given objective
+    /// function, `ray` goes along with the unbounded ones
+    pub(super) fn solution(&self, outcome: Outcome<T>, obj: &[T]) -> Solution<T> {
+        let (n, m) = (self.n(), self.m());
+        let status = match outcome {
+            Outcome::Optimal => Status::Optimal,
+            Outcome::Unbounded(..) => Status::Unbounded,
+            Outcome::Infeasible => Status::Infeasible,
+            Outcome::IterationLimit => Status::IterationLimit,
+        };
+        // The slack of a `>=` constraint is its surplus
+        let surplus = |values: &mut [T]| {
+            for (i, sense) in self.senses.iter().enumerate() {
+                if *sense == Sense::Ge {
+                    values[n + i] = -values[n + i].clone();
+                }
+            }
+        };
+        let ray = match outcome {
+            Outcome::Unbounded(q, dir) => {
+                let alpha = self.lu.ftran(self.dense_column(q));
+                let mut ray = vec![T::zero(); n + m];
+                ray[q] = dir.clone();
+                for (p, a) in alpha.into_iter().enumerate() {
+                    if self.basis[p] < n + m {
+                        ray[self.basis[p]] = -dir.clone() * a;
+                    }
+                }
+                surplus(&mut ray);
+                Some(ray)
+            }
+            _ => None,
+        };
+        let mut values = self.x[..n + m].to_vec();
+        surplus(&mut values);
+        let slack = values.split_off(n);
+        let (duals, reduced_costs) = if status == Status::Optimal {
+            let y = self.prices(&self.cost(obj));
+            let reduced_costs = (0..n)
+                .map(|j| obj[j].clone() - self.dot_column(j, &y))
+                .collect();
+            (y, reduced_costs)
+        } else {
+            (Vec::new(), Vec::new())
+        };
+        let bounds: Vec<_> = (self.lower.iter().zip(&self.upper))
+            .take(n)
+            .map(|(lower, upper)| (lower.clone(), upper.clone()))
+            .collect();
+        Solution {
+            status,
+            objective: dot(obj, &values),
+            x: values,
+            slack,
+            // An artificial column is its row's logical one up to the sign
+            basis: (self.basis.iter())
+                .map(|&j| if j >= n + m { j - m } else { j })
+                .collect(),
+            ray,
+            duals,
+            reduced_costs,
+            farkas: match status {
+                Status::Infeasible => self.farkas.clone(),
+                _ => None,
+            },
+            table: na::DMatrix::zeros(0, 0),
+            layout: Layout::new(&bounds, n + m + 1),
+        }
+    }
+}
+
+/// The bounds of the logical variable of a constraint
+pub(super) fn logical_bounds<T: Number>(sense: Sense) -> Interval<T> {
+    match sense {
+        Sense::Le => (Some(T::zero()), None),
+        Sense::Ge => (None, Some(T::zero())),
+        Sense::Eq => (Some(T::zero()), Some(T::zero())),
+    }
+}
+
+/// Where a non-basic variable starts: its lower bound, its upper one or zero
+pub(super) fn at_bound<T: Number>(lower: &Option<T>, upper: &Option<T>) -> T {
+    match (lower, upper) {
+        (Some(lower), _) => lower.clone(),
+        (None, Some(upper)) => upper.clone(),
+        (None, None) => T::zero(),
+    }
+}
+
+/// The revised simplex method for the problem of `bounded_simplex`, where the constraint
+/// matrix is sparse. The results are those of `bounded_simplex`, except that the solution
+/// has no table (it is empty), so `sensitivity::ranging` has nothing to read the ranges from.
+/// Its `basis` lists the basic columns as `x` followed by `slack`, an artificial
+/// variable left in the basis stands for the slack variable of its row.
+pub fn revised_simplex<T: Number>(
+    constr: SparseMatrix<T>,
+    senses: Vec<Sense>,
+    req: Vec<T>,
+    obj: Vec<T>,
+    bounds: &[Interval<T>],
+    options: &Options<T>,
+) -> Solution<T> {
+    assert_eq!(constr.ncols(), obj.len(), "One coefficient per variable");
+    options.observer.initialized(&Initialization {
+        method: "revised simplex",
+        rows: constr.nrows(),
+        columns: constr.ncols(),
+    });
+    let mut revised = Revised::new(constr, senses, req, bounds);
+    let mut iterations = 0;
+    let outcome = revised.optimize(&obj, options, &mut iterations);
+    let solution = revised.solution(outcome, &obj);
+    options.observer.terminated(&Termination {
+        status: solution.status,
+        objective: solution.objective.clone(),
+        iterations,
+    });
+    solution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::table_problems;
+    use super::super::{bounded_simplex, simplex_method, two_phase_simplex, Rational, Silent};
+    use super::*;
+
+    /// Solves the same problem with the table and the revised method
+    fn both(
+        constraints: na::DMatrix<f64>,
+        senses: Vec<Sense>,
+        req: Vec<f64>,
+        obj: Vec<f64>,
+    ) -> (Solution<f64>, Solution<f64>) {
+        let bounds = vec![(Some(0.0), None); obj.len()];
+        let options = Options {
+            refactor_every: 2,
+            ..Options::default()
+        };
+        let revised = revised_simplex(
+            SparseMatrix::from_dense(&constraints),
+            senses.clone(),
+            req.clone(),
+            obj.clone(),
+            &bounds,
+            &options,
+        );
+        let table = two_phase_simplex(constraints, senses, req, obj, &Options::default());
+        (table, revised)
+    }
+
+    #[test]
+    fn revised_test_problems() {
+        for (constraints, req, obj) in table_problems() {
+            let rows = constraints.nrows();
+            let expected = simplex_method(constraints.clone(), req.clone(), obj.clone(), &Silent);
+            let senses = vec![Sense::Le; rows];
+            let (_, revised) = both(constraints, senses, req, obj);
+            assert_eq!(revised.status, Status::Optimal);
+            assert!(
+                (revised.objective - expected.objective).abs() < 1E-6,
+                "The expected optimal value: {}, the value we got: {}",
+                expected.objective,
+                revised.objective
+            );
+            for (a, b) in revised.duals.iter().zip(&expected.duals) {
+                assert!(
+                    (a - b).abs() < 1E-6,
+                    "{:?} {:?}",
+                    revised.duals,
+                    expected.duals
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn revised_test_two_phase() {
+        /*
+        Objective function = -2x - 3y - z
+        Constraints:
+        x + y + z >= 4
+        x + 3y >= 6
+        x - z = -1
+        x, y, z >= 0
+        */
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(3, 3, &[
+            1.0, 1.0, 1.0,
+            1.0, 3.0, 0.0,
+            1.0, 0.0, -1.0,
+        ]);
+        let senses = vec![Sense::Ge, Sense::Ge, Sense::Eq];
+        let req = vec![4.0, 6.0, -1.0];
+        let (table, revised) = both(constraints, senses, req, vec![-2.0, -3.0, -1.0]);
+        assert_eq!(revised.status, Status::Optimal);
+        assert!((revised.objective - table.objective).abs() < 1E-9);
+        for (a, b) in revised.x.iter().zip(&table.x) {
+            assert!((a - b).abs() < 1E-9, "{:?} {:?}", revised.x, table.x);
+        }
+        for (a, b) in revised.slack.iter().zip(&table.slack) {
+            assert!(
+                (a - b).abs() < 1E-9,
+                "{:?} {:?}",
+                revised.slack,
+                table.slack
+            );
+        }
+        for (a, b) in revised.reduced_costs.iter().zip(&table.reduced_costs) {
+            assert!((a - b).abs() < 1E-9);
+        }
+        assert!(revised.table.is_empty());
+    }
+
+    #[test]
+    fn revised_test_infeasible_and_unbounded() {
+        let constraints = na::DMatrix::from_row_slice(2, 2, &[1.0, 1.0, 1.0, 1.0]);
+        let senses = vec![Sense::Le, Sense::Ge];
+        let (_, revised) = both(constraints, senses, vec![1.0, 2.0], vec![1.0, 1.0]);
+        assert_eq!(revised.status, Status::Infeasible);
+
+        let constraints = na::DMatrix::from_row_slice(1, 2, &[1.0, -1.0]);
+        let (table, revised) = both(constraints, vec![Sense::Le], vec![1.0], vec![1.0, 0.0]);
+        assert_eq!(table.status, Status::Unbounded);
+        assert_eq!(revised.status, Status::Unbounded);
+        // Both x and y grow along the ray, keeping x - y <= 1
+        let ray = revised.ray.unwrap();
+        assert!(ray[0] > 0.0 && (ray[0] - ray[1]).abs() < 1E-9, "{:?}", ray);
+    }
+
+    #[test]
+    fn revised_test_bounds() {
+        /*
+        Objective function = 3x + 2y - z
+        Constraints:
+        x + y - z <= 3
+        -1 <= x <= 1, 0 <= y <= 5, z free
+        */
+        let constraints =
+            SparseMatrix::from_triplets(1, 3, &[(0, 0, 1.0), (0, 1, 1.0), (0, 2, -1.0)]);
+        let bounds = vec![
+            (Some(-1.0), Some(1.0)),
+            (Some(0.0), Some(5.0)),
+            (None, None),
+        ];
+        let obj: Vec<f64> = vec![3.0, 2.0, -1.0];
+        let revised = revised_simplex(
+            constraints.clone(),
+            vec![Sense::Le],
+            vec![3.0],
+            obj.clone(),
+            &bounds,
+            &Options::default(),
+        );
+        let table = bounded_simplex(
+            constraints.to_dense(),
+            vec![Sense::Le],
+            vec![3.0],
+            obj,
+            &bounds,
+            &Options::default(),
+        );
+        assert_eq!(revised.status, Status::Optimal);
+        assert!(
+            (revised.objective - 10.0).abs() < 1E-9,
+            "{}",
+            revised.objective
+        );
+        assert!((revised.objective - table.objective).abs() < 1E-9);
+        assert!((revised.duals[0] - table.duals[0]).abs() < 1E-9);
+    }
+
+    #[test]
+    fn revised_test_sparse_transport() {
+        // Ship from 20 plants to 30 stores at the least cost, 60 out of 650 entries are non-zero
+        let (plants, stores) = (20, 30);
+        let mut triplets = Vec::new();
+        let mut obj = Vec::new();
+        for p in 0..plants {
+            for s in 0..stores {
+                let j = p * stores + s;
+                triplets.push((p, j, 1.0));
+                triplets.push((plants + s, j, 1.0));
+                obj.push(-(((p * 7 + s * 13) % 17 + 1) as f64));
+            }
+        }
+        let supply = vec![45.0; plants];
+        let demand: Vec<f64> = (0..stores).map(|s| (20 + s % 10) as f64).collect();
+        let mut senses = vec![Sense::Le; plants];
+        senses.extend(vec![Sense::Ge; stores]);
+        let req: Vec<f64> = supply.into_iter().chain(demand).collect();
+        let constraints = SparseMatrix::from_triplets(plants + stores, plants * stores, &triplets);
+        assert_eq!(constraints.nnz(), 2 * plants * stores);
+
+        let bounds = vec![(Some(0.0), None); obj.len()];
+        let revised = revised_simplex(
+            constraints.clone(),
+            senses.clone(),
+            req.clone(),
+            obj.clone(),
+            &bounds,
+            &Options::default(),
+        );
+        let table = two_phase_simplex(
+            constraints.to_dense(),
+            senses,
+            req,
+            obj,
+            &Options::default(),
+        );
+        assert_eq!(revised.status, Status::Optimal);
+        assert!(
+            (revised.objective - table.objective).abs() < 1E-6,
+            "{} {}",
+            revised.objective,
+            table.objective
+        );
+    }
+
+    #[test]
+    fn revised_test_rational() {
+        let r = |n: i64| Rational::from_integer(n);
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(3, 3, &[
+            r(10), r(15), r(10),
+            r(13), r(5), r(5),
+            r(20), r(5), r(10),
+        ]);
+        let res = revised_simplex(
+            SparseMatrix::from_dense(&constraints),
+            vec![Sense::Le; 3],
+            vec![r(720), r(680), r(550)],
+            vec![r(20), r(45), r(85)],
+            &[(Some(r(0)), None); 3],
+            &Options::default(),
+        );
+        assert_eq!(res.status, Status::Optimal);
+        let expected = simplex_method(
+            constraints,
+            vec![r(720), r(680), r(550)],
+            vec![r(20), r(45), r(85)],
+            &Silent,
+        );
+        assert_eq!(res.objective, expected.objective);
+    }
+
+    #[test]
+    fn revised_test_singular_basis() {
+        // The second row is twice the first one, Phase I leaves an artificial variable behind
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(3, 2, &[
+            1.0, 1.0,
+            2.0, 2.0,
+            1.0, 0.0,
+        ]);
+        let senses = vec![Sense::Eq, Sense::Eq, Sense::Le];
+        let (table, revised) = both(
+            constraints.clone(),
+            senses.clone(),
+            vec![2.0, 4.0, 1.5],
+            vec![1.0, 2.0],
+        );
+        assert_eq!(revised.status, Status::Optimal);
+        assert!((revised.objective - table.objective).abs() < 1E-9);
+        assert!(
+            revised.basis.iter().all(|&j| j < 2 + 3),
+            "{:?}",
+            revised.basis
+        );
+
+        // The artificial column of the last row is zero, as the row doesn't need it,
+        // so the logical column of the row the others leave uncovered takes its place
+        let bounds = vec![(Some(0.0), None); 2];
+        let mut state = Revised::new(
+            SparseMatrix::from_dense(&constraints),
+            senses,
+            vec![2.0, 4.0, 1.5],
+            &bounds,
+        );
+        state.basis = vec![0, 1, 5 + 2];
+        state.refactor();
+        assert_eq!(state.basis, vec![0, 1, 2]);
+        for i in 0..3 {
+            let row: f64 = (0..state.ncols())
+                .map(|j| state.dense_column(j)[i] * state.x[j])
+                .sum();
+            assert!((row - state.req[i]).abs() < 1E-9);
+        }
+    }
+}
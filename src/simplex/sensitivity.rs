@@ -0,0 +1,235 @@
+/// Sensitivity analysis of an optimal simplex table: how far a requirement
+/// or a coefficient of the objective function can move before the optimal basis changes.
+/// [https://en.wikipedia.org/wiki/Sensitivity_analysis]
+use super::{Number, Sense, Solution, Status};
+
+/// An interval of values (lowest, highest), `None` stands for no limit on that side
+pub type Interval<T> = (Option<T>, Option<T>);
+
+/// The intervals over which the optimal basis stays optimal.
+/// Within a requirement's interval the shadow prices hold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ranging<T: Number> {
+    /// One interval per requirement
+    pub rhs: Vec<Interval<T>>,
+    /// One interval per coefficient of the objective function
+    pub objective: Vec<Interval<T>>,
+}
+
+/// Computes the ranges of the requirements and of the objective function coefficients
+/// for an optimal solution of `two_phase_simplex` (or `bounded_simplex`)
+/// called with the same arguments.
+/// `None` if the solution isn't optimal or has no table, as those of `revised_simplex`
+/// and `interior_point`.
+pub fn ranging<T: Number>(
+    solution: &Solution<T>,
+    senses: &[Sense],
+    req: &[T],
+    obj: &[T],
+) -> Option<Ranging<T>> {
+    let table = &solution.table;
+    if solution.status != Status::Optimal || table.is_empty() {
+        return None;
+    }
+    let layout = &solution.layout;
+    let n_vars = obj.len();
+    let last_col = table.ncols() - 1;
+    let basic_rows = || {
+        (1..table.nrows())
+            .map(move |r| (r, solution.basis[r - 1] + 1))
+            .filter(|&(_, b)| !layout.is_free(b))
+    };
+
+    // The basic variables must stay within their bounds: 0 <= x_B + delta * f * column <= upper
+    let rhs = (0..senses.len())
+        .map(|i| {
+            let col = n_vars + 1 + i;
+            let factor = &layout.factors()[i];
+            let delta = non_negative(basic_rows().flat_map(|(r, b)| {
+                let value = table[(r, last_col)].clone();
+                let rate = factor.clone() * &table[(r, col)];
+                let room = layout
+                    .upper(b)
+                    .map(|upper| (upper.clone() - &value, -rate.clone()));
+                std::iter::once((value, rate)).chain(room)
+            }));
+            shift(delta, &req[i])
+        })
+        .collect();
+
+    // The slack variables of `=` constraints never enter, so they don't restrict anything
+    let can_enter = |k: usize| k <= n_vars || senses[k - n_vars - 1] != Sense::Eq;
+    // A non-basic free variable would enter from either side, so it restricts both
+    let limits = |k: usize, coef: T, rate: T| {
+        let mirrored = if layout.is_free(k) {
+            Some((-coef.clone(), -rate.clone()))
+        } else {
+            None
+        };
+        std::iter::once((coef, rate)).chain(mirrored)
+    };
+    let objective = (1..=n_vars)
+        .map(|j| match solution.basis.iter().position(|&b| b + 1 == j) {
+            // A non-basic variable stays out until its coefficient in row(0) turns negative,
+            // which changes by -delta per unit of the column (mirrored ones grow the other way)
+            None => {
+                let delta = non_negative(limits(j, table[(0, j)].clone(), -layout.sign(j)));
+                shift(delta, &obj[j - 1])
+            }
+            // The coefficient of a basic variable moves all of row(0) along its row
+            Some(r) => {
+                let sign = layout.sign(j);
+                let delta = non_negative(
+                    (1..last_col)
+                        .filter(|&k| k != j && can_enter(k) && !solution.basis.contains(&(k - 1)))
+                        .flat_map(|k| {
+                            limits(k, table[(0, k)].clone(), sign.clone() * &table[(r + 1, k)])
+                        }),
+                );
+                shift(delta, &obj[j - 1])
+            }
+        })
+        .collect();
+
+    Some(Ranging { rhs, objective })
+}
+
+/// The interval of delta keeping every `value + delta * rate` non-negative,
+/// given the pairs of (value, rate)
+fn non_negative<T: Number>(pairs: impl Iterator<Item = (T, T)>) -> Interval<T> {
+    let mut range: Interval<T> = (None, None);
+    for (value, rate) in pairs {
+        if rate > T::eps() {
+            let limit = -value / rate;
+            if range.0.as_ref().is_none_or(|low| limit > *low) {
+                range.0 = Some(limit);
+            }
+        } else if rate < -T::eps() {
+            let limit = -value / rate;
+            if range.1.as_ref().is_none_or(|high| limit < *high) {
+                range.1 = Some(limit);
+            }
+        }
+    }
+    range
+}
+
+/// Moves both ends of the interval by the given number
+pub fn shift<T: Number>(range: Interval<T>, by: &T) -> Interval<T> {
+    (range.0.map(|low| low + by), range.1.map(|high| high + by))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{two_phase_simplex, Options};
+    use super::*;
+    use nalgebra as na;
+
+    #[test]
+    fn sensitivity_test_ranging() {
+        /*
+        Objective function = 7x + 5y
+        Constraints:
+        2x + 3y <= 90
+        3x + 2y <= 120
+        x, y >= 0
+        */
+        let obj_f: Vec<f64> = vec![7.0, 5.0];
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(2, 2, &[
+            2.0, 3.0,
+            3.0, 2.0,
+        ]);
+        let req = vec![90.0, 120.0];
+        let senses = vec![Sense::Le; 2];
+        let res = two_phase_simplex(
+            constraints,
+            senses.clone(),
+            req.clone(),
+            obj_f.clone(),
+            &Options::default(),
+        );
+        let ranges = ranging(&res, &senses, &req, &obj_f).unwrap();
+
+        let close = |a: &Interval<f64>, b: (f64, f64)| match a {
+            (Some(low), Some(high)) => (low - b.0).abs() < 1E-6 && (high - b.1).abs() < 1E-6,
+            _ => false,
+        };
+        // Both x and y stay non-negative for 80 <= b1 <= 180 and 60 <= b2 <= 135
+        assert!(close(&ranges.rhs[0], (80.0, 180.0)), "{:?}", ranges.rhs);
+        assert!(close(&ranges.rhs[1], (60.0, 135.0)), "{:?}", ranges.rhs);
+        // The vertex stays optimal while 2/3 <= c_x / c_y <= 3/2
+        assert!(
+            close(&ranges.objective[0], (10.0 / 3.0, 7.5)),
+            "{:?}",
+            ranges.objective
+        );
+        assert!(
+            close(&ranges.objective[1], (14.0 / 3.0, 10.5)),
+            "{:?}",
+            ranges.objective
+        );
+    }
+
+    #[test]
+    fn sensitivity_test_ge_and_non_basic() {
+        /*
+        Objective function = -2x - 3y - z
+        Constraints:
+        x + y + z >= 4
+        x + 3y >= 6
+        x, y, z >= 0
+        */
+        let obj_f: Vec<f64> = vec![-2.0, -3.0, -1.0];
+        #[rustfmt::skip]
+        let constraints = na::DMatrix::from_row_slice(2, 3, &[
+            1.0, 1.0, 1.0,
+            1.0, 3.0, 0.0,
+        ]);
+        let req = vec![4.0, 6.0];
+        let senses = vec![Sense::Ge; 2];
+        let res = two_phase_simplex(
+            constraints,
+            senses.clone(),
+            req.clone(),
+            obj_f.clone(),
+            &Options::default(),
+        );
+        assert_eq!(res.status, Status::Optimal);
+        // y = 2 covers x + 3y >= 6 and z = 2 does the rest
+        assert!((res.objective + 8.0).abs() < 1E-4, "{}", res.objective);
+        assert!((res.duals[0] + 1.0).abs() < 1E-4 && (res.duals[1] + 2.0 / 3.0).abs() < 1E-4);
+        // x would cost 2 - 1 - 2/3 more than it brings
+        assert!((res.reduced_costs[0] + 1.0 / 3.0).abs() < 1E-4);
+
+        let ranges = ranging(&res, &senses, &req, &obj_f).unwrap();
+        assert_eq!(ranges.objective[0].0, None);
+        assert!((ranges.objective[0].1.unwrap() + 5.0 / 3.0).abs() < 1E-4);
+        // z stays basic while 4 >= 2 and y while 6 >= 0 and 6 <= 12
+        assert!((ranges.rhs[0].0.unwrap() - 2.0).abs() < 1E-4 && ranges.rhs[0].1.is_none());
+        assert!(ranges.rhs[1].0.unwrap().abs() < 1E-4);
+        assert!((ranges.rhs[1].1.unwrap() - 12.0).abs() < 1E-4);
+    }
+
+    #[test]
+    fn sensitivity_test_infeasible() {
+        /*
+        Constraints:
+        x + y <= 1
+        x + y >= 2
+        */
+        let obj_f: Vec<f64> = vec![1.0, 1.0];
+        let constraints = na::DMatrix::from_row_slice(2, 2, &[1.0; 4]);
+        let req = vec![1.0, 2.0];
+        let senses = vec![Sense::Le, Sense::Ge];
+        let res = two_phase_simplex(
+            constraints,
+            senses.clone(),
+            req.clone(),
+            obj_f.clone(),
+            &Options::default(),
+        );
+        assert_eq!(res.status, Status::Infeasible);
+        assert_eq!(ranging(&res, &senses, &req, &obj_f), None);
+    }
+}
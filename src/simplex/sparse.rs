@@ -0,0 +1,376 @@
+/// Sparse matrices stored by columns and the LU factorization of a simplex basis.
+/// The factorization picks its pivots in the spirit of Markowitz: the sparsest column first,
+/// within it a large enough entry of the sparsest row. Pivots of the simplex method
+/// are kept as eta columns (the product form of the inverse) until the next refactorization.
+/// [https://en.wikipedia.org/wiki/Sparse_matrix#Compressed_sparse_column_(CSC_or_CCS)]
+/// [https://en.wikipedia.org/wiki/Revised_simplex_method]
+use super::{num, Number};
+use nalgebra as na;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A matrix keeping only its non-zero entries, column after column
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseMatrix<T: Number> {
+    nrows: usize,
+    ncols: usize,
+    /// Where each column starts in `rows` and `values`, followed by the number of entries
+    col_starts: Vec<usize>,
+    rows: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T: Number> SparseMatrix<T> {
+    /// Builds the matrix out of (row, column, value) triplets.
+    /// The values of repeated positions are added up, zeros are left out.
+    pub fn from_triplets(nrows: usize, ncols: usize, triplets: &[(usize, usize, T)]) -> Self {
+        let mut columns: Vec<BTreeMap<usize, T>> = vec![BTreeMap::new(); ncols];
+        for (i, j, value) in triplets {
+            assert!(
+                *i < nrows && *j < ncols,
+                "({}, {}) is out of the matrix",
+                i,
+                j
+            );
+            *columns[*j].entry(*i).or_insert_with(T::zero) += value;
+        }
+        let mut matrix = SparseMatrix {
+            nrows,
+            ncols,
+            col_starts: vec![0],
+            rows: Vec::new(),
+            values: Vec::new(),
+        };
+        for column in columns {
+            for (i, value) in column.into_iter().filter(|(_, v)| !v.is_zero()) {
+                matrix.rows.push(i);
+                matrix.values.push(value);
+            }
+            matrix.col_starts.push(matrix.rows.len());
+        }
+        matrix
+    }
+
+    /// Keeps the non-zero entries of the dense matrix
+    pub fn from_dense(dense: &na::DMatrix<T>) -> Self {
+        let mut triplets = Vec::new();
+        for j in 0..dense.ncols() {
+            for i in 0..dense.nrows() {
+                if !dense[(i, j)].is_zero() {
+                    triplets.push((i, j, dense[(i, j)].clone()));
+                }
+            }
+        }
+        Self::from_triplets(dense.nrows(), dense.ncols(), &triplets)
+    }
+
+    /// Adds a row made of the given (column, value) pairs at the bottom
+    pub fn push_row(&mut self, entries: &[(usize, T)]) {
+        let row = self.nrows;
+        let mut triplets: Vec<(usize, usize, T)> = (0..self.ncols)
+            .flat_map(|j| self.column(j).map(move |(i, v)| (i, j, v.clone())))
+            .collect();
+        triplets.extend(entries.iter().map(|(j, v)| (row, *j, v.clone())));
+        *self = Self::from_triplets(self.nrows + 1, self.ncols, &triplets);
+    }
+
+    pub fn to_dense(&self) -> na::DMatrix<T> {
+        let mut dense = na::DMatrix::zeros(self.nrows, self.ncols);
+        for j in 0..self.ncols {
+            for (i, value) in self.column(j) {
+                dense[(i, j)] = value.clone();
+            }
+        }
+        dense
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// The number of the stored (non-zero) entries
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The (row, value) pairs of the non-zero entries of the column
+    pub fn column(&self, j: usize) -> impl Iterator<Item = (usize, &T)> + '_ {
+        let range = self.col_starts[j]..self.col_starts[j + 1];
+        self.rows[range.clone()]
+            .iter()
+            .copied()
+            .zip(&self.values[range])
+    }
+
+    /// The product of the column with the given (dense) vector
+    pub fn dot_column(&self, j: usize, y: &[T]) -> T {
+        self.column(j)
+            .fold(T::zero(), |acc, (i, value)| acc + value.clone() * &y[i])
+    }
+}
+
+/// An elimination step: the pivot and the entries left in its row and column
+#[derive(Debug, Clone)]
+struct Elimination<T> {
+    row: usize,
+    col: usize,
+    pivot: T,
+    /// The multipliers of the rows below the pivot
+    lower: Vec<(usize, T)>,
+    /// The rest of the pivot row, by column
+    upper: Vec<(usize, T)>,
+}
+
+/// A pivot made since the factorization
+#[derive(Debug, Clone)]
+struct Eta<T> {
+    /// The basis position the entering column replaced
+    position: usize,
+    /// The non-zeros of the entering column in terms of the old basis, but the pivot
+    column: Vec<(usize, T)>,
+    pivot: T,
+}
+
+/// B = (L U) E_1^-1 ... E_k^-1, where L U is the last factorization of the basis
+/// and E are the eta matrices of the pivots made since then.
+/// Solves B x = b (`ftran`) and B^T y = c (`btran`) without forming an inverse.
+#[derive(Debug, Clone)]
+pub struct SparseLu<T: Number> {
+    size: usize,
+    steps: Vec<Elimination<T>>,
+    etas: Vec<Eta<T>>,
+}
+
+impl<T: Number> SparseLu<T> {
+    /// Factorizes the square matrix made of the given sparse columns.
+    /// Returns `None` if the matrix is singular.
+    pub fn factorize(columns: &[Vec<(usize, T)>]) -> Option<Self> {
+        let size = columns.len();
+        let mut active: Vec<BTreeMap<usize, T>> = columns
+            .iter()
+            .map(|c| c.iter().filter(|(_, v)| !v.is_zero()).cloned().collect())
+            .collect();
+        let mut row_cols: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); size];
+        for (j, column) in active.iter().enumerate() {
+            for &i in column.keys() {
+                row_cols[i].insert(j);
+            }
+        }
+        let mut remaining: BTreeSet<usize> = (0..size).collect();
+        let mut steps = Vec::with_capacity(size);
+
+        while let Some(col) = remaining.iter().copied().min_by_key(|&j| active[j].len()) {
+            // A large enough entry of the sparsest row keeps both the fill-in and the errors small
+            let largest = active[col]
+                .values()
+                .map(|v| v.abs())
+                .fold(T::zero(), |acc, v| if v > acc { v } else { acc });
+            if largest <= T::eps() {
+                return None;
+            }
+            let threshold = largest * num::<T>(0.1);
+            let row = active[col]
+                .iter()
+                .filter(|(_, v)| v.abs() >= threshold)
+                .min_by_key(|(&i, _)| row_cols[i].len())
+                .map(|(&i, _)| i)
+                .expect("The largest entry passes the threshold");
+            remaining.remove(&col);
+
+            let pivot = active[col][&row].clone();
+            let lower: Vec<(usize, T)> = (active[col].iter())
+                .filter(|(&i, _)| i != row)
+                .map(|(&i, v)| (i, v.clone() / &pivot))
+                .collect();
+            let upper: Vec<(usize, T)> = (row_cols[row].iter())
+                .filter(|&&j| j != col)
+                .map(|&j| (j, active[j][&row].clone()))
+                .collect();
+            for &i in active[col].keys() {
+                row_cols[i].remove(&col);
+            }
+            for (j, a_rj) in &upper {
+                active[*j].remove(&row);
+                for (i, l) in &lower {
+                    let entry = active[*j].entry(*i).or_insert_with(T::zero);
+                    *entry -= l.clone() * a_rj;
+                    if entry.is_zero() {
+                        active[*j].remove(i);
+                        row_cols[*i].remove(j);
+                    } else {
+                        row_cols[*i].insert(*j);
+                    }
+                }
+            }
+            row_cols[row].clear();
+            steps.push(Elimination {
+                row,
+                col,
+                pivot,
+                lower,
+                upper,
+            });
+        }
+        Some(SparseLu {
+            size,
+            steps,
+            etas: Vec::new(),
+        })
+    }
+
+    /// The number of pivots applied since the factorization
+    pub fn updates(&self) -> usize {
+        self.etas.len()
+    }
+
+    /// Replaces the column at the given position, `entering` is the new column
+    /// in terms of the current basis, i.e. the result of `ftran`
+    pub fn update(&mut self, position: usize, entering: &[T]) {
+        let pivot = entering[position].clone();
+        assert!(!pivot.is_zero(), "The entering column can't replace a zero");
+        let column = (entering.iter().enumerate())
+            .filter(|&(i, v)| i != position && !v.is_zero())
+            .map(|(i, v)| (i, v.clone()))
+            .collect();
+        self.etas.push(Eta {
+            position,
+            column,
+            pivot,
+        });
+    }
+
+    /// Solves B x = b, `b` is indexed by rows and `x` by the basis positions
+    pub fn ftran(&self, mut b: Vec<T>) -> Vec<T> {
+        assert_eq!(b.len(), self.size);
+        for step in &self.steps {
+            let value = b[step.row].clone();
+            if !value.is_zero() {
+                for (i, l) in &step.lower {
+                    b[*i] -= l.clone() * &value;
+                }
+            }
+        }
+        let mut x = vec![T::zero(); self.size];
+        for step in self.steps.iter().rev() {
+            let rest = (step.upper.iter())
+                .fold(b[step.row].clone(), |acc, (j, u)| acc - u.clone() * &x[*j]);
+            x[step.col] = rest / &step.pivot;
+        }
+        for eta in &self.etas {
+            let value = x[eta.position].clone() / &eta.pivot;
+            if !value.is_zero() {
+                for (i, a) in &eta.column {
+                    x[*i] -= a.clone() * &value;
+                }
+            }
+            x[eta.position] = value;
+        }
+        x
+    }
+
+    /// Solves B^T y = c, `c` is indexed by the basis positions and `y` by rows
+    pub fn btran(&self, mut c: Vec<T>) -> Vec<T> {
+        assert_eq!(c.len(), self.size);
+        for eta in self.etas.iter().rev() {
+            let rest = (eta.column.iter()).fold(c[eta.position].clone(), |acc, (i, a)| {
+                acc - a.clone() * &c[*i]
+            });
+            c[eta.position] = rest / &eta.pivot;
+        }
+        let mut y = vec![T::zero(); self.size];
+        for step in &self.steps {
+            let value = c[step.col].clone() / &step.pivot;
+            for (j, u) in &step.upper {
+                c[*j] -= u.clone() * &value;
+            }
+            y[step.row] = value;
+        }
+        for step in self.steps.iter().rev() {
+            let rest = (step.lower.iter())
+                .fold(y[step.row].clone(), |acc, (i, l)| acc - l.clone() * &y[*i]);
+            y[step.row] = rest;
+        }
+        y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Rational;
+    use super::*;
+
+    #[test]
+    fn sparse_test_matrix() {
+        let triplets = vec![
+            (0, 0, 1.0),
+            (2, 0, 2.0),
+            (1, 1, 3.0),
+            (2, 0, 1.0),
+            (0, 2, 0.0),
+        ];
+        let matrix = SparseMatrix::<f64>::from_triplets(3, 3, &triplets);
+        assert_eq!(matrix.nnz(), 3);
+        let column: Vec<_> = matrix.column(0).collect();
+        assert_eq!(column, vec![(0, &1.0), (2, &3.0)]);
+        assert_eq!(matrix.column(2).count(), 0);
+        assert_eq!(matrix.dot_column(0, &[1.0, 5.0, 2.0]), 7.0);
+        assert_eq!(SparseMatrix::from_dense(&matrix.to_dense()), matrix);
+
+        let mut longer = matrix.clone();
+        longer.push_row(&[(2, 4.0), (0, -1.0)]);
+        assert_eq!((longer.nrows(), longer.nnz()), (4, 5));
+        assert_eq!(longer.column(0).last(), Some((3, &-1.0)));
+        assert_eq!(longer.to_dense().remove_row(3), matrix.to_dense());
+    }
+
+    #[test]
+    fn sparse_test_lu_solves() {
+        #[rustfmt::skip]
+        let dense = na::DMatrix::<f64>::from_row_slice(4, 4, &[
+            0.0, 2.0, 0.0, 1.0,
+            4.0, 0.0, 0.0, 0.0,
+            1.0, 1.0, 3.0, 0.0,
+            0.0, 0.0, 1.0, 5.0,
+        ]);
+        let sparse = SparseMatrix::from_dense(&dense);
+        let columns: Vec<Vec<_>> = (0..4)
+            .map(|j| sparse.column(j).map(|(i, v)| (i, *v)).collect())
+            .collect();
+        let mut lu = SparseLu::factorize(&columns).unwrap();
+        let b = vec![1.0, 2.0, 3.0, 4.0];
+        let x = lu.ftran(b.clone());
+        let y = lu.btran(b.clone());
+        let close = |a: na::DVector<f64>, b: &[f64]| (a - na::DVector::from_column_slice(b)).norm();
+        assert!(close(&dense * na::DVector::from_vec(x), &b) < 1E-12);
+        assert!(close(dense.transpose() * na::DVector::from_vec(y), &b) < 1E-12);
+
+        // The second column gives way to (1, 1, 1, 1)
+        let mut updated = dense.clone();
+        updated.set_column(1, &na::DVector::from_element(4, 1.0));
+        let entering = lu.ftran(vec![1.0; 4]);
+        lu.update(1, &entering);
+        assert_eq!(lu.updates(), 1);
+        let x = lu.ftran(b.clone());
+        let y = lu.btran(b.clone());
+        assert!(close(&updated * na::DVector::from_vec(x), &b) < 1E-12);
+        assert!(close(updated.transpose() * na::DVector::from_vec(y), &b) < 1E-12);
+    }
+
+    #[test]
+    fn sparse_test_lu_singular_and_exact() {
+        let singular = vec![vec![(0, 1.0), (1, 2.0)], vec![(0, 2.0), (1, 4.0)]];
+        assert!(SparseLu::<f64>::factorize(&singular).is_none());
+
+        let r = |n: i64| Rational::from_integer(n);
+        let columns = vec![vec![(0, r(3)), (1, r(1))], vec![(0, r(1)), (1, r(2))]];
+        let lu = SparseLu::factorize(&columns).unwrap();
+        // 3x + y = 1, x + 2y = 0
+        assert_eq!(
+            lu.ftran(vec![r(1), r(0)]),
+            vec![Rational::new(2, 5), Rational::new(-1, 5)]
+        );
+    }
+}
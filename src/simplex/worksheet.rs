@@ -0,0 +1,493 @@
+/// Worked solutions for teaching: every table of the simplex method rendered
+/// as a LaTeX `tabular` or a Markdown table, with the basic variable of each row
+/// on its left, the variables on top and the pivot element highlighted.
+use super::{
+    simplex_method, Initialization, Number, Observer, Phase, Solution, Status, Tableau, Termination,
+};
+use nalgebra as na;
+use std::cell::RefCell;
+use std::fmt::Write;
+
+/// The markup to render the tables in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// A standalone document, the pivot element is `\boxed`
+    Latex,
+    /// The pivot element is in bold
+    Markdown,
+}
+
+/// A table of the simplex method kept by a `Worksheet`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot<T: Number> {
+    pub iteration: usize,
+    pub phase: Phase,
+    pub table: na::DMatrix<T>,
+    pub basis: Vec<usize>,
+    pub entering: Option<usize>,
+    pub leaving: Option<usize>,
+}
+
+/// Keeps the tables of a run of the (table) simplex method and renders them step by step.
+/// The columns hold the decision variables, one slack (or surplus) variable `s` per
+/// constraint and the artificial variables `a` of Phase I.
+/// A variable with bounds other than `x >= 0` stands for its shifted value, e.g. x - lower.
+#[derive(Debug)]
+pub struct Worksheet<T: Number> {
+    names: Vec<String>,
+    shape: RefCell<(usize, usize)>,
+    snapshots: RefCell<Vec<Snapshot<T>>>,
+    termination: RefCell<Option<Termination<T>>>,
+}
+
+impl<T: Number> Default for Worksheet<T> {
+    fn default() -> Self {
+        Worksheet {
+            names: Vec::new(),
+            shape: RefCell::new((0, 0)),
+            snapshots: RefCell::new(Vec::new()),
+            termination: RefCell::new(None),
+        }
+    }
+}
+
+impl<T: Number> Worksheet<T> {
+    /// The decision variables are called `x1`, `x2`, ...
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The decision variables get the given names, written as they are in both formats
+    pub fn with_names(names: &[&str]) -> Self {
+        Worksheet {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            ..Self::new()
+        }
+    }
+
+    /// The tables so far
+    pub fn snapshots(&self) -> Vec<Snapshot<T>> {
+        self.snapshots.borrow().clone()
+    }
+
+    /// Every table followed by what happens to it, headed by the phase it belongs to,
+    /// and the outcome at the end
+    pub fn render(&self, format: Format) -> String {
+        let mut out = String::new();
+        let mut phase = None;
+        for (k, snapshot) in self.snapshots.borrow().iter().enumerate() {
+            if phase != Some(snapshot.phase) {
+                phase = Some(snapshot.phase);
+                let (title, text) = match snapshot.phase {
+                    Phase::One => (
+                        "Phase I",
+                        "Row z holds the negated sum of the artificial variables, \
+                         which has to reach zero for a feasible basis.",
+                    ),
+                    _ => ("Phase II", "Row z holds the objective function."),
+                };
+                out += &heading(format, 2, title);
+                writeln!(out, "{}\n", text).unwrap();
+            }
+            out += &heading(format, 3, &format!("Tableau {}", k + 1));
+            out += &self.table(snapshot, format);
+            let name = |col| self.name(col, format);
+            let text = match (snapshot.entering, snapshot.leaving) {
+                (Some(entering), Some(leaving)) => format!(
+                    "{} enters the basis and {} leaves it, the pivot element is {}.",
+                    name(entering),
+                    name(snapshot.basis[leaving - 1]),
+                    number(&snapshot.table[(leaving, entering)], format),
+                ),
+                (Some(entering), None) => format!(
+                    "{} moves from one bound to the other, the basis stays the same.",
+                    name(entering)
+                ),
+                _ if snapshot.phase == Phase::One => "Phase I is over.".to_string(),
+                _ => "No variable can improve the objective function any further.".to_string(),
+            };
+            writeln!(out, "{}\n", text).unwrap();
+        }
+        if let Some(end) = self.termination.borrow().as_ref() {
+            out += &heading(format, 2, "Outcome");
+            let text = match end.status {
+                Status::Optimal => format!(
+                    "The tableau is optimal after {} pivots: {} = {}.",
+                    end.iterations,
+                    self.name(0, format),
+                    number(&end.objective, format)
+                ),
+                Status::Unbounded => "The objective function is unbounded.".to_string(),
+                Status::Infeasible => {
+                    "Phase I leaves artificial variables above zero: there is no feasible solution."
+                        .to_string()
+                }
+                Status::IterationLimit => {
+                    format!("The iterations ran out after {} pivots.", end.iterations)
+                }
+            };
+            writeln!(out, "{}\n", text).unwrap();
+        }
+        out
+    }
+
+    /// The name of a column of the table
+    fn name(&self, col: usize, format: Format) -> String {
+        let (n_vars, n_constr) = *self.shape.borrow();
+        let (letter, index) = if col == 0 {
+            return match format {
+                Format::Latex => "$z$".to_string(),
+                Format::Markdown => "z".to_string(),
+            };
+        } else if col <= n_vars {
+            if let Some(name) = self.names.get(col - 1) {
+                return name.clone();
+            }
+            ("x", col)
+        } else if col <= n_vars + n_constr {
+            ("s", col - n_vars)
+        } else {
+            ("a", col - n_vars - n_constr)
+        };
+        match format {
+            Format::Latex => format!("${}_{{{}}}$", letter, index),
+            Format::Markdown => format!("{}{}", letter, index),
+        }
+    }
+
+    fn table(&self, snapshot: &Snapshot<T>, format: Format) -> String {
+        let table = &snapshot.table;
+        let last_col = table.ncols() - 1;
+        let pivot = snapshot.entering.zip(snapshot.leaving);
+        let mut header = vec!["Basic".to_string()];
+        header.extend((0..last_col).map(|j| self.name(j, format)));
+        header.push("RHS".to_string());
+        let rows = (0..table.nrows()).map(|i| {
+            let label = match i {
+                0 => self.name(0, format),
+                _ => self.name(snapshot.basis[i - 1], format),
+            };
+            let cells = (0..table.ncols()).map(|j| {
+                let cell = number(&table[(i, j)], format);
+                match format {
+                    _ if pivot != Some((j, i)) => cell,
+                    Format::Latex => format!("$\\boxed{{{}}}$", &cell[1..cell.len() - 1]),
+                    Format::Markdown => format!("**{}**", cell),
+                }
+            });
+            std::iter::once(label).chain(cells).collect::<Vec<_>>()
+        });
+        let mut out = String::new();
+        match format {
+            Format::Latex => {
+                let columns = format!("c|c|{}|c", "c".repeat(last_col - 1));
+                writeln!(out, "\\begin{{center}}\n\\begin{{tabular}}{{{}}}", columns).unwrap();
+                writeln!(out, "{} \\\\\n\\hline", header.join(" & ")).unwrap();
+                for (i, row) in rows.enumerate() {
+                    let rule = if i == 0 { "\n\\hline" } else { "" };
+                    writeln!(out, "{} \\\\{}", row.join(" & "), rule).unwrap();
+                }
+                writeln!(out, "\\end{{tabular}}\n\\end{{center}}\n").unwrap();
+            }
+            Format::Markdown => {
+                let rule: Vec<_> = (0..header.len())
+                    .map(|j| if j == 0 { ":---" } else { "---:" })
+                    .collect();
+                writeln!(out, "| {} |", header.join(" | ")).unwrap();
+                writeln!(out, "| {} |", rule.join(" | ")).unwrap();
+                for row in rows {
+                    writeln!(out, "| {} |", row.join(" | ")).unwrap();
+                }
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+impl<T: Number> Observer<T> for Worksheet<T> {
+    fn initialized(&self, event: &Initialization) {
+        *self.shape.borrow_mut() = (event.columns, event.rows);
+        self.snapshots.borrow_mut().clear();
+        *self.termination.borrow_mut() = None;
+    }
+    fn tabulated(&self, event: &Tableau<T>) {
+        self.snapshots.borrow_mut().push(Snapshot {
+            iteration: event.iteration,
+            phase: event.phase,
+            table: event.table.clone(),
+            basis: event.basis.to_vec(),
+            entering: event.entering,
+            leaving: event.leaving,
+        });
+    }
+    fn terminated(&self, event: &Termination<T>) {
+        *self.termination.borrow_mut() = Some(event.clone());
+    }
+}
+
+/// A complete worked solution of `simplex_method` (maximize obj * x subject to
+/// constr * x <= req, x >= 0): the problem, every table and the optimal point.
+/// The decision variables get the given names, or `x1`, `x2`, ... if there are none.
+pub fn worked_solution<T: Number>(
+    constr: na::DMatrix<T>,
+    req: Vec<T>,
+    obj: Vec<T>,
+    names: &[&str],
+    format: Format,
+) -> String {
+    let worksheet = Worksheet::with_names(names);
+    let problem = statement(&worksheet, &constr, &req, &obj, format);
+    let solution = simplex_method(constr, req, obj, &worksheet);
+    let mut out = String::new();
+    if format == Format::Latex {
+        out += "\\documentclass{article}\n\\usepackage{amsmath}\n\\begin{document}\n\n";
+    }
+    out += &heading(format, 1, "Worked solution");
+    out += &heading(format, 2, "Problem");
+    out += &problem;
+    out += &worksheet.render(format);
+    out += &point(&worksheet, &solution, format);
+    if format == Format::Latex {
+        out += "\\end{document}\n";
+    }
+    out
+}
+
+/// Maximize the objective function subject to the constraints, one per line
+fn statement<T: Number>(
+    worksheet: &Worksheet<T>,
+    constr: &na::DMatrix<T>,
+    req: &[T],
+    obj: &[T],
+    format: Format,
+) -> String {
+    *worksheet.shape.borrow_mut() = (obj.len(), req.len());
+    let name = |j: usize| worksheet.name(j + 1, format);
+    let variables: Vec<_> = (0..obj.len()).map(name).collect();
+    let mut lines = vec![format!("Maximize z = {}", linear(obj, &name, format))];
+    lines.extend((constr.row_iter().zip(req)).map(|(row, b)| {
+        let row: Vec<T> = row.iter().cloned().collect();
+        format!("{} <= {}", linear(&row, &name, format), number(b, format))
+    }));
+    lines.push(format!("{} >= 0", variables.join(", ")));
+    let mut out = String::new();
+    match format {
+        Format::Latex => {
+            // The whole statement is set in math mode, the names drop their dollars
+            let lines: Vec<_> = (lines.iter())
+                .map(|line| {
+                    (line.replace('$', ""))
+                        .replace("Maximize", "\\text{Maximize}\\quad")
+                        .replace("<=", "&\\le")
+                        .replace(">=", "&\\ge")
+                        .replace("z =", "z &=")
+                })
+                .collect();
+            writeln!(
+                out,
+                "\\begin{{align*}}\n{}\n\\end{{align*}}\n",
+                lines.join(" \\\\\n")
+            )
+            .unwrap();
+        }
+        Format::Markdown => {
+            writeln!(out, "```\n{}\n```\n", lines.join("\n")).unwrap();
+        }
+    }
+    out
+}
+
+/// The values of the decision variables once the tables are done
+fn point<T: Number>(worksheet: &Worksheet<T>, solution: &Solution<T>, format: Format) -> String {
+    if solution.status != Status::Optimal {
+        return String::new();
+    }
+    let values: Vec<_> = (solution.x.iter().enumerate())
+        .map(|(j, v)| format!("{} = {}", worksheet.name(j + 1, format), number(v, format)))
+        .collect();
+    format!("The optimal point is {}.\n\n", values.join(", "))
+}
+
+/// The sum of the non-zero terms, e.g. 7x1 - x2
+fn linear<T: Number>(coefs: &[T], name: &dyn Fn(usize) -> String, format: Format) -> String {
+    let mut out = String::new();
+    for (j, c) in coefs.iter().enumerate().filter(|(_, c)| !c.is_zero()) {
+        match (out.is_empty(), c.is_negative()) {
+            (true, true) => out += "-",
+            (false, true) => out += " - ",
+            (false, false) => out += " + ",
+            (true, false) => {}
+        }
+        if c.abs() != T::one() {
+            out += &number(&c.abs(), format);
+        }
+        out += &name(j);
+    }
+    if out.is_empty() {
+        out += "0";
+    }
+    out
+}
+
+/// A number as it's written in the format: LaTeX gets fractions and math mode
+fn number<T: Number>(x: &T, format: Format) -> String {
+    // The row operations leave negative zeros behind
+    let text = if x.is_zero() {
+        "0".to_string()
+    } else {
+        x.to_string()
+    };
+    match format {
+        Format::Markdown => text,
+        Format::Latex => {
+            let (sign, magnitude) = match text.strip_prefix('-') {
+                Some(magnitude) => ("-", magnitude),
+                None => ("", text.as_str()),
+            };
+            match magnitude.split_once('/') {
+                Some((numer, denom)) => format!("${}\\frac{{{}}}{{{}}}$", sign, numer, denom),
+                None => format!("${}$", text),
+            }
+        }
+    }
+}
+
+fn heading(format: Format, level: usize, title: &str) -> String {
+    match format {
+        Format::Latex => {
+            let command = ["section", "subsection", "subsubsection"][level - 1];
+            format!("\\{}*{{{}}}\n\n", command, title)
+        }
+        Format::Markdown => format!("{} {}\n\n", "#".repeat(level), title),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplex::{two_phase_simplex, Options, Rational, Sense};
+
+    fn rationals(values: &[i64]) -> Vec<Rational> {
+        values.iter().map(|&v| Rational::from_integer(v)).collect()
+    }
+
+    #[test]
+    fn worksheet_test_markdown() {
+        let constraints = na::DMatrix::from_row_slice(2, 2, &rationals(&[2, 3, 3, 2]));
+        let (req, obj) = (rationals(&[90, 120]), rationals(&[7, 5]));
+        let text = worked_solution(constraints, req, obj, &["x", "y"], Format::Markdown);
+        let expected = "# Worked solution
+
+## Problem
+
+```
+Maximize z = 7x + 5y
+2x + 3y <= 90
+3x + 2y <= 120
+x, y >= 0
+```
+
+## Phase II
+
+Row z holds the objective function.
+
+### Tableau 1
+
+| Basic | z | x | y | s1 | s2 | RHS |
+| :--- | ---: | ---: | ---: | ---: | ---: | ---: |
+| z | 1 | -7 | -5 | 0 | 0 | 0 |
+| s1 | 0 | 2 | 3 | 1 | 0 | 90 |
+| s2 | 0 | **3** | 2 | 0 | 1 | 120 |
+
+x enters the basis and s2 leaves it, the pivot element is 3.
+
+### Tableau 2
+
+| Basic | z | x | y | s1 | s2 | RHS |
+| :--- | ---: | ---: | ---: | ---: | ---: | ---: |
+| z | 1 | 0 | -1/3 | 0 | 7/3 | 280 |
+| s1 | 0 | 0 | **5/3** | 1 | -2/3 | 10 |
+| x | 0 | 1 | 2/3 | 0 | 1/3 | 40 |
+
+y enters the basis and s1 leaves it, the pivot element is 5/3.
+
+### Tableau 3
+
+| Basic | z | x | y | s1 | s2 | RHS |
+| :--- | ---: | ---: | ---: | ---: | ---: | ---: |
+| z | 1 | 0 | 0 | 1/5 | 11/5 | 282 |
+| y | 0 | 0 | 1 | 3/5 | -2/5 | 6 |
+| x | 0 | 1 | 0 | -2/5 | 3/5 | 36 |
+
+No variable can improve the objective function any further.
+
+## Outcome
+
+The tableau is optimal after 2 pivots: z = 282.
+
+The optimal point is x = 36, y = 6.
+
+";
+        assert_eq!(text, expected);
+    }
+
+    #[test]
+    fn worksheet_test_latex_two_phases() {
+        /*
+        Objective function = 7x + 5y
+        Constraints:
+        2x + 3y <= 90
+        -x - y <= -10, which needs an artificial variable
+        */
+        let constraints = na::DMatrix::from_row_slice(2, 2, &rationals(&[2, 3, -1, -1]));
+        let (req, obj) = (rationals(&[90, -10]), rationals(&[7, 5]));
+        let text = worked_solution(constraints, req, obj, &[], Format::Latex);
+        assert!(text.starts_with("\\documentclass{article}"));
+        assert!(text.ends_with("\\end{document}\n"));
+        assert!(text.contains("2x_{1} + 3x_{2} &\\le 90 \\\\\n-x_{1} - x_{2} &\\le -10"));
+        let phase1 = text.find("\\subsection*{Phase I}").unwrap();
+        let phase2 = text.find("\\subsection*{Phase II}").unwrap();
+        assert!(phase1 < phase2);
+        assert!(text[phase1..phase2]
+            .contains("Basic & $z$ & $x_{1}$ & $x_{2}$ & $s_{1}$ & $s_{2}$ & $a_{1}$ & RHS"));
+        assert!(!text[phase2..].contains("$a_{1}$"));
+        assert_eq!(
+            text.matches("\\begin{tabular}").count(),
+            text.matches("\\subsubsection*{Tableau").count()
+        );
+        // One highlighted element per pivot
+        assert_eq!(text.matches("\\boxed").count(), 2);
+        assert!(text.contains("$x_{1}$ enters the basis and $a_{1}$ leaves it"));
+        assert!(text.contains("The optimal point is $x_{1}$ = $45$, $x_{2}$ = $0$."));
+    }
+
+    #[test]
+    fn worksheet_test_observer() {
+        // Rendering the tables of another method, the row operations leave -0 behind
+        let constraints = na::DMatrix::from_row_slice(2, 2, &[1.0, 1.0, 1.0, -1.0]);
+        let worksheet = Worksheet::with_names(&["u", "v"]);
+        let options = Options {
+            observer: &worksheet,
+            ..Options::default()
+        };
+        let senses = vec![Sense::Ge, Sense::Eq];
+        let solution = two_phase_simplex(
+            constraints,
+            senses,
+            vec![2.0, 0.5],
+            vec![-1.0, -2.0],
+            &options,
+        );
+        assert_eq!(solution.status, Status::Optimal);
+        let snapshots = worksheet.snapshots();
+        assert_eq!(snapshots[0].phase, Phase::One);
+        assert_eq!(snapshots.last().unwrap().phase, Phase::Two);
+        assert_eq!(snapshots.last().unwrap().entering, None);
+        let text = worksheet.render(Format::Markdown);
+        assert!(!text.contains("-0 "));
+        assert!(text.contains("| Basic | z | u | v | s1 | s2 | a1 | RHS |"));
+        assert!(text.contains("The tableau is optimal after"));
+        assert!(text.contains("z = -2.75."), "{}", text);
+    }
+}
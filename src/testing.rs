@@ -0,0 +1,57 @@
+//! Fixtures shared by the tests of the network problems
+use crate::network::Network;
+use crate::simplex::{LinearProgram, Options, Sense, Status};
+use nalgebra as na;
+
+/// The minimum-cost flow as a linear program: what leaves a node minus what enters it
+/// is its supply
+pub fn linear_program(network: &Network<f64>) -> (Status, f64) {
+    let mut lp = LinearProgram::new();
+    let flows: Vec<_> = (network.arcs.iter().enumerate())
+        .map(|(k, arc)| lp.add_variable(&format!("f{}", k), 0.0, arc.capacity))
+        .collect();
+    for (i, b) in network.supplies.iter().enumerate() {
+        let terms: Vec<_> = (network.arcs.iter().zip(&flows))
+            .filter_map(|(arc, &f)| match () {
+                _ if arc.from.0 == i => Some((f, 1.0)),
+                _ if arc.to.0 == i => Some((f, -1.0)),
+                _ => None,
+            })
+            .collect();
+        lp.add_constraint(&format!("n{}", i), &terms, Sense::Eq, *b);
+    }
+    let objective: Vec<_> = (network.arcs.iter().zip(&flows))
+        .map(|(arc, &f)| (f, arc.cost))
+        .collect();
+    lp.minimize(&objective);
+    let solution = lp.solve(&Options::default());
+    (solution.status, solution.objective)
+}
+
+/// The cheapest way to ship from the sources to the destinations as a minimum-cost flow,
+/// a free dummy node takes what is left over on the larger side
+pub fn bipartite(supply: &[f64], demand: &[f64], costs: &na::DMatrix<f64>) -> f64 {
+    let mut network = Network::new();
+    let sources: Vec<_> = supply.iter().map(|&s| network.add_node(s)).collect();
+    let destinations: Vec<_> = demand.iter().map(|&d| network.add_node(-d)).collect();
+    for (i, &source) in sources.iter().enumerate() {
+        for (j, &destination) in destinations.iter().enumerate() {
+            network.add_arc(source, destination, costs[(i, j)], None);
+        }
+    }
+    let excess = supply.iter().sum::<f64>() - demand.iter().sum::<f64>();
+    if excess > 0.0 {
+        let dummy = network.add_node(-excess);
+        for &source in &sources {
+            network.add_arc(source, dummy, 0.0, None);
+        }
+    } else if excess < 0.0 {
+        let dummy = network.add_node(-excess);
+        for &destination in &destinations {
+            network.add_arc(dummy, destination, 0.0, None);
+        }
+    }
+    let (status, cost) = linear_program(&network);
+    assert_eq!(status, Status::Optimal);
+    cost
+}
@@ -0,0 +1,475 @@
+/// The transportation problem: the supply of m sources meets the demand of n destinations
+/// at the least cost, where shipping a unit from source i to destination j costs c_ij.
+/// A starting plan (northwest corner or Vogel's approximation) is improved by
+/// the modified distribution method (MODI): the potentials u_i + v_j = c_ij of the shipping
+/// routes price every other route, and the cheapest one enters along its stepping-stone cycle.
+/// The amounts are perturbed by an infinitesimal so that no pivot is degenerate,
+/// which keeps MODI from cycling.
+/// [https://en.wikipedia.org/wiki/Transportation_theory_(mathematics)]
+use crate::simplex::Number;
+use nalgebra as na;
+
+/// How to find the starting plan
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Start {
+    /// Fill the routes from the top left corner on, whatever they cost
+    NorthwestCorner,
+    /// Fill the cheapest route of the source or destination that would lose the most
+    /// by missing it, usually close to optimal
+    Vogel,
+}
+
+/// An optimal shipping plan
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transportation<T: Number> {
+    /// The total cost of shipping
+    pub cost: T,
+    /// The amount shipped from each source (row) to each destination (column)
+    pub flows: na::DMatrix<T>,
+    /// The potential of each source: u_i + v_j <= c_ij, with equality on every route in use.
+    /// Together with `v` they are the dual values of the (balanced) problem.
+    pub u: Vec<T>,
+    /// The potential of each destination
+    pub v: Vec<T>,
+    /// The number of stepping-stone pivots it took from the starting plan
+    pub pivots: usize,
+}
+
+/// Ships the supply to meet the demand at the least cost.
+/// If supply and demand don't balance, a dummy destination (or source) takes up
+/// the difference at no cost: the surplus stays at the sources, or the shortage
+/// is left unmet at the destinations.
+pub fn transportation<T: Number>(
+    supply: &[T],
+    demand: &[T],
+    costs: &na::DMatrix<T>,
+    start: Start,
+) -> Transportation<T> {
+    assert_eq!(costs.nrows(), supply.len(), "One row of costs per source");
+    assert_eq!(
+        costs.ncols(),
+        demand.len(),
+        "One column of costs per destination"
+    );
+    assert!(
+        supply.iter().chain(demand).all(|x| !x.is_negative()),
+        "Supply and demand can't be negative"
+    );
+    let (m, n) = (supply.len(), demand.len());
+    if m == 0 || n == 0 {
+        // Nothing ships: the supply stays at the sources, the demand is left unmet
+        return Transportation {
+            cost: T::zero(),
+            flows: na::DMatrix::zeros(m, n),
+            u: vec![T::zero(); m],
+            v: vec![T::zero(); n],
+            pivots: 0,
+        };
+    }
+    let total = |xs: &[T]| xs.iter().fold(T::zero(), |acc, x| acc + x);
+    let excess = total(supply) - total(demand);
+    let (mut supply, mut demand, mut costs) = (supply.to_vec(), demand.to_vec(), costs.clone());
+    if excess > T::eps() {
+        demand.push(excess);
+        costs = costs.insert_column(n, T::zero());
+    } else if excess < -T::eps() {
+        supply.push(-excess);
+        costs = costs.insert_row(m, T::zero());
+    }
+    // Every source gets ε more, the last destination takes them all
+    let sources = supply.len() as i64;
+    let supply = (supply.into_iter()).map(|x| Amount::new(x, 1)).collect();
+    let mut demand: Vec<_> = demand.into_iter().map(|x| Amount::new(x, 0)).collect();
+    demand.last_mut().expect("There are destinations").epsilons = sources;
+    let mut plan = match start {
+        Start::NorthwestCorner => Plan::northwest_corner(costs, supply, demand),
+        Start::Vogel => Plan::vogel(costs, supply, demand),
+    };
+    let mut pivots = 0;
+    let (u, v) = loop {
+        let (u, v) = plan.potentials();
+        match plan.entering(&u, &v) {
+            Some(cell) => plan.pivot(cell),
+            None => break (u, v),
+        }
+        pivots += 1;
+    };
+    let flows = plan.flows.slice((0, 0), (m, n)).into_owned();
+    let cost = flows.component_mul(&plan.costs.slice((0, 0), (m, n))).sum();
+    Transportation {
+        cost,
+        flows,
+        u: u[..m].to_vec(),
+        v: v[..n].to_vec(),
+        pivots,
+    }
+}
+
+/// An amount plus a multiple of an infinitesimal ε. Perturbed like this, no subset of
+/// the sources supplies exactly what a subset of the destinations demands (short of all
+/// of them), so every route of a plan ships something and every pivot lowers the cost.
+#[derive(Debug, Clone)]
+struct Amount<T: Number> {
+    value: T,
+    epsilons: i64,
+}
+
+impl<T: Number> Amount<T> {
+    fn new(value: T, epsilons: i64) -> Self {
+        Amount { value, epsilons }
+    }
+
+    fn less(&self, other: &Self) -> bool {
+        let difference = self.value.clone() - &other.value;
+        if difference.abs() > T::eps() {
+            difference.is_negative()
+        } else {
+            self.epsilons < other.epsilons
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value.abs() <= T::eps() && self.epsilons == 0
+    }
+
+    fn sub(&mut self, other: &Self) {
+        self.value -= &other.value;
+        self.epsilons -= other.epsilons;
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.value += &other.value;
+        self.epsilons += other.epsilons;
+    }
+}
+
+/// A basic plan of a balanced problem: m + n - 1 routes, none of them shipping nothing
+/// once perturbed
+struct Plan<T: Number> {
+    costs: na::DMatrix<T>,
+    flows: na::DMatrix<T>,
+    /// The multiples of ε the routes ship on top of `flows`
+    epsilons: na::DMatrix<i64>,
+    basis: Vec<(usize, usize)>,
+}
+
+impl<T: Number> Plan<T> {
+    fn new(costs: na::DMatrix<T>) -> Self {
+        let (m, n) = costs.shape();
+        Plan {
+            costs,
+            flows: na::DMatrix::zeros(m, n),
+            epsilons: na::DMatrix::zeros(m, n),
+            basis: Vec::with_capacity(m + n - 1),
+        }
+    }
+
+    fn amount(&self, cell: (usize, usize)) -> Amount<T> {
+        Amount::new(self.flows[cell].clone(), self.epsilons[cell])
+    }
+
+    fn set_amount(&mut self, cell: (usize, usize), amount: Amount<T>) {
+        self.flows[cell] = amount.value;
+        self.epsilons[cell] = amount.epsilons;
+    }
+
+    /// Ships as much as possible on the route. Only one of its source and destination
+    /// is done with, even if both are, so the routes of the plan stay connected.
+    fn ship(&mut self, (i, j): (usize, usize), supply: &mut [Amount<T>], demand: &mut [Amount<T>]) {
+        let amount = if supply[i].less(&demand[j]) {
+            supply[i].clone()
+        } else {
+            demand[j].clone()
+        };
+        supply[i].sub(&amount);
+        demand[j].sub(&amount);
+        self.set_amount((i, j), amount);
+        self.basis.push((i, j));
+    }
+
+    fn northwest_corner(
+        costs: na::DMatrix<T>,
+        mut supply: Vec<Amount<T>>,
+        mut demand: Vec<Amount<T>>,
+    ) -> Self {
+        let mut plan = Plan::new(costs);
+        let (m, n) = (supply.len(), demand.len());
+        let (mut i, mut j) = (0, 0);
+        while j < n {
+            plan.ship((i, j), &mut supply, &mut demand);
+            if supply[i].is_zero() && i + 1 < m {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        plan
+    }
+
+    fn vogel(
+        costs: na::DMatrix<T>,
+        mut supply: Vec<Amount<T>>,
+        mut demand: Vec<Amount<T>>,
+    ) -> Self {
+        let mut plan = Plan::new(costs);
+        let (m, n) = (supply.len(), demand.len());
+        let mut rows: Vec<usize> = (0..m).collect();
+        let mut cols: Vec<usize> = (0..n).collect();
+        while !cols.is_empty() {
+            // The difference between the two cheapest routes of a line, and the cheapest one
+            let penalty = |cells: Vec<(usize, usize)>| {
+                let cost = |&(i, j): &(usize, usize)| plan.costs[(i, j)].clone();
+                let mut sorted = cells.clone();
+                sorted.sort_by(|a, b| cost(a).partial_cmp(&cost(b)).unwrap());
+                let penalty = match sorted.get(1) {
+                    Some(second) => cost(second) - cost(&sorted[0]),
+                    None => cost(&sorted[0]),
+                };
+                (penalty, sorted[0])
+            };
+            let lines = (rows.iter())
+                .map(|&i| penalty(cols.iter().map(|&j| (i, j)).collect()))
+                .chain((cols.iter()).map(|&j| penalty(rows.iter().map(|&i| (i, j)).collect())));
+            let mut best: Option<(T, (usize, usize))> = None;
+            for (penalty, cell) in lines {
+                if best.as_ref().is_none_or(|(most, _)| penalty > *most) {
+                    best = Some((penalty, cell));
+                }
+            }
+            let (i, j) = best.unwrap().1;
+            plan.ship((i, j), &mut supply, &mut demand);
+            if supply[i].is_zero() && rows.len() > 1 {
+                rows.retain(|&row| row != i);
+            } else {
+                cols.retain(|&col| col != j);
+            }
+        }
+        plan
+    }
+
+    /// u_i + v_j = c_ij on every route of the plan, starting from u_0 = 0
+    fn potentials(&self) -> (Vec<T>, Vec<T>) {
+        let (m, n) = self.costs.shape();
+        let mut u = vec![None; m];
+        let mut v = vec![None; n];
+        u[0] = Some(T::zero());
+        let mut known = 1;
+        while known < m + n {
+            for &(i, j) in &self.basis {
+                let cost = self.costs[(i, j)].clone();
+                match (u[i].clone(), v[j].clone()) {
+                    (Some(ui), None) => v[j] = Some(cost - &ui),
+                    (None, Some(vj)) => u[i] = Some(cost - &vj),
+                    _ => continue,
+                }
+                known += 1;
+            }
+        }
+        let unwrap = |xs: Vec<Option<T>>| xs.into_iter().map(Option::unwrap).collect();
+        (unwrap(u), unwrap(v))
+    }
+
+    /// The route not in use with the most negative reduced cost c_ij - u_i - v_j
+    fn entering(&self, u: &[T], v: &[T]) -> Option<(usize, usize)> {
+        let mut best: Option<(T, (usize, usize))> = None;
+        for (i, ui) in u.iter().enumerate() {
+            for (j, vj) in v.iter().enumerate() {
+                let reduced = self.costs[(i, j)].clone() - ui - vj;
+                let lowest = best
+                    .as_ref()
+                    .map_or(-T::eps(), |(lowest, _)| lowest.clone());
+                if reduced < lowest && !self.basis.contains(&(i, j)) {
+                    best = Some((reduced, (i, j)));
+                }
+            }
+        }
+        best.map(|(_, cell)| cell)
+    }
+
+    /// The routes of the plan leading from the destination of the entering route back
+    /// to its source. They lose and gain flow in turns, starting with a loss.
+    fn cycle(&self, (i, j): (usize, usize)) -> Vec<usize> {
+        let m = self.costs.nrows();
+        // Sources are nodes 0..m, destinations m..m + n, routes are edges between them
+        let mut via = vec![None; m + self.costs.ncols()];
+        let mut queue = std::collections::VecDeque::new();
+        queue.push_back(i);
+        while let Some(node) = queue.pop_front() {
+            for (k, &(row, col)) in self.basis.iter().enumerate() {
+                let next = match node {
+                    _ if node == row => m + col,
+                    _ if node == m + col => row,
+                    _ => continue,
+                };
+                if next != i && via[next].is_none() {
+                    via[next] = Some((node, k));
+                    queue.push_back(next);
+                }
+            }
+        }
+        let mut path = Vec::new();
+        let mut node = m + j;
+        while node != i {
+            let (previous, k) = via[node].expect("The routes of a plan are connected");
+            path.push(k);
+            node = previous;
+        }
+        path
+    }
+
+    /// The entering route takes as much flow as the losing routes can give up,
+    /// the one of them emptied leaves the plan (the perturbation leaves a single one)
+    fn pivot(&mut self, entering: (usize, usize)) {
+        let path = self.cycle(entering);
+        let mut leaving = path[0];
+        for &k in path.iter().step_by(2) {
+            if self
+                .amount(self.basis[k])
+                .less(&self.amount(self.basis[leaving]))
+            {
+                leaving = k;
+            }
+        }
+        let amount = self.amount(self.basis[leaving]);
+        for (step, &k) in path.iter().enumerate() {
+            let mut flow = self.amount(self.basis[k]);
+            if step % 2 == 0 {
+                flow.sub(&amount);
+            } else {
+                flow.add(&amount);
+            }
+            self.set_amount(self.basis[k], flow);
+        }
+        self.set_amount(self.basis[leaving], Amount::new(T::zero(), 0));
+        self.set_amount(entering, amount);
+        self.basis[leaving] = entering;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simplex::Rational;
+    use crate::testing::bipartite;
+
+    /// The plan ships what it can, and the potentials prove it optimal
+    fn check(res: &Transportation<f64>, supply: &[f64], demand: &[f64], costs: &na::DMatrix<f64>) {
+        for (i, s) in supply.iter().enumerate() {
+            assert!(res.flows.row(i).sum() <= s + 1E-9);
+            for (j, _) in demand.iter().enumerate() {
+                assert!(res.flows[(i, j)] >= 0.0);
+                let reduced = costs[(i, j)] - res.u[i] - res.v[j];
+                assert!(reduced >= -1E-9, "{:?}", res);
+                if res.flows[(i, j)] > 1E-9 {
+                    assert!(reduced.abs() < 1E-9, "{:?}", res);
+                }
+            }
+        }
+        let shipped: f64 = res.flows.sum();
+        let needed = supply.iter().sum::<f64>().min(demand.iter().sum());
+        assert!((shipped - needed).abs() < 1E-9);
+        assert!((res.cost - res.flows.component_mul(costs).sum()).abs() < 1E-9);
+    }
+
+    #[test]
+    fn transportation_test_balanced() {
+        // Three plants supplying four warehouses
+        let supply = [300.0, 400.0, 500.0];
+        let demand = [250.0, 350.0, 400.0, 200.0];
+        let costs = na::DMatrix::from_row_slice(
+            3,
+            4,
+            &[3.0, 1.0, 7.0, 4.0, 2.0, 6.0, 5.0, 9.0, 8.0, 3.0, 3.0, 2.0],
+        );
+        let optimum = bipartite(&supply, &demand, &costs);
+        for &start in &[Start::NorthwestCorner, Start::Vogel] {
+            let res = transportation(&supply, &demand, &costs, start);
+            assert!((res.cost - optimum).abs() < 1E-9, "{:?}", res);
+            check(&res, &supply, &demand, &costs);
+            // With equalities on both sides the potentials price the whole plan
+            let dual = (supply.iter().zip(&res.u).map(|(s, u)| s * u).sum::<f64>())
+                + demand.iter().zip(&res.v).map(|(d, v)| d * v).sum::<f64>();
+            assert!((dual - res.cost).abs() < 1E-9);
+        }
+        // Vogel's start is closer to the optimum
+        let northwest = transportation(&supply, &demand, &costs, Start::NorthwestCorner);
+        let vogel = transportation(&supply, &demand, &costs, Start::Vogel);
+        assert!(vogel.pivots < northwest.pivots);
+    }
+
+    #[test]
+    fn transportation_test_unbalanced_and_degenerate() {
+        let costs =
+            na::DMatrix::from_row_slice(3, 3, &[4.0, 8.0, 8.0, 16.0, 24.0, 16.0, 8.0, 16.0, 24.0]);
+        let problems: Vec<(Vec<f64>, Vec<f64>)> = vec![
+            // Supply and demand run out at the same time on the way: degenerate plans
+            (vec![10.0, 20.0, 30.0], vec![10.0, 20.0, 30.0]),
+            (vec![76.0, 82.0, 77.0], vec![72.0, 102.0, 41.0]),
+            // More demand than supply
+            (vec![20.0, 30.0, 25.0], vec![40.0, 50.0, 30.0]),
+        ];
+        for (supply, demand) in problems {
+            let optimum = bipartite(&supply, &demand, &costs);
+            for &start in &[Start::NorthwestCorner, Start::Vogel] {
+                let res = transportation(&supply, &demand, &costs, start);
+                assert!((res.cost - optimum).abs() < 1E-9, "{:?}", res);
+                check(&res, &supply, &demand, &costs);
+            }
+        }
+
+        // No sources or no destinations: there is nothing to ship
+        let empty: [(Vec<f64>, Vec<f64>); 3] = [
+            (vec![], vec![5.0, 3.0]),
+            (vec![4.0], vec![]),
+            (vec![], vec![]),
+        ];
+        for (supply, demand) in &empty {
+            let costs = na::DMatrix::zeros(supply.len(), demand.len());
+            for &start in &[Start::NorthwestCorner, Start::Vogel] {
+                let res = transportation(supply, demand, &costs, start);
+                assert_eq!(res.cost, 0.0);
+                assert_eq!(res.flows.shape(), (supply.len(), demand.len()));
+                assert_eq!((res.u.len(), res.v.len()), (supply.len(), demand.len()));
+            }
+        }
+    }
+
+    #[test]
+    fn transportation_test_perturbed_plans() {
+        // Each source runs out together with a destination, so the plans are degenerate
+        // unless perturbed
+        let costs = na::DMatrix::from_fn(4, 4, |i, j| ((3 * i + 5 * j) % 7) as f64);
+        let supply = [10.0, 10.0, 10.0, 10.0];
+        let sources: Vec<_> = supply.iter().map(|&x| Amount::new(x, 1)).collect();
+        let mut destinations: Vec<_> = supply.iter().map(|&x| Amount::new(x, 0)).collect();
+        destinations[3].epsilons = 4;
+        for plan in [
+            Plan::northwest_corner(costs.clone(), sources.clone(), destinations.clone()),
+            Plan::vogel(costs.clone(), sources.clone(), destinations.clone()),
+        ] {
+            assert_eq!(plan.basis.len(), 7);
+            for &cell in &plan.basis {
+                assert!(Amount::new(0.0, 0).less(&plan.amount(cell)), "{:?}", cell);
+            }
+        }
+        let res = transportation(&supply, &supply, &costs, Start::NorthwestCorner);
+        assert!((res.cost - bipartite(&supply, &supply, &costs)).abs() < 1E-9);
+    }
+
+    #[test]
+    fn transportation_test_rational() {
+        let r = Rational::from_integer;
+        let supply = [r(7), r(9), r(18)];
+        let demand = [r(5), r(8), r(7), r(14)];
+        let costs = na::DMatrix::from_row_slice(
+            3,
+            4,
+            &[19, 30, 50, 10, 70, 30, 40, 60, 40, 8, 70, 20].map(r),
+        );
+        for &start in &[Start::NorthwestCorner, Start::Vogel] {
+            let res = transportation(&supply, &demand, &costs, start);
+            assert_eq!(res.cost, r(743));
+            assert_eq!(res.flows.sum(), r(34));
+        }
+    }
+}